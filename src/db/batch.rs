@@ -0,0 +1,151 @@
+//! Batch module - provides the ConnectionBatch handle used inside `Database.batch()`
+
+use crate::db::convert_params_container;
+use crate::db::row::{
+    place_in_row, row_to_object, NativeKey, ResultValue, RowWithNatives, ValueEncoding,
+};
+use crate::db::statement::with_inserted_rowid;
+use crate::error::{sql_snippet, to_napi_error};
+use crate::models::QueryResult;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// ConnectionBatch struct - runs statements on a connection that `Database.batch()` holds locked
+/// Only usable while the batch callback runs; afterwards every method throws
+#[napi]
+pub struct ConnectionBatch {
+    conn: *const Connection,
+    open: Arc<AtomicBool>,
+    encoding: ValueEncoding,
+}
+
+impl ConnectionBatch {
+    /// Create a handle to a connection whose lock the caller holds until `open` is cleared
+    pub(crate) fn new(conn: &Connection, open: Arc<AtomicBool>, encoding: ValueEncoding) -> Self {
+        ConnectionBatch {
+            conn,
+            open,
+            encoding,
+        }
+    }
+
+    /// The locked connection, or an error once the batch callback has returned
+    fn conn(&self, operation: &str) -> Result<&Connection> {
+        if !self.open.load(Ordering::SeqCst) {
+            return Err(Error::from_reason(format!(
+                "{}: the batch has ended; only use the handle inside the batch() callback",
+                operation
+            )));
+        }
+        // Safe while `open` is set: Database.batch() holds the connection's lock until it clears it
+        Ok(unsafe { &*self.conn })
+    }
+
+    /// Run `sql` with `params` and convert up to `limit` rows to objects
+    fn query_rows(
+        &self,
+        env: &Env,
+        sql: &str,
+        params: Option<Unknown>,
+        operation: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RowWithNatives>> {
+        let conn = self.conn(operation)?;
+        let mut stmt = conn.prepare(sql).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Prepare failed: {}", sql_snippet(sql))),
+            )
+        })?;
+        let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        convert_params_container(env, params)?
+            .bind(&mut stmt)
+            .map_err(to_napi_error)?;
+        let mut rows = stmt.raw_query();
+        let mut results = Vec::new();
+        while limit != Some(results.len()) {
+            let Some(row) = rows.next().map_err(to_napi_error)? else {
+                break;
+            };
+            let mut cells = Vec::new();
+            let map = row_to_object(row, &column_names, self.encoding, &mut cells)
+                .map_err(to_napi_error)?;
+            results.push((map, cells));
+        }
+        Ok(results)
+    }
+}
+
+#[napi]
+impl ConnectionBatch {
+    /// Execute one or more SQL statements without parameters
+    #[napi]
+    pub fn exec(&self, sql: String) -> Result<QueryResult> {
+        let conn = self.conn("ConnectionBatch.exec")?;
+        let changes_before = conn.total_changes();
+        let ((), last_insert_rowid) =
+            with_inserted_rowid(conn, &sql, || conn.execute_batch(&sql)).map_err(to_napi_error)?;
+        Ok(QueryResult {
+            changes: conn.changes() as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Execute a statement and return metadata (changes, last_insert_rowid)
+    #[napi]
+    pub fn run(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<QueryResult> {
+        let conn = self.conn("ConnectionBatch.run")?;
+        let changes_before = conn.total_changes();
+        let params_container = convert_params_container(&env, params)?;
+        let (changes, last_insert_rowid) =
+            with_inserted_rowid(conn, &sql, || params_container.execute(conn, &sql)).map_err(
+                |e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Run failed: {}", sql_snippet(&sql))),
+                    )
+                },
+            )?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Execute a query and return all rows as objects
+    #[napi(ts_return_type = "any[]")]
+    pub fn all(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<ResultValue> {
+        let results = self.query_rows(&env, &sql, params, "ConnectionBatch.all", None)?;
+        let mut natives = Vec::new();
+        let rows: Vec<serde_json::Value> = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, (map, mut cells))| {
+                place_in_row(&mut cells, NativeKey::Index(index));
+                natives.append(&mut cells);
+                serde_json::Value::Object(map)
+            })
+            .collect();
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Array(rows),
+            natives,
+        ))
+    }
+
+    /// Execute a query and return its first row as an object, or null if there are none
+    #[napi(ts_return_type = "any")]
+    pub fn get(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<ResultValue> {
+        let mut results = self.query_rows(&env, &sql, params, "ConnectionBatch.get", Some(1))?;
+        Ok(match results.pop() {
+            Some((map, natives)) => {
+                ResultValue::with_natives(serde_json::Value::Object(map), natives)
+            }
+            None => serde_json::Value::Null.into(),
+        })
+    }
+}
@@ -1,31 +1,147 @@
 //! Database module - provides the Database struct for SQLite connections
 
 use crate::db::convert_params_container;
-use crate::error::to_napi_error;
-use crate::models::{Migration, QueryResult};
+use crate::db::hooks::{
+    authorizer, AuthorizerArgs, ConnectionTrace, JsCallback, ProfileFn, SlowQueryHook,
+    SlowQueryLog, SlowQueryTimer,
+};
+use crate::db::params::{json_value_to_param, object_to_params};
+use crate::db::row::{
+    place_in_row, row_to_object, sqlite_real_text, BlobEncoding, NativeKey, ResultValue,
+    StatementSummary, TextErrorMode, ValueEncoding,
+};
+use crate::db::statement::{top_level_keywords, with_inserted_rowid};
+use crate::db::{sqlite_to_json, Param};
+use crate::error::{lock_connection, lock_with_context, sql_snippet, to_napi_error};
+use crate::models::{
+    ImportResult, Migration, MigrationEntry, QueryResult, RunManyResult, SlowQuery,
+    StatementProfile,
+};
+use crate::schema::{parse_migration_filename, quote_identifier, validate_identifier};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use rusqlite::limits::Limit;
 use rusqlite::serialize::OwnedData;
 use rusqlite::Connection;
 use rusqlite::OpenFlags;
+use rusqlite::OptionalExtension;
 use rusqlite::ToSql;
 
 use std::collections::HashMap;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicU64};
 use std::sync::{Arc, Mutex};
 
+use super::ConnectionBatch;
 use super::Statement;
 use super::Transaction;
 
+/// One `sqlite_stat1` row: table, index and the leading numbers of its stat column
+type Stat1Row = (String, Option<String>, Vec<f64>);
+
 /// Database options for connection configuration
 #[napi(object)]
 pub struct DatabaseOptions {
-    /// Open database in read-only mode
+    /// Open database in read-only mode; can't be combined with `readwrite` or `create` set to true
     pub readonly: Option<bool>,
-    /// Create database if it doesn't exist (default: true)
+    /// Create database if it doesn't exist (default: true, ignored when read-only)
     pub create: Option<bool>,
-    /// Open database in read-write mode (default: true)
+    /// Open database in read-write mode (default: true); false requires `readonly`
     pub readwrite: Option<bool>,
+    /// How blob columns are returned: "base64" (default), "hex" or "buffer"
+    #[napi(ts_type = "'base64' | 'hex' | 'buffer'")]
+    pub blob_encoding: Option<String>,
+    /// Name of the SQLite VFS to open the database with (default: the platform VFS)
+    pub vfs: Option<String>,
+    /// Let SQLite spill dirty pages to disk mid-transaction when the cache is full (default: true)
+    pub cache_spill: Option<bool>,
+    /// Cap the database at this many pages (`PRAGMA max_page_count`); writes beyond it fail
+    /// with SQLITE_FULL (default: SQLite's limit)
+    pub max_page_count: Option<u32>,
+    /// Apply the default journal_mode, synchronous, cache_size, temp_store, mmap_size and
+    /// foreign_keys pragmas when opening (default: true)
+    pub apply_default_pragmas: Option<bool>,
+    /// Throw instead of buffering more than this many rows in all(), values() and iter()
+    /// (default: unlimited)
+    pub max_rows: Option<u32>,
+    /// Keep the `-wal` and `-shm` files when the last connection closes (default: false)
+    pub persistent_wal: Option<bool>,
+    /// Open a private on-disk temporary database, deleted when the connection closes;
+    /// the path must be "" (default: false)
+    pub temporary: Option<bool>,
+}
+
+/// Options for registering a custom SQL function
+#[napi(object)]
+pub struct FunctionOptions {
+    /// The function always returns the same result for the same arguments, letting SQLite
+    /// cache calls and use it in indexes (default: false)
+    pub deterministic: Option<bool>,
+    /// Only allow calls from top-level SQL, not from triggers, views or schema (default: false)
+    pub direct_only: Option<bool>,
+    /// Number of arguments the function takes, or -1 for any number (default: -1)
+    pub arg_count: Option<i32>,
+}
+
+/// Options for the update/delete helpers
+#[napi(object)]
+pub struct UpdateOptions {
+    /// Allow an empty `where` object, affecting every row in the table (default: false)
+    pub allow_full_table_update: Option<bool>,
+}
+
+/// Options for the upsert helper
+#[napi(object)]
+pub struct UpsertOptions {
+    /// Use `ON CONFLICT DO NOTHING` instead of updating the existing row (default: false)
+    pub do_nothing: Option<bool>,
+}
+
+/// Options for warming the page cache
+#[napi(object)]
+pub struct PreloadOptions {
+    /// Only warm these tables (and their indexes) instead of the whole database
+    pub warm_tables: Option<Vec<String>>,
+}
+
+/// Options for closing the database
+#[napi(object)]
+pub struct CloseOptions {
+    /// Roll back an open transaction instead of refusing to close (default: false)
+    pub force: Option<bool>,
+}
+
+/// Result of closing the database
+#[napi(object)]
+pub struct CloseResult {
+    /// Whether `force` rolled back an open transaction before closing
+    pub rolled_back: bool,
+}
+
+/// Options for counting table rows
+#[napi(object)]
+pub struct RowCountOptions {
+    /// Run `COUNT(*)` on every table instead of reading ANALYZE estimates (default: false)
+    pub precise: Option<bool>,
+}
+
+/// Options for importing newline-delimited JSON
+#[napi(object)]
+pub struct ImportOptions {
+    /// Commit after every this many rows instead of once at the end
+    pub batch_size: Option<u32>,
+    /// Skip rows with keys that aren't table columns instead of failing (default: false)
+    pub skip_invalid_rows: Option<bool>,
+}
+
+/// Options for retrying a statement that hits a locked database
+#[napi(object)]
+pub struct RetryOptions {
+    /// How many times to retry after the first attempt (default: 5)
+    pub max_retries: Option<u32>,
+    /// Delay before the first retry in milliseconds, doubled after each retry (default: 10)
+    pub backoff_ms: Option<u32>,
+    /// Longest delay between two retries in milliseconds (default: 1000)
+    pub max_backoff_ms: Option<u32>,
 }
 
 /// Database connection struct - represents an SQLite database connection
@@ -35,13 +151,82 @@ pub struct Database {
     in_transaction: Arc<AtomicBool>,
     closed: Arc<AtomicBool>,
     filename: String,
-    /// Stored custom SQL function names
-    functions: Arc<Mutex<HashMap<String, bool>>>,
+    /// Stored custom SQL function names and their argument counts
+    functions: Arc<Mutex<HashMap<String, i32>>>,
     /// Stored custom collation names
     collations: Arc<Mutex<HashMap<String, bool>>>,
+    /// Counter used to name the savepoints of nested transaction() calls
+    savepoint_counter: Arc<AtomicU64>,
+    /// How statements created by this connection return blob columns
+    blob_encoding: BlobEncoding,
+    /// How statements handle TEXT values that aren't valid UTF-8
+    text_error_mode: Arc<Mutex<TextErrorMode>>,
+    /// Callback for statements slower than a threshold, shared with statements
+    slow_query_log: SlowQueryHook,
+    /// Most rows a statement may buffer for one call
+    max_rows: Option<u32>,
+    /// Callbacks installed by `setTrace` and `setProfiler`, unregistered when dropped
+    trace: Mutex<ConnectionTrace>,
 }
 
 impl Database {
+    /// Wrap an open connection with fresh per-connection state
+    fn from_connection(
+        conn: Connection,
+        filename: String,
+        blob_encoding: BlobEncoding,
+        text_error_mode: TextErrorMode,
+        max_rows: Option<u32>,
+    ) -> Self {
+        let conn = Arc::new(Mutex::new(conn));
+        Database {
+            trace: Mutex::new(ConnectionTrace::new(conn.clone())),
+            conn,
+            in_transaction: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
+            filename,
+            functions: Arc::new(Mutex::new(HashMap::new())),
+            collations: Arc::new(Mutex::new(HashMap::new())),
+            savepoint_counter: Arc::new(AtomicU64::new(0)),
+            blob_encoding,
+            text_error_mode: Arc::new(Mutex::new(text_error_mode)),
+            slow_query_log: Arc::new(Mutex::new(None)),
+            max_rows,
+        }
+    }
+
+    /// Resolve the open options into one of three modes, rejecting contradictory combinations:
+    /// read-only (`readonly`), read-write (`create: false`) or read-write-create (the default)
+    fn open_flags(
+        readonly: Option<bool>,
+        readwrite: Option<bool>,
+        create: Option<bool>,
+    ) -> Result<OpenFlags> {
+        if readonly.unwrap_or(false) {
+            if readwrite == Some(true) {
+                return Err(Error::from_reason(
+                    "Invalid options: readonly and readwrite can't both be true",
+                ));
+            }
+            if create == Some(true) {
+                return Err(Error::from_reason(
+                    "Invalid options: create needs write access, so it can't be combined with readonly",
+                ));
+            }
+            return Ok(OpenFlags::SQLITE_OPEN_READ_ONLY);
+        }
+        if readwrite == Some(false) {
+            return Err(Error::from_reason(
+                "Invalid options: readwrite is false but readonly isn't set; pass readonly: true to open without write access",
+            ));
+        }
+        let mut flags = OpenFlags::SQLITE_OPEN_READ_WRITE;
+        if create.unwrap_or(true) {
+            flags.insert(OpenFlags::SQLITE_OPEN_CREATE);
+        }
+        Ok(flags)
+    }
+
     /// Extract table name from CREATE TABLE SQL
     fn extract_table_name(sql: &str) -> Result<String> {
         let sql_lower = sql.to_lowercase();
@@ -99,53 +284,595 @@ impl Database {
             Err(Error::from_reason("Invalid CREATE TABLE SQL"))
         }
     }
+
+    /// Build an INSERT statement for the given table and column values
+    fn build_insert_sql(table: &str, columns: &[(String, Param)]) -> Result<String> {
+        validate_identifier(table)?;
+        if columns.is_empty() {
+            return Ok(format!(
+                "INSERT INTO {} DEFAULT VALUES",
+                quote_identifier(table)
+            ));
+        }
+        let mut names = Vec::with_capacity(columns.len());
+        for (name, _) in columns {
+            validate_identifier(name)?;
+            names.push(quote_identifier(name));
+        }
+        let placeholders = vec!["?"; columns.len()].join(", ");
+        Ok(format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_identifier(table),
+            names.join(", "),
+            placeholders
+        ))
+    }
+
+    /// Update the `in_transaction` flag from SQLite's autocommit state and return it
+    fn sync_transaction_flag(&self, conn: &Connection) -> bool {
+        let active = !conn.is_autocommit();
+        self.in_transaction
+            .store(active, std::sync::atomic::Ordering::SeqCst);
+        active
+    }
+
+    /// Begin a top-level transaction in the given mode; unknown modes are deferred
+    /// "concurrent" needs a SQLite build with `BEGIN CONCURRENT`, which the bundled one lacks
+    fn begin_transaction(conn: &Connection, mode: Option<&str>) -> Result<()> {
+        let mode_str = match mode {
+            Some("immediate") => "IMMEDIATE",
+            Some("exclusive") => "EXCLUSIVE",
+            Some("concurrent") => "CONCURRENT",
+            _ => "DEFERRED",
+        };
+        conn.execute(&format!("BEGIN {}", mode_str), [])
+            .map_err(|e| {
+                if mode_str == "CONCURRENT" {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some("BEGIN CONCURRENT is not supported by this SQLite build"),
+                    )
+                } else {
+                    to_napi_error(e)
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Write every row produced by `stmt` to `out` as one JSON object per line
+    fn write_ndjson_rows<W: std::io::Write>(
+        stmt: &mut rusqlite::Statement<'_>,
+        out: &mut W,
+    ) -> rusqlite::Result<u32> {
+        let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let mut rows = stmt.raw_query();
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let mut map = serde_json::Map::new();
+            for (i, name) in column_names.iter().enumerate() {
+                map.insert(name.clone(), sqlite_to_json(row, i)?);
+            }
+            serde_json::to_writer(&mut *out, &map)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            out.write_all(b"\n")
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Collect the single text column of a pragma table-valued function query
+    fn pragma_names(&self, sql: &str, operation: &str) -> Result<Vec<String>> {
+        let conn = lock_connection(&self.conn, operation)?;
+        let mut stmt = conn.prepare(sql).map_err(to_napi_error)?;
+        let names = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(to_napi_error)?
+            .collect::<rusqlite::Result<Vec<String>>>()
+            .map_err(to_napi_error)?;
+        Ok(names)
+    }
+
+    /// Run a prepared statement without parameters and return its rows as objects, encoding
+    /// blobs and invalid text the way `encoding` asks, like `Statement.all`
+    fn encoded_rows(
+        stmt: &mut rusqlite::Statement<'_>,
+        encoding: ValueEncoding,
+    ) -> rusqlite::Result<ResultValue> {
+        let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let mut rows = stmt.query([])?;
+        let mut collected = Vec::new();
+        let mut natives = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut cells = Vec::new();
+            let map = row_to_object(row, &column_names, encoding, &mut cells)?;
+            place_in_row(&mut cells, NativeKey::Index(collected.len()));
+            natives.append(&mut cells);
+            collected.push(serde_json::Value::Object(map));
+        }
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Array(collected),
+            natives,
+        ))
+    }
+
+    /// How rows read through this connection return blobs and invalid text
+    fn value_encoding(&self, operation: &str) -> ValueEncoding {
+        ValueEncoding {
+            blobs: self.blob_encoding,
+            invalid_text: *lock_with_context(&self.text_error_mode, operation),
+        }
+    }
+
+    /// Read `sqlite_stat1` as (table, index, numbers) rows; empty until ANALYZE has run
+    fn stat1_rows(conn: &Connection) -> rusqlite::Result<Vec<Stat1Row>> {
+        let has_stats: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_stat1'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_stats {
+            return Ok(Vec::new());
+        }
+        let mut stmt = conn.prepare("SELECT tbl, idx, stat FROM sqlite_stat1")?;
+        let rows = stmt.query_map([], |row| {
+            let stat: String = row.get(2)?;
+            // Trailing keywords such as "unordered" end the numeric part
+            let numbers = stat
+                .split_whitespace()
+                .map_while(|n| n.parse::<f64>().ok())
+                .collect();
+            Ok((row.get(0)?, row.get(1)?, numbers))
+        })?;
+        rows.collect()
+    }
+
+    /// Find the table a query-plan loop over `name` reads, resolving aliases from `sql`
+    fn plan_loop_table(
+        conn: &Connection,
+        sql: &str,
+        name: &str,
+    ) -> rusqlite::Result<Option<String>> {
+        let lookup = |candidate: &str| -> rusqlite::Result<Option<String>> {
+            conn.query_row(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?1 COLLATE NOCASE",
+                [candidate],
+                |row| row.get(0),
+            )
+            .optional()
+        };
+        if let Some(table) = lookup(name)? {
+            return Ok(Some(table));
+        }
+        // "FROM items i" or "JOIN items AS i": the table is the word before the alias
+        let words: Vec<&str> = sql
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | ';'))
+            .filter(|w| !w.is_empty())
+            .map(|w| w.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']')))
+            .collect();
+        for (i, word) in words.iter().enumerate().skip(1) {
+            if !word.eq_ignore_ascii_case(name) {
+                continue;
+            }
+            let before = if words[i - 1].eq_ignore_ascii_case("AS") && i >= 2 {
+                words[i - 2]
+            } else {
+                words[i - 1]
+            };
+            if let Some(table) = lookup(before)? {
+                return Ok(Some(table));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Estimate the rows one top-level EXPLAIN QUERY PLAN loop produces per outer row
+    /// Returns None for plan shapes the statistics can't describe (subqueries, virtual tables, ...)
+    fn plan_loop_estimate(
+        conn: &Connection,
+        sql: &str,
+        stats: &[Stat1Row],
+        detail: &str,
+    ) -> rusqlite::Result<Option<f64>> {
+        if detail.starts_with("USE TEMP B-TREE") || detail == "SCAN CONSTANT ROW" {
+            return Ok(Some(1.0));
+        }
+        let Some((kind @ ("SCAN" | "SEARCH"), rest)) = detail.split_once(' ') else {
+            return Ok(None);
+        };
+        if rest.starts_with('(') || rest.contains("VIRTUAL TABLE") || rest.contains("AUTOMATIC") {
+            return Ok(None);
+        }
+        let name = rest.split_whitespace().next().unwrap_or_default();
+        let Some(table) = Self::plan_loop_table(conn, sql, name)? else {
+            return Ok(None);
+        };
+
+        // Table size: the largest stat1 row count, else the highest rowid
+        let analyzed = stats
+            .iter()
+            .filter(|(tbl, _, _)| tbl.eq_ignore_ascii_case(&table))
+            .filter_map(|(_, _, numbers)| numbers.first().copied())
+            .reduce(f64::max);
+        let table_rows = match analyzed {
+            Some(rows) => rows,
+            None => {
+                let max_rowid = conn.query_row(
+                    &format!("SELECT MAX(_rowid_) FROM {}", quote_identifier(&table)),
+                    [],
+                    |row| row.get::<_, Option<i64>>(0),
+                );
+                match max_rowid {
+                    Ok(rows) => rows.unwrap_or(0) as f64,
+                    // WITHOUT ROWID tables have no _rowid_ to go by
+                    Err(_) => return Ok(None),
+                }
+            }
+        };
+        if kind == "SCAN" {
+            return Ok(Some(table_rows));
+        }
+
+        // "SEARCH t USING INDEX idx (a=? AND b>?)": equality terms narrow to the index's
+        // average rows per key prefix, each range bound keeps a quarter like the planner assumes
+        let terms: Vec<&str> = rest
+            .rsplit_once(" (")
+            .map(|(_, terms)| terms.trim_end_matches(')').split(" AND ").collect())
+            .unwrap_or_default();
+        let equalities = terms.iter().filter(|t| t.ends_with("=?")).count();
+        let bounds = terms.iter().filter(|t| t.contains(['<', '>'])).count() as i32;
+        let mut rows = if equalities == 0 {
+            table_rows
+        } else if rest.contains("USING INTEGER PRIMARY KEY") {
+            1.0
+        } else {
+            let index = if rest.contains("USING PRIMARY KEY") {
+                conn.query_row(
+                    "SELECT name FROM pragma_index_list(?1) WHERE origin = 'pk'",
+                    [&table],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?
+            } else {
+                rest.split_once("INDEX ")
+                    .and_then(|(_, after)| after.split_whitespace().next())
+                    .map(str::to_string)
+            };
+            let per_key = index.and_then(|index| {
+                stats
+                    .iter()
+                    .find(|(_, idx, _)| idx.as_deref() == Some(index.as_str()))
+                    .and_then(|(_, _, numbers)| numbers.get(equalities).copied())
+            });
+            // Without statistics SQLite guesses ten rows per key
+            per_key.unwrap_or(10.0).min(table_rows)
+        };
+        rows /= 4f64.powi(bounds.min(3));
+        Ok(Some(rows))
+    }
+
+    /// Read the single argument of a hash function as bytes (NULL stays NULL)
+    /// Numbers are hashed as the text SQLite converts them to, so `sha256(x)` matches
+    /// `sha256(CAST(x AS TEXT))`
+    fn hash_argument(ctx: &rusqlite::functions::Context) -> rusqlite::Result<Option<Vec<u8>>> {
+        use rusqlite::types::ValueRef;
+        Ok(match ctx.get_raw(0) {
+            ValueRef::Null => None,
+            ValueRef::Integer(i) => Some(i.to_string().into_bytes()),
+            ValueRef::Real(f) => Some(sqlite_real_text(f).into_bytes()),
+            ValueRef::Text(t) | ValueRef::Blob(t) => Some(t.to_vec()),
+        })
+    }
+
+    /// Map a limit category name such as "SQL_LENGTH" (optionally prefixed with "SQLITE_LIMIT_") to a `Limit`
+    fn parse_limit(category: &str) -> Result<Limit> {
+        let upper = category.to_ascii_uppercase();
+        let name = upper.strip_prefix("SQLITE_LIMIT_").unwrap_or(&upper);
+        match name {
+            "LENGTH" => Ok(Limit::SQLITE_LIMIT_LENGTH),
+            "SQL_LENGTH" => Ok(Limit::SQLITE_LIMIT_SQL_LENGTH),
+            "COLUMN" => Ok(Limit::SQLITE_LIMIT_COLUMN),
+            "EXPR_DEPTH" => Ok(Limit::SQLITE_LIMIT_EXPR_DEPTH),
+            "COMPOUND_SELECT" => Ok(Limit::SQLITE_LIMIT_COMPOUND_SELECT),
+            "VDBE_OP" => Ok(Limit::SQLITE_LIMIT_VDBE_OP),
+            "FUNCTION_ARG" => Ok(Limit::SQLITE_LIMIT_FUNCTION_ARG),
+            "ATTACHED" => Ok(Limit::SQLITE_LIMIT_ATTACHED),
+            "LIKE_PATTERN_LENGTH" => Ok(Limit::SQLITE_LIMIT_LIKE_PATTERN_LENGTH),
+            "VARIABLE_NUMBER" => Ok(Limit::SQLITE_LIMIT_VARIABLE_NUMBER),
+            "TRIGGER_DEPTH" => Ok(Limit::SQLITE_LIMIT_TRIGGER_DEPTH),
+            "WORKER_THREADS" => Ok(Limit::SQLITE_LIMIT_WORKER_THREADS),
+            _ => Err(Error::from_reason(format!(
+                "Unknown limit category '{}'. Expected one of: LENGTH, SQL_LENGTH, COLUMN, EXPR_DEPTH, COMPOUND_SELECT, VDBE_OP, FUNCTION_ARG, ATTACHED, LIKE_PATTERN_LENGTH, VARIABLE_NUMBER, TRIGGER_DEPTH, WORKER_THREADS",
+                category
+            ))),
+        }
+    }
+
+    /// Read the current value of a `sqlite3_db_status` counter
+    fn db_status(conn: &Connection, op: i32) -> Result<i64> {
+        let mut current = 0;
+        let mut highwater = 0;
+        let rc = unsafe {
+            rusqlite::ffi::sqlite3_db_status(conn.handle(), op, &mut current, &mut highwater, 0)
+        };
+        if rc != rusqlite::ffi::SQLITE_OK {
+            return Err(to_napi_error(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rc),
+                None,
+            )));
+        }
+        Ok(current as i64)
+    }
+
+    /// Create a Statement for `sql` sharing this connection's settings
+    fn statement(&self, sql: String, cached: bool) -> Statement {
+        Statement::new(
+            sql,
+            self.conn.clone(),
+            self.blob_encoding,
+            self.text_error_mode.clone(),
+            self.slow_query_log.clone(),
+            self.max_rows,
+            cached,
+        )
+    }
+
+    /// Run `UPDATE table SET column = <expression> WHERE rowid = ?` for the JSON helpers
+    /// `{column}` in `expression` is replaced by the quoted column; the rowid is the last param
+    fn update_json(
+        &self,
+        operation: &str,
+        table: &str,
+        column: &str,
+        expression: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<QueryResult> {
+        validate_identifier(table)?;
+        validate_identifier(column)?;
+        let column_sql = quote_identifier(column);
+        let sql = format!(
+            "UPDATE {} SET {} = {} WHERE rowid = ?",
+            quote_identifier(table),
+            column_sql,
+            expression.replace("{column}", &column_sql)
+        );
+        let conn = lock_connection(&self.conn, operation)?;
+        if conn
+            .query_row("SELECT json_valid('{}')", [], |_| Ok(()))
+            .is_err()
+        {
+            return Err(Error::from_reason(format!(
+                "{}: JSON functions are not available in this SQLite build",
+                operation
+            )));
+        }
+        let changes_before = conn.total_changes();
+        let changes = conn.execute(&sql, params).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("JSON update of {}.{} failed", table, column)),
+            )
+        })?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid: None,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Copy `bytes` into memory from `sqlite3_malloc64`, which `sqlite3_deserialize` takes over
+    /// With `SQLITE_DESERIALIZE_FREEONCLOSE` SQLite frees it on close, or right away on failure
+    fn sqlite_owned_copy(bytes: &[u8]) -> Result<OwnedData> {
+        let len = bytes.len();
+        let sqlite_ptr = unsafe { rusqlite::ffi::sqlite3_malloc64(len as u64) as *mut u8 };
+        let sqlite_ptr = std::ptr::NonNull::new(sqlite_ptr)
+            .ok_or_else(|| Error::from_reason("Failed to allocate memory"))?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), sqlite_ptr.as_ptr(), len);
+            let data = std::slice::from_raw_parts_mut(sqlite_ptr.as_ptr(), len);
+            // Header bytes 18-19 are 2 in WAL mode, which an in-memory database can't open;
+            // 1 switches the copy to the rollback journal without changing any page
+            if len >= 100 && data[18] == 2 && data[19] == 2 {
+                data[18] = 1;
+                data[19] = 1;
+            }
+            Ok(OwnedData::from_raw_nonnull(sqlite_ptr, len))
+        }
+    }
+
+    /// Keep or remove the WAL files on close (`SQLITE_FCNTL_PERSIST_WAL`)
+    /// VFSes without the file control, such as in-memory databases, are left unchanged
+    fn set_persistent_wal(conn: &Connection, enabled: bool) -> Result<()> {
+        let mut value: std::os::raw::c_int = enabled.into();
+        let rc = unsafe {
+            rusqlite::ffi::sqlite3_file_control(
+                conn.handle(),
+                c"main".as_ptr(),
+                rusqlite::ffi::SQLITE_FCNTL_PERSIST_WAL,
+                (&mut value as *mut std::os::raw::c_int).cast(),
+            )
+        };
+        if rc != rusqlite::ffi::SQLITE_OK && rc != rusqlite::ffi::SQLITE_NOTFOUND {
+            return Err(to_napi_error(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rc),
+                None,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set `PRAGMA max_page_count` and return the limit in effect
+    /// SQLite never lowers the limit below the current page count
+    fn apply_max_page_count(conn: &Connection, pages: u32) -> Result<u32> {
+        // A zero would only read the current value back
+        if pages == 0 {
+            return Err(Error::from_reason("maxPageCount must be at least 1"));
+        }
+        let effective: i64 = conn
+            .pragma_update_and_check(None, "max_page_count", pages, |row| row.get(0))
+            .map_err(to_napi_error)?;
+        Ok(effective as u32)
+    }
+
+    /// Check whether `table` was declared WITHOUT ROWID
+    fn table_is_without_rowid(conn: &Connection, table: &str) -> Result<bool> {
+        let without_rowid: Option<bool> = conn
+            .query_row(
+                "SELECT wr FROM pragma_table_list WHERE name = ? AND type = 'table'",
+                [table],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(to_napi_error)?;
+        without_rowid.ok_or_else(|| Error::from_reason(format!("Table '{}' does not exist", table)))
+    }
+
+    /// Build an INSERT ... ON CONFLICT statement for a single row
+    /// Non-conflict columns are updated from `excluded` unless `do_nothing` is set
+    fn build_upsert_sql(
+        table: &str,
+        columns: &[(String, Param)],
+        conflict_columns: &[String],
+        do_nothing: bool,
+    ) -> Result<String> {
+        if columns.is_empty() {
+            return Err(Error::from_reason(
+                "Upsert data must contain at least one column",
+            ));
+        }
+        if conflict_columns.is_empty() {
+            return Err(Error::from_reason(
+                "Upsert requires at least one conflict column",
+            ));
+        }
+        let insert_sql = Self::build_insert_sql(table, columns)?;
+        let mut targets = Vec::with_capacity(conflict_columns.len());
+        for name in conflict_columns {
+            validate_identifier(name)?;
+            targets.push(quote_identifier(name));
+        }
+        let assignments: Vec<String> = columns
+            .iter()
+            .filter(|(name, _)| {
+                !conflict_columns
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(name))
+            })
+            .map(|(name, _)| {
+                let quoted = quote_identifier(name);
+                format!("{} = excluded.{}", quoted, quoted)
+            })
+            .collect();
+        let action = if do_nothing || assignments.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", assignments.join(", "))
+        };
+        Ok(format!(
+            "{} ON CONFLICT({}) {}",
+            insert_sql,
+            targets.join(", "),
+            action
+        ))
+    }
+
+    /// Build a WHERE clause matching every (column, value) pair
+    /// NULL values are matched with `IS NULL` and are not bound
+    fn build_where_clause<'a>(
+        conditions: &'a [(String, Param)],
+        options: Option<&UpdateOptions>,
+    ) -> Result<(String, Vec<&'a Param>)> {
+        if conditions.is_empty() {
+            let allowed = options
+                .and_then(|o| o.allow_full_table_update)
+                .unwrap_or(false);
+            if !allowed {
+                return Err(Error::from_reason(
+                    "Refusing to modify every row: the where object is empty (set allowFullTableUpdate to allow this)",
+                ));
+            }
+            return Ok((String::new(), Vec::new()));
+        }
+        let mut clauses = Vec::with_capacity(conditions.len());
+        let mut params = Vec::new();
+        for (name, param) in conditions {
+            validate_identifier(name)?;
+            if matches!(param, Param::Null) {
+                clauses.push(format!("{} IS NULL", quote_identifier(name)));
+            } else {
+                clauses.push(format!("{} = ?", quote_identifier(name)));
+                params.push(param);
+            }
+        }
+        Ok((format!(" WHERE {}", clauses.join(" AND ")), params))
+    }
 }
 
 #[napi]
 impl Database {
     /// Create a new Database connection
     #[napi(constructor)]
-    pub fn new(path: String, options: Option<DatabaseOptions>) -> Result<Self> {
+    pub fn new(env: Env, path: String, options: Option<DatabaseOptions>) -> Result<Self> {
+        crate::error::register_env(&env);
         let opts = options.unwrap_or(DatabaseOptions {
             readonly: Some(false),
             create: Some(true),
             readwrite: Some(true),
+            blob_encoding: None,
+            vfs: None,
+            cache_spill: None,
+            max_page_count: None,
+            apply_default_pragmas: None,
+            max_rows: None,
+            persistent_wal: None,
+            temporary: None,
         });
 
         let readonly = opts.readonly.unwrap_or(false);
-        let create = opts.create.unwrap_or(true);
-        let readwrite = opts.readwrite.unwrap_or(true);
+        let mut flags = Self::open_flags(opts.readonly, opts.readwrite, opts.create)?;
+        let blob_encoding = match opts.blob_encoding.as_deref() {
+            Some(name) => BlobEncoding::parse(name)?,
+            None => BlobEncoding::default(),
+        };
+
+        // SQLite opens an empty path as a private temporary file that it deletes on close
+        let temporary = path.is_empty();
+        if opts.temporary.unwrap_or(false) && !temporary {
+            return Err(Error::from_reason(format!(
+                "Invalid options: temporary databases are anonymous, so the path must be \"\" (got \"{}\")",
+                path
+            )));
+        }
 
-        let conn = if path == ":memory:" {
+        let conn = if path == ":memory:" && opts.vfs.is_none() {
             Connection::open_in_memory().map_err(to_napi_error)?
         } else {
-            let mut flags = OpenFlags::empty();
-
-            if readonly {
-                flags.insert(OpenFlags::SQLITE_OPEN_READ_ONLY);
-            } else {
-                if readwrite {
-                    flags.insert(OpenFlags::SQLITE_OPEN_READ_WRITE);
-                }
-                if create {
-                    flags.insert(OpenFlags::SQLITE_OPEN_CREATE);
-                }
+            // URI filenames can set mode, cache, immutable, etc. as query parameters
+            if path.starts_with("file:") {
+                flags.insert(OpenFlags::SQLITE_OPEN_URI);
             }
 
-            if flags.is_empty() {
-                flags.insert(OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE);
+            match opts.vfs.as_deref() {
+                Some(vfs) => Connection::open_with_flags_and_vfs(&path, flags, vfs),
+                None => Connection::open_with_flags(&path, flags),
             }
-
-            Connection::open_with_flags(&path, flags).map_err(to_napi_error)?
+            .map_err(to_napi_error)?
         };
 
+        // A URI such as `file:app.db?mode=ro` can open read-only without the readonly option
+        let readonly = readonly || conn.is_readonly(rusqlite::MAIN_DB).map_err(to_napi_error)?;
+
         conn.execute_batch("PRAGMA extended_result_codes = ON")
             .map_err(to_napi_error)?;
 
-        if !readonly {
+        if !readonly && opts.apply_default_pragmas.unwrap_or(true) {
+            // Temporary databases can't use WAL, so they keep their default journal
+            if !temporary {
+                conn.execute_batch("PRAGMA journal_mode = WAL;")
+                    .map_err(to_napi_error)?;
+            }
             conn.execute_batch(
-                "PRAGMA journal_mode = WAL;
-                 PRAGMA synchronous = NORMAL;
+                "PRAGMA synchronous = NORMAL;
                  PRAGMA cache_size = -64000;
                  PRAGMA temp_store = MEMORY;
                  PRAGMA mmap_size = 268435456;
@@ -154,14 +881,67 @@ impl Database {
             .map_err(to_napi_error)?;
         }
 
-        Ok(Database {
-            conn: Arc::new(Mutex::new(conn)),
-            in_transaction: Arc::new(AtomicBool::new(false)),
-            closed: Arc::new(AtomicBool::new(false)),
-            filename: path,
-            functions: Arc::new(Mutex::new(HashMap::new())),
-            collations: Arc::new(Mutex::new(HashMap::new())),
-        })
+        if let Some(enabled) = opts.persistent_wal {
+            Self::set_persistent_wal(&conn, enabled)?;
+        }
+
+        if let Some(enabled) = opts.cache_spill {
+            conn.pragma_update(None, "cache_spill", enabled)
+                .map_err(to_napi_error)?;
+        }
+
+        if let Some(pages) = opts.max_page_count {
+            Self::apply_max_page_count(&conn, pages)?;
+        }
+
+        Ok(Database::from_connection(
+            conn,
+            path,
+            blob_encoding,
+            TextErrorMode::default(),
+            opts.max_rows,
+        ))
+    }
+
+    /// Open a new in-memory connection holding a copy of the database in `data`
+    /// Unlike `deserializeBinary`, no existing connection or its statements are touched
+    #[napi(factory)]
+    pub fn open_from_buffer(env: Env, data: Buffer, read_only: bool) -> Result<Self> {
+        crate::error::register_env(&env);
+        // Every database file starts with a 100-byte header beginning with this magic string
+        if data.len() < 100 || !data.starts_with(b"SQLite format 3\0") {
+            return Err(Error::from_reason(
+                "Invalid database buffer: missing the SQLite format 3 header",
+            ));
+        }
+
+        let mut conn = Connection::open_in_memory().map_err(to_napi_error)?;
+        let owned_data = Self::sqlite_owned_copy(&data)?;
+        conn.deserialize(rusqlite::MAIN_DB, owned_data, read_only)
+            .map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some("Failed to open database from buffer"),
+                )
+            })?;
+        conn.execute_batch("PRAGMA extended_result_codes = ON")
+            .map_err(to_napi_error)?;
+        // Reading the schema reports a corrupt buffer now rather than on the first query
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+            .map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some("Failed to open database from buffer"),
+                )
+            })?;
+
+        Ok(Database::from_connection(
+            conn,
+            ":memory:".to_string(),
+            BlobEncoding::default(),
+            TextErrorMode::default(),
+            None,
+        ))
     }
 
     /// Prepare a SQL statement for execution
@@ -169,79 +949,355 @@ impl Database {
     pub fn query(&self, sql: String) -> Result<Statement> {
         // Don't validate SQL here - let it fail at execution time if invalid
         // This allows getting stmt.source() even for queries referencing non-existent tables
-        Ok(Statement::new(sql, self.conn.clone()))
+        Ok(self.statement(sql, false))
+    }
+
+    /// Like `query`, but prepare the SQL right away so syntax errors and
+    /// missing tables are reported here rather than on first execution
+    #[napi]
+    pub fn prepare(&self, sql: String) -> Result<Statement> {
+        {
+            let conn = lock_connection(&self.conn, "prepare")?;
+            conn.prepare(&sql).map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Prepare failed: {}", sql_snippet(&sql))),
+                )
+            })?;
+        }
+        self.query(sql)
+    }
+
+    /// Like `prepare`, but keep the compiled statement in the connection's statement cache
+    /// Every all/get/run of the returned Statement reuses it instead of compiling the SQL again
+    #[napi]
+    pub fn prepare_cached(&self, sql: String) -> Result<Statement> {
+        {
+            let conn = lock_connection(&self.conn, "prepareCached")?;
+            // Dropping the cached statement puts it in the cache for the first execution
+            conn.prepare_cached(&sql).map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Prepare failed: {}", sql_snippet(&sql))),
+                )
+            })?;
+        }
+        Ok(self.statement(sql, true))
+    }
+
+    /// Choose how TEXT values that aren't valid UTF-8 are returned:
+    /// "lossy" (default, invalid bytes become U+FFFD), "error" (the query fails)
+    /// or "bytes" (the raw bytes are returned as a Buffer)
+    /// Applies to every statement of this connection, including ones already prepared
+    #[napi(ts_args_type = "mode: 'lossy' | 'error' | 'bytes'")]
+    pub fn set_text_error_mode(&self, mode: String) -> Result<()> {
+        let mode = TextErrorMode::parse(&mode)?;
+        let mut current = lock_with_context(&self.text_error_mode, "setTextErrorMode");
+        *current = mode;
+        Ok(())
     }
 
     /// Execute a SQL statement directly
     #[napi]
     pub fn run(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<QueryResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &sql);
+        let conn = lock_connection(&self.conn, "run")?;
+        let changes_before = conn.total_changes();
 
         let params_container = convert_params_container(&env, params)?;
+        let (_, last_insert_rowid) =
+            with_inserted_rowid(&conn, &sql, || params_container.execute(&conn, &sql)).map_err(
+                |e| {
+                    let snippet = sql_snippet(&sql);
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", snippet)),
+                    )
+                },
+            )?;
 
-        match params_container {
-            crate::db::ParamsContainer::Positional(positional_params) => {
-                let params_refs: Vec<&dyn ToSql> =
-                    positional_params.iter().map(|p| p as &dyn ToSql).collect();
-                conn.execute(&sql, params_refs.as_slice())
-                    .map_err(|e| {
-                        let snippet = if sql.len() > 100 { format!("{}...", &sql[..100]) } else { sql.clone() };
-                        crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", snippet)))
-                    })?;
+        Ok(QueryResult {
+            changes: conn.changes() as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Run one statement once per parameter set, atomically, collecting the rowid after each run
+    /// Runs inside a savepoint so it also composes with an open transaction
+    #[napi]
+    pub fn run_many(
+        &self,
+        env: Env,
+        sql: String,
+        params_list: Vec<Unknown>,
+    ) -> Result<RunManyResult> {
+        let mut containers = Vec::with_capacity(params_list.len());
+        for params in params_list {
+            containers.push(convert_params_container(&env, Some(params))?);
+        }
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &sql);
+        let conn = lock_connection(&self.conn, "runMany")?;
+        let mut stmt = conn.prepare_cached(&sql).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Prepare failed: {}", sql_snippet(&sql))),
+            )
+        })?;
+        conn.execute_batch("SAVEPOINT sqlite_napi_run_many")
+            .map_err(to_napi_error)?;
+        let rowid_before = conn.last_insert_rowid();
+        let mut run_all = || -> std::result::Result<RunManyResult, (usize, rusqlite::Error)> {
+            let mut changes = 0;
+            let mut rowids = Vec::with_capacity(containers.len());
+            for (i, container) in containers.iter().enumerate() {
+                // Named parameter sets may leave some placeholders unbound
+                stmt.clear_bindings();
+                container.bind(&mut stmt).map_err(|e| (i, e))?;
+                let (run_changes, rowid) =
+                    with_inserted_rowid(&conn, &sql, || stmt.raw_execute()).map_err(|e| (i, e))?;
+                changes += run_changes;
+                rowids.push(rowid);
             }
-            crate::db::ParamsContainer::Named(named_params) => {
-                let mut named_params_refs: Vec<(&str, &dyn ToSql)> = Vec::new();
-                for (key, param) in named_params.iter() {
-                    named_params_refs.push((key.as_str(), param as &dyn ToSql));
-                }
-                conn.execute(&sql, named_params_refs.as_slice())
-                    .map_err(|e| {
-                        let snippet = if sql.len() > 100 { format!("{}...", &sql[..100]) } else { sql.clone() };
-                        crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", snippet)))
-                    })?;
+            Ok(RunManyResult {
+                changes: changes as u32,
+                rowids,
+            })
+        };
+        match run_all() {
+            Ok(result) => {
+                conn.execute_batch("RELEASE sqlite_napi_run_many")
+                    .map_err(to_napi_error)?;
+                Ok(result)
+            }
+            Err((i, e)) => {
+                // The rolled back inserts would otherwise stay visible through lastInsertRowid
+                unsafe {
+                    rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), rowid_before)
+                };
+                conn.execute_batch(
+                    "ROLLBACK TO sqlite_napi_run_many; RELEASE sqlite_napi_run_many",
+                )
+                .ok();
+                Err(crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("runMany failed at parameter set {}", i)),
+                ))
             }
         }
+    }
 
-        Ok(QueryResult {
-            changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
-        })
+    /// Run a statement inside a savepoint and always roll it back, reporting what it would change
+    ///
+    /// Statements that can't be undone by a rollback are rejected: VACUUM, ATTACH and DETACH,
+    /// transaction control and PRAGMAs, most of which change connection settings directly.
+    #[napi]
+    pub fn dry_run(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<QueryResult> {
+        let keyword = top_level_keywords(&sql)
+            .into_iter()
+            .find(|keyword| keyword != ";")
+            .unwrap_or_default();
+        if matches!(
+            keyword.as_str(),
+            "VACUUM"
+                | "ATTACH"
+                | "DETACH"
+                | "PRAGMA"
+                | "BEGIN"
+                | "COMMIT"
+                | "END"
+                | "ROLLBACK"
+                | "SAVEPOINT"
+                | "RELEASE"
+        ) {
+            return Err(Error::from_reason(format!(
+                "Dry run failed: {} statements can't be rolled back",
+                keyword
+            )));
+        }
+        let params_container = convert_params_container(&env, params)?;
+        let conn = lock_connection(&self.conn, "dryRun")?;
+        let changes_before = conn.total_changes();
+        let rowid_before = conn.last_insert_rowid();
+        conn.execute_batch("SAVEPOINT sqlite_napi_dry_run")
+            .map_err(to_napi_error)?;
+        let result = with_inserted_rowid(&conn, &sql, || params_container.execute(&conn, &sql))
+            .map(|(_, last_insert_rowid)| QueryResult {
+                changes: conn.changes() as u32,
+                last_insert_rowid,
+                total_changes: (conn.total_changes() - changes_before) as u32,
+            });
+        let rollback =
+            conn.execute_batch("ROLLBACK TO sqlite_napi_dry_run; RELEASE sqlite_napi_dry_run");
+        // The rolled back insert would otherwise stay visible through lastInsertRowid
+        unsafe { rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), rowid_before) };
+        // The statement's own error explains a failed rollback better than the rollback's does
+        let result = result
+            .map_err(|e| crate::error::to_napi_error_with_context(e, Some("Dry run failed")))?;
+        rollback.map_err(to_napi_error)?;
+        Ok(result)
+    }
+
+    /// Like `run`, but retry with exponential backoff while the database is busy or locked
+    /// (`SQLITE_BUSY*` / `SQLITE_LOCKED*`); any other error is thrown immediately
+    /// The waits block the JS thread, so the delay is capped by `maxBackoffMs` and the
+    /// number of retries by `MAX_RETRIES`
+    #[napi]
+    pub fn run_with_retry(
+        &self,
+        env: Env,
+        sql: String,
+        params: Option<Unknown>,
+        options: Option<RetryOptions>,
+    ) -> Result<QueryResult> {
+        const MAX_RETRIES: u32 = 100;
+        let max_retries = options.as_ref().and_then(|o| o.max_retries).unwrap_or(5);
+        if max_retries > MAX_RETRIES {
+            return Err(Error::from_reason(format!(
+                "maxRetries must be at most {}, got {}",
+                MAX_RETRIES, max_retries
+            )));
+        }
+        let max_backoff_ms = options
+            .as_ref()
+            .and_then(|o| o.max_backoff_ms)
+            .unwrap_or(1000) as u64;
+        let mut backoff_ms = options.as_ref().and_then(|o| o.backoff_ms).unwrap_or(10) as u64;
+        let params_container = convert_params_container(&env, params)?;
+        let is_busy = |e: &rusqlite::Error| {
+            matches!(
+                e.sqlite_error_code(),
+                Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+            )
+        };
+
+        let mut attempt = 0;
+        loop {
+            let result = {
+                let _timer = SlowQueryTimer::start(&self.slow_query_log, &sql);
+                let conn = lock_connection(&self.conn, "runWithRetry")?;
+                let changes_before = conn.total_changes();
+                with_inserted_rowid(&conn, &sql, || params_container.execute(&conn, &sql)).map(
+                    |(_, last_insert_rowid)| QueryResult {
+                        changes: conn.changes() as u32,
+                        last_insert_rowid,
+                        total_changes: (conn.total_changes() - changes_before) as u32,
+                    },
+                )
+            };
+            match result {
+                Err(e) if attempt < max_retries && is_busy(&e) => {
+                    // The connection lock was released at the end of the attempt
+                    let delay = backoff_ms.min(max_backoff_ms);
+                    std::thread::sleep(std::time::Duration::from_millis(delay));
+                    backoff_ms = backoff_ms.saturating_mul(2);
+                    attempt += 1;
+                }
+                result => {
+                    return result.map_err(|e| {
+                        let context = if is_busy(&e) {
+                            format!(
+                                "Query still busy after {} retries: {}",
+                                attempt,
+                                sql_snippet(&sql)
+                            )
+                        } else {
+                            format!("Query failed: {}", sql_snippet(&sql))
+                        };
+                        crate::error::to_napi_error_with_context(e, Some(&context))
+                    });
+                }
+            }
+        }
     }
 
     /// Execute SQL directly (without callback)
     #[napi]
     pub fn exec(&self, sql: String) -> Result<QueryResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        conn.execute_batch(&sql).map_err(|e| {
-            let snippet = if sql.len() > 100 { format!("{}...", &sql[..100]) } else { sql.clone() };
-            crate::error::to_napi_error_with_context(e, Some(&format!("Execute failed: {}", snippet)))
-        })?;
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &sql);
+        let conn = lock_connection(&self.conn, "exec")?;
+        let changes_before = conn.total_changes();
+        let ((), last_insert_rowid) = with_inserted_rowid(&conn, &sql, || conn.execute_batch(&sql))
+            .map_err(|e| {
+                let snippet = sql_snippet(&sql);
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Execute failed: {}", snippet)),
+                )
+            })?;
         Ok(QueryResult {
             changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
         })
     }
 
-    /// Begin a transaction
+    /// Execute a multi-statement script, returning a summary of each statement in order
+    ///
+    /// Each summary has the statement's `sql`, `changes` and `lastInsertRowid`, plus `rows`
+    /// for statements that return data (SELECT, PRAGMA, RETURNING). Statements are split
+    /// by SQLite's own parser, so semicolons inside literals and trigger bodies are safe.
+    #[napi(ts_return_type = "any[]")]
+    pub fn exec_script(&self, sql: String) -> Result<Vec<StatementSummary>> {
+        use rusqlite::fallible_iterator::FallibleIterator;
+
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &sql);
+        let encoding = self.value_encoding("execScript");
+        let conn = lock_connection(&self.conn, "execScript")?;
+        let mut batch = rusqlite::Batch::new(&conn, &sql);
+        let mut results = Vec::new();
+        let context = |e: rusqlite::Error, index: usize| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Script statement {} failed", index + 1)),
+            )
+        };
+        while let Some(mut stmt) = batch.next().map_err(|e| context(e, results.len()))? {
+            let statement_sql = stmt.expanded_sql().unwrap_or_default().trim().to_string();
+            let total_changes = conn.total_changes();
+            let mut summary = serde_json::Map::new();
+            let (rows, last_insert_rowid) = with_inserted_rowid(&conn, &statement_sql, || {
+                if stmt.column_count() > 0 {
+                    Self::encoded_rows(&mut stmt, encoding).map(Some)
+                } else {
+                    stmt.raw_execute().map(|_| None)
+                }
+            })
+            .map_err(|e| context(e, results.len()))?;
+            // sqlite3_changes() keeps the last DML count, so only report it if this statement wrote
+            let changes = if conn.total_changes() != total_changes {
+                conn.changes()
+            } else {
+                0
+            };
+            summary.insert("sql".to_string(), serde_json::Value::String(statement_sql));
+            summary.insert("changes".to_string(), changes.into());
+            summary.insert("lastInsertRowid".to_string(), last_insert_rowid.into());
+            results.push(StatementSummary::new(summary, rows));
+        }
+        Ok(results)
+    }
+
+    /// Begin a transaction, or a uniquely named savepoint if a transaction is already open
     #[napi]
     pub fn transaction(&self, mode: Option<String>) -> Result<Transaction> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        let mode_str = match mode.as_deref() {
-            Some("immediate") => "IMMEDIATE",
-            Some("exclusive") => "EXCLUSIVE",
-            _ => "DEFERRED",
-        };
-        conn.execute(&format!("BEGIN {}", mode_str), [])
-            .map_err(to_napi_error)?;
+        let conn = lock_connection(&self.conn, "transaction")?;
+        if self.sync_transaction_flag(&conn) {
+            // Already inside a transaction: nest with a uniquely named savepoint
+            let id = self
+                .savepoint_counter
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let name = format!("sqlite_napi_tx_{}", id);
+            conn.execute(&format!("SAVEPOINT {}", name), [])
+                .map_err(to_napi_error)?;
+            return Ok(Transaction::new(
+                self.conn.clone(),
+                self.in_transaction.clone(),
+                false,
+                Some(name),
+            ));
+        }
+        Self::begin_transaction(&conn, mode.as_deref())?;
         self.in_transaction
             .store(true, std::sync::atomic::Ordering::SeqCst);
         Ok(Transaction::new(
@@ -259,22 +1315,26 @@ impl Database {
         mode: Option<String>,
         statements: Vec<String>,
     ) -> Result<QueryResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        let mode_str = match mode.as_deref() {
-            Some("immediate") => "IMMEDIATE",
-            Some("exclusive") => "EXCLUSIVE",
-            _ => "DEFERRED",
-        };
-        conn.execute(&format!("BEGIN {}", mode_str), [])
-            .map_err(to_napi_error)?;
+        let conn = lock_connection(&self.conn, "transactionFn")?;
+        let changes_before = conn.total_changes();
+        if self.sync_transaction_flag(&conn) {
+            return Err(Error::from_reason(
+                "A transaction is already open on this connection; commit or roll it back first",
+            ));
+        }
+        Self::begin_transaction(&conn, mode.as_deref())?;
+        let mut last_insert_rowid = None;
         for (i, sql) in statements.iter().enumerate() {
-            if let Err(e) = conn.execute_batch(sql) {
-                conn.execute("ROLLBACK", []).ok();
-                let snippet = if sql.len() > 100 { format!("{}...", &sql[..100]) } else { sql.clone() };
-                return Err(crate::error::to_napi_error_with_context(e, Some(&format!("Transaction statement {} failed: {}", i, snippet))));
+            match with_inserted_rowid(&conn, sql, || conn.execute_batch(sql)) {
+                Ok(((), rowid)) => last_insert_rowid = rowid.or(last_insert_rowid),
+                Err(e) => {
+                    conn.execute("ROLLBACK", []).ok();
+                    let snippet = sql_snippet(sql);
+                    return Err(crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Transaction statement {} failed: {}", i, snippet)),
+                    ));
+                }
             }
         }
         conn.execute("COMMIT", []).map_err(|e| {
@@ -283,17 +1343,167 @@ impl Database {
         })?;
         Ok(QueryResult {
             changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
         })
     }
 
+    /// Run `statements` in one transaction like `transactionFn`, returning the final statement's rows
+    /// Rolls back and throws if any statement fails; a final statement without rows returns []
+    #[napi(ts_return_type = "any[]")]
+    pub fn transaction_query(
+        &self,
+        mode: Option<String>,
+        statements: Vec<String>,
+    ) -> Result<ResultValue> {
+        let Some((last, rest)) = statements.split_last() else {
+            return Err(Error::from_reason(
+                "transactionQuery needs at least one statement",
+            ));
+        };
+        let encoding = self.value_encoding("transactionQuery");
+        let conn = lock_connection(&self.conn, "transactionQuery")?;
+        if self.sync_transaction_flag(&conn) {
+            return Err(Error::from_reason(
+                "A transaction is already open on this connection; commit or roll it back first",
+            ));
+        }
+        Self::begin_transaction(&conn, mode.as_deref())?;
+        let fail = |e: rusqlite::Error, i: usize, sql: &str| {
+            conn.execute("ROLLBACK", []).ok();
+            let snippet = sql_snippet(sql);
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Transaction statement {} failed: {}", i, snippet)),
+            )
+        };
+        for (i, sql) in rest.iter().enumerate() {
+            conn.execute_batch(sql).map_err(|e| fail(e, i, sql))?;
+        }
+        let rows = conn
+            .prepare(last)
+            .and_then(|mut stmt| {
+                if stmt.column_count() > 0 {
+                    Self::encoded_rows(&mut stmt, encoding)
+                } else {
+                    stmt.raw_execute()
+                        .map(|_| serde_json::Value::Array(Vec::new()).into())
+                }
+            })
+            .map_err(|e| fail(e, rest.len(), last))?;
+        conn.execute("COMMIT", []).map_err(|e| {
+            conn.execute("ROLLBACK", []).ok();
+            to_napi_error(e)
+        })?;
+        Ok(rows)
+    }
+
+    /// Run `callback` with the connection locked for its whole duration and return its result
+    /// The callback gets a ConnectionBatch handle to run statements with; calling this
+    /// Database or its statements inside the callback throws instead of waiting on the lock
+    #[napi(
+        ts_args_type = "callback: (batch: ConnectionBatch) => any",
+        ts_return_type = "any"
+    )]
+    pub fn batch<'env>(
+        &self,
+        callback: Function<'env, ConnectionBatch, Unknown<'env>>,
+    ) -> Result<Unknown<'env>> {
+        let conn = lock_connection(&self.conn, "batch")?;
+        let encoding = self.value_encoding("batch");
+        let open = Arc::new(AtomicBool::new(true));
+        let result = {
+            let _mark = crate::error::BatchLockMark::new(&self.conn);
+            callback.call(ConnectionBatch::new(&conn, open.clone(), encoding))
+        };
+        open.store(false, std::sync::atomic::Ordering::SeqCst);
+        self.sync_transaction_flag(&conn);
+        result
+    }
+
+    /// Run `callback` inside a deferred read-only transaction and return its result
+    /// All reads in the callback see one consistent snapshot; the transaction is always rolled back
+    #[napi(ts_args_type = "callback: () => any", ts_return_type = "any")]
+    pub fn read_transaction<'env>(
+        &self,
+        callback: Function<'env, (), Unknown<'env>>,
+    ) -> Result<Unknown<'env>> {
+        let query_only = {
+            let conn = lock_connection(&self.conn, "readTransaction")?;
+            if !conn.is_autocommit() {
+                return Err(Error::from_reason(
+                    "Cannot start a read transaction while another transaction is open",
+                ));
+            }
+            let query_only: bool = conn
+                .query_row("PRAGMA query_only", [], |row| row.get(0))
+                .map_err(to_napi_error)?;
+            conn.execute_batch("BEGIN DEFERRED; PRAGMA query_only = ON")
+                .map_err(to_napi_error)?;
+            self.in_transaction
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            query_only
+        };
+
+        // The connection must be unlocked while the callback runs so it can query
+        let result = callback.call(());
+
+        let conn = lock_connection(&self.conn, "readTransaction")?;
+        let restore = format!(
+            "PRAGMA query_only = {}",
+            if query_only { "ON" } else { "OFF" }
+        );
+        conn.execute_batch(&restore).map_err(to_napi_error)?;
+        if !conn.is_autocommit() {
+            conn.execute_batch("ROLLBACK").map_err(to_napi_error)?;
+        }
+        self.in_transaction
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        result
+    }
+
+    /// Create `TEMP VIEW name AS sql`, run `callback` and drop the view again, even if it throws
+    /// The view is only visible to this connection; the callback's result is returned
+    #[napi(
+        ts_args_type = "name: string, sql: string, callback: () => any",
+        ts_return_type = "any"
+    )]
+    pub fn with_temp_view<'env>(
+        &self,
+        name: String,
+        sql: String,
+        callback: Function<'env, (), Unknown<'env>>,
+    ) -> Result<Unknown<'env>> {
+        validate_identifier(&name)?;
+        let view = quote_identifier(&name);
+        {
+            let conn = lock_connection(&self.conn, "withTempView")?;
+            // execute() rejects a trailing second statement smuggled in through `sql`
+            conn.execute(&format!("CREATE TEMP VIEW {} AS {}", view, sql), [])
+                .map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Creating temp view {} failed", name)),
+                    )
+                })?;
+        }
+
+        // The connection must be unlocked while the callback runs so it can query
+        let result = callback.call(());
+
+        let conn = lock_connection(&self.conn, "withTempView")?;
+        let dropped = conn
+            .execute(&format!("DROP VIEW IF EXISTS temp.{}", view), [])
+            .map_err(to_napi_error);
+        let value = result?;
+        dropped?;
+        Ok(value)
+    }
+
     /// Load a SQLite extension
     #[napi]
     pub fn load_extension(&self, path: String) -> Result<()> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "loadExtension")?;
         unsafe {
             conn.load_extension(&path, Option::<&str>::None)
                 .map_err(to_napi_error)?;
@@ -304,32 +1514,35 @@ impl Database {
     /// Serialize the database to binary format
     #[napi]
     pub fn serialize_binary(&self) -> Result<Buffer> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "serializeBinary")?;
         let data = conn.serialize("main").map_err(to_napi_error)?;
         Ok(Buffer::from(data.to_vec()))
     }
 
+    /// Serialize the database straight to `path`, without building a JS Buffer
+    /// Databases loaded with `deserializeBinary` are written from SQLite's own pages
+    /// (`SQLITE_SERIALIZE_NOCOPY`), borrowed only while the connection is locked;
+    /// any other database is copied once by SQLite
+    /// Returns the number of bytes written
+    #[napi]
+    pub fn serialize_to_file(&self, path: String) -> Result<i64> {
+        let conn = lock_connection(&self.conn, "serializeToFile")?;
+        let data = conn.serialize("main").map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Serialize to {} failed", path)),
+            )
+        })?;
+        std::fs::write(&path, &*data)
+            .map_err(|e| Error::from_reason(format!("Failed to write {}: {}", path, e)))?;
+        Ok(data.len() as i64)
+    }
+
     /// Deserialize a database from binary format
     #[napi]
     pub fn deserialize_binary(&self, data: Buffer, read_only: Option<bool>) -> Result<()> {
-        let mut conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        let len = data.len();
-        let sqlite_ptr = unsafe { rusqlite::ffi::sqlite3_malloc(len as i32) as *mut u8 };
-        if sqlite_ptr.is_null() {
-            return Err(Error::from_reason("Failed to allocate memory"));
-        }
-        unsafe {
-            std::ptr::copy_nonoverlapping(data.as_ref().as_ptr(), sqlite_ptr, len);
-        }
-        let owned_data = unsafe {
-            OwnedData::from_raw_nonnull(std::ptr::NonNull::new_unchecked(sqlite_ptr), len)
-        };
+        let mut conn = lock_connection(&self.conn, "deserializeBinary")?;
+        let owned_data = Self::sqlite_owned_copy(&data)?;
         conn.deserialize("main", owned_data, read_only.unwrap_or(false))
             .map_err(to_napi_error)?;
         Ok(())
@@ -338,10 +1551,7 @@ impl Database {
     /// Serialize the database schema to SQL statements
     #[napi]
     pub fn serialize(&self) -> Result<String> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "serialize")?;
         let mut stmt = conn.prepare("SELECT sql FROM sqlite_master WHERE sql IS NOT NULL ORDER BY CASE WHEN type = 'table' THEN 1 WHEN type = 'index' THEN 2 ELSE 3 END, name").map_err(to_napi_error)?;
         let statements: Vec<String> = stmt
             .query_map([], |row| row.get(0))
@@ -354,14 +1564,547 @@ impl Database {
     /// Deserialize a database from SQL statements
     #[napi]
     pub fn deserialize(&self, sql: String) -> Result<()> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "deserialize")?;
         conn.execute_batch(&sql).map_err(to_napi_error)?;
         Ok(())
     }
 
+    // ========================================
+    // CRUD Helpers
+    // ========================================
+
+    /// Insert a row built from an object's keys (columns) and values
+    /// Table and column names are quoted; values are bound as parameters
+    #[napi]
+    pub fn insert(&self, table: String, data: Unknown) -> Result<QueryResult> {
+        let columns = object_to_params(&data)?;
+        let sql = Self::build_insert_sql(&table, &columns)?;
+        let conn = lock_connection(&self.conn, "insert")?;
+        let changes_before = conn.total_changes();
+        let params_refs: Vec<&dyn ToSql> = columns.iter().map(|(_, p)| p as &dyn ToSql).collect();
+        let (changes, last_insert_rowid) =
+            with_inserted_rowid(&conn, &sql, || conn.execute(&sql, params_refs.as_slice()))
+                .map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Insert into {} failed", table)),
+                    )
+                })?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Insert several rows atomically - all rows are inserted or none are
+    /// Runs inside a savepoint so it also composes with an open transaction
+    #[napi]
+    pub fn insert_many(&self, table: String, rows: Vec<Unknown>) -> Result<QueryResult> {
+        let mut prepared = Vec::with_capacity(rows.len());
+        for row in rows.iter() {
+            let columns = object_to_params(row)?;
+            let sql = Self::build_insert_sql(&table, &columns)?;
+            prepared.push((sql, columns));
+        }
+        let conn = lock_connection(&self.conn, "insertMany")?;
+        let changes_before = conn.total_changes();
+        let rowid_before = conn.last_insert_rowid();
+        conn.execute_batch("SAVEPOINT sqlite_napi_insert_many")
+            .map_err(to_napi_error)?;
+        let mut changes = 0;
+        let mut last_insert_rowid = None;
+        for (i, (sql, columns)) in prepared.iter().enumerate() {
+            let params_refs: Vec<&dyn ToSql> =
+                columns.iter().map(|(_, p)| p as &dyn ToSql).collect();
+            let result = conn.prepare_cached(sql).and_then(|mut stmt| {
+                with_inserted_rowid(&conn, sql, || stmt.execute(params_refs.as_slice()))
+            });
+            match result {
+                Ok((n, rowid)) => {
+                    changes += n;
+                    last_insert_rowid = rowid.or(last_insert_rowid);
+                }
+                Err(e) => {
+                    // The rolled back inserts would otherwise stay visible through lastInsertRowid
+                    unsafe {
+                        rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), rowid_before)
+                    };
+                    conn.execute_batch(
+                        "ROLLBACK TO sqlite_napi_insert_many; RELEASE sqlite_napi_insert_many",
+                    )
+                    .ok();
+                    return Err(crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Insert into {} failed at row {}", table, i)),
+                    ));
+                }
+            }
+        }
+        conn.execute_batch("RELEASE sqlite_napi_insert_many")
+            .map_err(to_napi_error)?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Insert a row, or update the existing row when `conflictColumns` collide
+    /// Pass `{ doNothing: true }` to keep the existing row instead
+    #[napi]
+    pub fn upsert(
+        &self,
+        table: String,
+        data: Unknown,
+        conflict_columns: Vec<String>,
+        options: Option<UpsertOptions>,
+    ) -> Result<QueryResult> {
+        let columns = object_to_params(&data)?;
+        let do_nothing = options.and_then(|o| o.do_nothing).unwrap_or(false);
+        let sql = Self::build_upsert_sql(&table, &columns, &conflict_columns, do_nothing)?;
+        let conn = lock_connection(&self.conn, "upsert")?;
+        let changes_before = conn.total_changes();
+        let params_refs: Vec<&dyn ToSql> = columns.iter().map(|(_, p)| p as &dyn ToSql).collect();
+        let (changes, last_insert_rowid) =
+            with_inserted_rowid(&conn, &sql, || conn.execute(&sql, params_refs.as_slice()))
+                .map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Upsert into {} failed", table)),
+                    )
+                })?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Update rows matching every key/value in `where` with the values in `data`
+    /// An empty `where` is rejected unless `allowFullTableUpdate` is set
+    #[napi]
+    pub fn update(
+        &self,
+        table: String,
+        data: Unknown,
+        r#where: Unknown,
+        options: Option<UpdateOptions>,
+    ) -> Result<QueryResult> {
+        validate_identifier(&table)?;
+        let columns = object_to_params(&data)?;
+        if columns.is_empty() {
+            return Err(Error::from_reason(
+                "Update data must contain at least one column",
+            ));
+        }
+        let conditions = object_to_params(&r#where)?;
+        let (where_sql, where_params) = Self::build_where_clause(&conditions, options.as_ref())?;
+
+        let mut assignments = Vec::with_capacity(columns.len());
+        for (name, _) in columns.iter() {
+            validate_identifier(name)?;
+            assignments.push(format!("{} = ?", quote_identifier(name)));
+        }
+        let sql = format!(
+            "UPDATE {} SET {}{}",
+            quote_identifier(&table),
+            assignments.join(", "),
+            where_sql
+        );
+
+        let mut params_refs: Vec<&dyn ToSql> =
+            columns.iter().map(|(_, p)| p as &dyn ToSql).collect();
+        params_refs.extend(where_params.into_iter().map(|p| p as &dyn ToSql));
+
+        let conn = lock_connection(&self.conn, "update")?;
+        let changes_before = conn.total_changes();
+        let changes = conn.execute(&sql, params_refs.as_slice()).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Update of {} failed", table)),
+            )
+        })?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid: None,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Set the value at JSON `path` in `column` of the row with `rowid`, via SQLite's `json_set`
+    /// `value` is stored as JSON, so objects and arrays become nested JSON rather than text
+    #[napi]
+    pub fn json_set(
+        &self,
+        table: String,
+        column: String,
+        rowid: i64,
+        path: String,
+        value: serde_json::Value,
+    ) -> Result<QueryResult> {
+        // A NULL document starts out as an empty object, since json_set(NULL, ...) stays NULL
+        self.update_json(
+            "jsonSet",
+            &table,
+            &column,
+            "json_set(COALESCE({column}, '{}'), ?, json(?))",
+            &[&path, &value.to_string(), &rowid],
+        )
+    }
+
+    /// Remove the value at JSON `path` from `column` of the row with `rowid`, via `json_remove`
+    #[napi]
+    pub fn json_remove(
+        &self,
+        table: String,
+        column: String,
+        rowid: i64,
+        path: String,
+    ) -> Result<QueryResult> {
+        self.update_json(
+            "jsonRemove",
+            &table,
+            &column,
+            "json_remove({column}, ?)",
+            &[&path, &rowid],
+        )
+    }
+
+    /// Update many rows in bulk, giving each keyed row its own values
+    /// Every row must have the same columns, including `keyColumn`; each row updates the
+    /// table row whose `keyColumn` equals its key with the values of its other columns.
+    /// Rows are applied with one `UPDATE ... FROM` statement per batch of bound parameters
+    #[napi]
+    pub fn update_many(
+        &self,
+        table: String,
+        key_column: String,
+        rows: Vec<Unknown>,
+    ) -> Result<QueryResult> {
+        validate_identifier(&table)?;
+        validate_identifier(&key_column)?;
+        let mut prepared = Vec::with_capacity(rows.len());
+        for row in rows.iter() {
+            prepared.push(object_to_params(row)?);
+        }
+        let Some(first) = prepared.first() else {
+            return Ok(QueryResult {
+                changes: 0,
+                last_insert_rowid: None,
+                total_changes: 0,
+            });
+        };
+
+        // The first row fixes the column order; the key column leads every VALUES row
+        let mut names = vec![key_column.clone()];
+        for (name, _) in first.iter() {
+            validate_identifier(name)?;
+            if *name != key_column {
+                names.push(name.clone());
+            }
+        }
+        if names.len() == 1 {
+            return Err(Error::from_reason(
+                "updateMany rows must contain at least one column besides the key",
+            ));
+        }
+        let mut values = Vec::with_capacity(prepared.len());
+        for (i, mut columns) in prepared.into_iter().enumerate() {
+            if columns.len() != names.len() {
+                return Err(Error::from_reason(format!(
+                    "updateMany row {} must have exactly the columns {}",
+                    i,
+                    names.join(", ")
+                )));
+            }
+            let mut row = Vec::with_capacity(names.len());
+            for name in names.iter() {
+                let Some(index) = columns.iter().position(|(c, _)| c == name) else {
+                    return Err(Error::from_reason(format!(
+                        "updateMany row {} is missing column '{}'",
+                        i, name
+                    )));
+                };
+                row.push(columns.swap_remove(index).1);
+            }
+            values.push(row);
+        }
+
+        let quoted: Vec<String> = names.iter().map(|n| quote_identifier(n)).collect();
+        let assignments: Vec<String> = quoted[1..]
+            .iter()
+            .map(|c| format!("{} = sqlite_napi_rows.{}", c, c))
+            .collect();
+        let build_sql = |row_count: usize| {
+            let row_placeholders = format!("({})", vec!["?"; names.len()].join(", "));
+            format!(
+                "WITH sqlite_napi_rows ({}) AS (VALUES {}) UPDATE {} SET {} FROM sqlite_napi_rows WHERE {}.{} = sqlite_napi_rows.{}",
+                quoted.join(", "),
+                vec![row_placeholders; row_count].join(", "),
+                quote_identifier(&table),
+                assignments.join(", "),
+                quote_identifier(&table),
+                quoted[0],
+                quoted[0]
+            )
+        };
+
+        let conn = lock_connection(&self.conn, "updateMany")?;
+        let changes_before = conn.total_changes();
+        let max_params = conn
+            .limit(Limit::SQLITE_LIMIT_VARIABLE_NUMBER)
+            .map_err(to_napi_error)?
+            .max(1) as usize;
+        let batch_size = (max_params / names.len()).max(1);
+        conn.execute_batch("SAVEPOINT sqlite_napi_update_many")
+            .map_err(to_napi_error)?;
+        let mut changes = 0;
+        for batch in values.chunks(batch_size) {
+            let params_refs: Vec<&dyn ToSql> =
+                batch.iter().flatten().map(|p| p as &dyn ToSql).collect();
+            let result = conn
+                .prepare_cached(&build_sql(batch.len()))
+                .and_then(|mut stmt| stmt.execute(params_refs.as_slice()));
+            match result {
+                Ok(n) => changes += n,
+                Err(e) => {
+                    conn.execute_batch(
+                        "ROLLBACK TO sqlite_napi_update_many; RELEASE sqlite_napi_update_many",
+                    )
+                    .ok();
+                    return Err(crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Update of {} failed", table)),
+                    ));
+                }
+            }
+        }
+        conn.execute_batch("RELEASE sqlite_napi_update_many")
+            .map_err(to_napi_error)?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid: None,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Delete rows matching every key/value in `where`
+    /// An empty `where` is rejected unless `allowFullTableUpdate` is set
+    #[napi]
+    pub fn delete(
+        &self,
+        table: String,
+        r#where: Unknown,
+        options: Option<UpdateOptions>,
+    ) -> Result<QueryResult> {
+        validate_identifier(&table)?;
+        let conditions = object_to_params(&r#where)?;
+        let (where_sql, where_params) = Self::build_where_clause(&conditions, options.as_ref())?;
+        let sql = format!("DELETE FROM {}{}", quote_identifier(&table), where_sql);
+        let params_refs: Vec<&dyn ToSql> =
+            where_params.into_iter().map(|p| p as &dyn ToSql).collect();
+
+        let conn = lock_connection(&self.conn, "delete")?;
+        let changes_before = conn.total_changes();
+        let changes = conn.execute(&sql, params_refs.as_slice()).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Delete from {} failed", table)),
+            )
+        })?;
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid: None,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    // ========================================
+    // Import and Export
+    // ========================================
+
+    /// Run a query and stream its rows to `path` as newline-delimited JSON
+    /// Rows are written as they are read, so the full result is never held in memory
+    /// Returns the number of rows written
+    #[napi]
+    pub fn export_ndjson(
+        &self,
+        env: Env,
+        sql: String,
+        path: String,
+        params: Option<Unknown>,
+    ) -> Result<u32> {
+        let params_container = convert_params_container(&env, params)?;
+        let conn = lock_connection(&self.conn, "exportNdjson")?;
+        let mut stmt = conn.prepare(&sql).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Prepare failed: {}", sql_snippet(&sql))),
+            )
+        })?;
+        params_container
+            .resolve_names(&stmt)
+            .and_then(|params_container| params_container.bind(&mut stmt))
+            .map_err(to_napi_error)?;
+
+        // Write next to `path` and rename on success, so a failed export neither leaves a
+        // truncated file behind nor clobbers an earlier export
+        let tmp_path = format!("{}.{}.tmp", path, std::process::id());
+        let written = std::fs::File::create(&tmp_path)
+            .map_err(|e| Error::from_reason(format!("Failed to create {}: {}", tmp_path, e)))
+            .and_then(|file| {
+                let mut out = std::io::BufWriter::new(file);
+                let written = Self::write_ndjson_rows(&mut stmt, &mut out).map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Export to {} failed", path)),
+                    )
+                })?;
+                out.into_inner().map_err(|e| {
+                    Error::from_reason(format!("Failed to write {}: {}", path, e.error()))
+                })?;
+                std::fs::rename(&tmp_path, &path)
+                    .map_err(|e| Error::from_reason(format!("Failed to write {}: {}", path, e)))?;
+                Ok(written)
+            });
+        if written.is_err() {
+            std::fs::remove_file(&tmp_path).ok();
+        }
+        written
+    }
+
+    /// Insert every line of a newline-delimited JSON file into `table`, mapping keys to columns
+    /// Runs in one transaction (or a savepoint inside an open transaction) unless `batchSize` is set
+    #[napi]
+    pub fn import_ndjson(
+        &self,
+        table: String,
+        path: String,
+        options: Option<ImportOptions>,
+    ) -> Result<ImportResult> {
+        use std::io::BufRead;
+
+        validate_identifier(&table)?;
+        let skip_invalid = options
+            .as_ref()
+            .and_then(|o| o.skip_invalid_rows)
+            .unwrap_or(false);
+        let batch_size = options.as_ref().and_then(|o| o.batch_size).unwrap_or(0) as usize;
+        let file = std::fs::File::open(&path)
+            .map_err(|e| Error::from_reason(format!("Failed to open {}: {}", path, e)))?;
+
+        let conn = lock_connection(&self.conn, "importNdjson")?;
+        let columns: Vec<String> = {
+            let mut stmt = conn
+                .prepare(&format!("PRAGMA table_info({})", quote_identifier(&table)))
+                .map_err(to_napi_error)?;
+            let names = stmt
+                .query_map([], |row| row.get::<_, String>(1))
+                .map_err(to_napi_error)?
+                .collect::<std::result::Result<Vec<String>, _>>()
+                .map_err(to_napi_error)?;
+            names
+        };
+        if columns.is_empty() {
+            return Err(Error::from_reason(format!(
+                "Table '{}' does not exist",
+                table
+            )));
+        }
+
+        // Batching only applies to a top-level import; inside a transaction use a savepoint
+        let top_level = conn.is_autocommit();
+        let (begin, commit, rollback) = if top_level {
+            ("BEGIN", "COMMIT", "ROLLBACK")
+        } else {
+            (
+                "SAVEPOINT sqlite_napi_import",
+                "RELEASE sqlite_napi_import",
+                "ROLLBACK TO sqlite_napi_import; RELEASE sqlite_napi_import",
+            )
+        };
+        conn.execute_batch(begin).map_err(to_napi_error)?;
+
+        let mut inserted = 0u32;
+        let mut skipped = 0u32;
+        let mut pending = 0usize;
+        let result: Result<()> = (|| {
+            for (index, line) in std::io::BufReader::new(file).lines().enumerate() {
+                let line_number = index + 1;
+                let line = line
+                    .map_err(|e| Error::from_reason(format!("Failed to read {}: {}", path, e)))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let invalid = |reason: String| {
+                    Error::from_reason(format!("Line {} of {}: {}", line_number, path, reason))
+                };
+                let object = match serde_json::from_str::<serde_json::Value>(&line) {
+                    Ok(serde_json::Value::Object(object)) => object,
+                    Ok(_) if skip_invalid => {
+                        skipped += 1;
+                        continue;
+                    }
+                    Ok(_) => return Err(invalid("expected a JSON object".to_string())),
+                    Err(_) if skip_invalid => {
+                        skipped += 1;
+                        continue;
+                    }
+                    Err(e) => return Err(invalid(format!("invalid JSON: {}", e))),
+                };
+                if let Some(unknown) = object
+                    .keys()
+                    .find(|key| !columns.iter().any(|c| c.eq_ignore_ascii_case(key)))
+                {
+                    if skip_invalid {
+                        skipped += 1;
+                        continue;
+                    }
+                    return Err(invalid(format!(
+                        "'{}' is not a column of {}",
+                        unknown, table
+                    )));
+                }
+
+                let mut row = Vec::with_capacity(object.len());
+                for (key, value) in object.iter() {
+                    row.push((key.clone(), json_value_to_param(value)?));
+                }
+                let sql = Self::build_insert_sql(&table, &row)?;
+                let params_refs: Vec<&dyn ToSql> =
+                    row.iter().map(|(_, p)| p as &dyn ToSql).collect();
+                conn.prepare_cached(&sql)
+                    .and_then(|mut stmt| stmt.execute(params_refs.as_slice()))
+                    .map_err(|e| {
+                        crate::error::to_napi_error_with_context(
+                            e,
+                            Some(&format!("Line {} of {}", line_number, path)),
+                        )
+                    })?;
+                inserted += 1;
+                pending += 1;
+
+                if top_level && batch_size > 0 && pending >= batch_size {
+                    conn.execute_batch("COMMIT; BEGIN").map_err(to_napi_error)?;
+                    pending = 0;
+                }
+            }
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                conn.execute_batch(commit).map_err(to_napi_error)?;
+                Ok(ImportResult { inserted, skipped })
+            }
+            Err(e) => {
+                conn.execute_batch(rollback).ok();
+                Err(e)
+            }
+        }
+    }
+
     // ========================================
     // Schema Introspection Methods
     // ========================================
@@ -369,10 +2112,7 @@ impl Database {
     /// Get list of all tables in the database
     #[napi]
     pub fn get_tables(&self) -> Result<Vec<String>> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "getTables")?;
         let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name").map_err(to_napi_error)?;
         let tables: Vec<String> = stmt
             .query_map([], |row| row.get(0))
@@ -385,10 +2125,7 @@ impl Database {
     /// Get column information for a table
     #[napi]
     pub fn get_columns(&self, table_name: String) -> Result<Vec<serde_json::Value>> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "getColumns")?;
         let mut stmt = conn
             .prepare(&format!("PRAGMA table_info({})", table_name))
             .map_err(to_napi_error)?;
@@ -409,13 +2146,48 @@ impl Database {
         Ok(columns)
     }
 
+    /// Get the primary key column names of a table in key order (handles composite keys)
+    #[napi]
+    pub fn primary_key_columns(&self, table_name: String) -> Result<Vec<String>> {
+        let conn = lock_connection(&self.conn, "primaryKeyColumns")?;
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk")
+            .map_err(to_napi_error)?;
+        let columns = stmt
+            .query_map([&table_name], |row| row.get(0))
+            .map_err(to_napi_error)?
+            .collect::<std::result::Result<Vec<String>, _>>()
+            .map_err(to_napi_error)?;
+        Ok(columns)
+    }
+
+    /// Get the names of generated columns (VIRTUAL or STORED) of a table
+    #[napi]
+    pub fn generated_columns(&self, table_name: String) -> Result<Vec<String>> {
+        let conn = lock_connection(&self.conn, "generatedColumns")?;
+        // table_xinfo marks virtual generated columns with hidden = 2 and stored ones with 3
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_xinfo(?) WHERE hidden IN (2, 3) ORDER BY cid")
+            .map_err(to_napi_error)?;
+        let columns = stmt
+            .query_map([&table_name], |row| row.get(0))
+            .map_err(to_napi_error)?
+            .collect::<std::result::Result<Vec<String>, _>>()
+            .map_err(to_napi_error)?;
+        Ok(columns)
+    }
+
+    /// Check if a table was declared WITHOUT ROWID, in which case `lastInsertRowid` is meaningless
+    #[napi]
+    pub fn is_without_rowid(&self, table_name: String) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "isWithoutRowid")?;
+        Self::table_is_without_rowid(&conn, &table_name)
+    }
+
     /// Get index information for a table
     #[napi]
     pub fn get_indexes(&self, table_name: String) -> Result<Vec<serde_json::Value>> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "getIndexes")?;
         let mut stmt = conn
             .prepare(&format!("PRAGMA index_list({})", table_name))
             .map_err(to_napi_error)?;
@@ -450,10 +2222,7 @@ impl Database {
     /// Get the CREATE statement for a table
     #[napi]
     pub fn get_table_sql(&self, table_name: String) -> Result<Option<String>> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "getTableSql")?;
         let mut stmt = conn
             .prepare("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
             .map_err(to_napi_error)?;
@@ -464,10 +2233,7 @@ impl Database {
     /// Export the entire schema as SQL statements
     #[napi]
     pub fn export_schema(&self) -> Result<String> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "exportSchema")?;
         let mut stmt = conn.prepare("SELECT sql FROM sqlite_master WHERE sql IS NOT NULL ORDER BY CASE WHEN type = 'table' THEN 1 WHEN type = 'index' THEN 2 ELSE 3 END, name").map_err(to_napi_error)?;
         let statements: Vec<String> = stmt
             .query_map([], |row| row.get(0))
@@ -480,54 +2246,299 @@ impl Database {
     /// Check if a table exists
     #[napi]
     pub fn table_exists(&self, table_name: String) -> Result<bool> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "tableExists")?;
         let count: i32 = conn
             .query_row(
-                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
-                [&table_name],
-                |row| row.get(0),
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+                [&table_name],
+                |row| row.get(0),
+            )
+            .map_err(to_napi_error)?;
+        Ok(count > 0)
+    }
+
+    /// Get database metadata
+    #[napi]
+    pub fn get_metadata(&self) -> Result<serde_json::Value> {
+        let conn = lock_connection(&self.conn, "getMetadata")?;
+        let table_count: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'", [], |row| row.get(0)).map_err(to_napi_error)?;
+        let index_count: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name NOT LIKE 'sqlite_%'", [], |row| row.get(0)).map_err(to_napi_error)?;
+        let page_count: i32 = conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))
+            .map_err(to_napi_error)?;
+        let page_size: i32 = conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))
+            .map_err(to_napi_error)?;
+        let version: String = conn
+            .query_row("SELECT sqlite_version()", [], |row| row.get(0))
+            .map_err(to_napi_error)?;
+        Ok(
+            serde_json::json!({ "table_count": table_count, "index_count": index_count, "page_count": page_count, "page_size": page_size, "db_size_bytes": page_count * page_size, "sqlite_version": version }),
+        )
+    }
+
+    /// Check whether SQLite's JSON functions (`json`, `json_extract`, `->>`, ...) are available
+    #[napi]
+    pub fn supports_json(&self) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "supportsJson")?;
+        // Preparing fails with "no such function" when SQLite was built without JSON support
+        let supported = conn.prepare("SELECT json('{}')").is_ok();
+        Ok(supported)
+    }
+
+    /// Get runtime memory usage of this connection (page cache, schema and statements)
+    #[napi]
+    pub fn memory_stats(&self) -> Result<serde_json::Value> {
+        use rusqlite::ffi;
+        let conn = lock_connection(&self.conn, "memoryStats")?;
+        Ok(serde_json::json!({
+            "cache_used": Self::db_status(&conn, ffi::SQLITE_DBSTATUS_CACHE_USED)?,
+            "schema_used": Self::db_status(&conn, ffi::SQLITE_DBSTATUS_SCHEMA_USED)?,
+            "stmt_used": Self::db_status(&conn, ffi::SQLITE_DBSTATUS_STMT_USED)?,
+            "cache_hit": Self::db_status(&conn, ffi::SQLITE_DBSTATUS_CACHE_HIT)?,
+            "cache_miss": Self::db_status(&conn, ffi::SQLITE_DBSTATUS_CACHE_MISS)?,
+        }))
+    }
+
+    /// Get the row count of every user table as `{ estimated, counts: { table: count } }`
+    /// By default counts are estimates read from `sqlite_stat1`, which ANALYZE fills in;
+    /// tables without statistics get null. Pass `precise` to run `COUNT(*)` on each table.
+    #[napi(ts_return_type = "{ estimated: boolean; counts: Record<string, number | null> }")]
+    pub fn row_counts(&self, options: Option<RowCountOptions>) -> Result<serde_json::Value> {
+        let precise = options.and_then(|o| o.precise).unwrap_or(false);
+        let conn = lock_connection(&self.conn, "rowCounts")?;
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+            .map_err(to_napi_error)?;
+
+        let mut counts = serde_json::Map::new();
+        if precise {
+            for table in tables {
+                let count: i64 = conn
+                    .query_row(
+                        &format!("SELECT COUNT(*) FROM {}", quote_identifier(&table)),
+                        [],
+                        |row| row.get(0),
+                    )
+                    .map_err(to_napi_error)?;
+                counts.insert(table, count.into());
+            }
+        } else {
+            // The first number of each stat row is the row count of the table or index;
+            // take the largest so partial indexes don't undercount
+            let mut estimates: HashMap<String, i64> = HashMap::new();
+            let has_stats: bool = conn
+                .query_row("SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_stat1'", [], |row| row.get(0))
+                .map_err(to_napi_error)?;
+            if has_stats {
+                let mut stmt = conn
+                    .prepare("SELECT tbl, stat FROM sqlite_stat1")
+                    .map_err(to_napi_error)?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })
+                    .map_err(to_napi_error)?;
+                for row in rows {
+                    let (table, stat) = row.map_err(to_napi_error)?;
+                    if let Some(count) = stat
+                        .split_whitespace()
+                        .next()
+                        .and_then(|n| n.parse::<i64>().ok())
+                    {
+                        let entry = estimates.entry(table).or_insert(count);
+                        *entry = (*entry).max(count);
+                    }
+                }
+            }
+            for table in tables {
+                let count = estimates
+                    .get(&table)
+                    .map_or(serde_json::Value::Null, |&n| n.into());
+                counts.insert(table, count);
+            }
+        }
+
+        Ok(serde_json::json!({
+            "estimated": !precise,
+            "counts": counts,
+        }))
+    }
+
+    /// Estimate how many rows `sql` returns from its query plan and `sqlite_stat1`, without running it
+    /// The estimate ignores GROUP BY, LIMIT and filters no index serves, and is only as good as
+    /// the last ANALYZE; plans it can't estimate (subqueries, virtual tables) return null
+    #[napi]
+    pub fn estimate_count(
+        &self,
+        env: Env,
+        sql: String,
+        params: Option<Unknown>,
+    ) -> Result<Option<i64>> {
+        let params_container = convert_params_container(&env, params)?;
+        let conn = lock_connection(&self.conn, "estimateCount")?;
+        let sql = sql.trim().trim_end_matches(';');
+        let fail = |e: rusqlite::Error| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Estimate failed: {}", sql_snippet(sql))),
+            )
+        };
+
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .map_err(fail)?;
+        let params_container = params_container.resolve_names(&stmt).map_err(fail)?;
+        params_container.bind(&mut stmt).map_err(fail)?;
+        let mut loops = Vec::new();
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next().map_err(fail)? {
+            // Only loops of the outermost query multiply into its row count
+            if row.get::<_, i64>(1).map_err(fail)? == 0 {
+                loops.push(row.get::<_, String>(3).map_err(fail)?);
+            }
+        }
+        drop(rows);
+        drop(stmt);
+
+        let stats = Self::stat1_rows(&conn).map_err(fail)?;
+        let mut estimate = 1.0;
+        for detail in &loops {
+            match Self::plan_loop_estimate(&conn, sql, &stats, detail).map_err(fail)? {
+                Some(rows) => estimate *= rows,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(estimate.round() as i64))
+    }
+
+    /// Warm the page cache by reading every b-tree page, or only those of `warmTables`
+    /// Returns the number of pages touched; pages beyond `cache_size` are evicted again
+    #[napi]
+    pub fn preload(&self, options: Option<PreloadOptions>) -> Result<u32> {
+        let conn = lock_connection(&self.conn, "preload")?;
+        // dbstat visits every page of the b-trees it reports on, pulling them into the cache
+        let pages: i64 = match options.and_then(|o| o.warm_tables) {
+            None => conn
+                .query_row("SELECT COUNT(*) FROM dbstat", [], |row| row.get(0))
+                .map_err(to_napi_error)?,
+            Some(tables) => {
+                let mut total = 0;
+                for table in tables.iter() {
+                    let exists: bool = conn
+                        .query_row(
+                            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?",
+                            [table],
+                            |row| row.get(0),
+                        )
+                        .map_err(to_napi_error)?;
+                    if !exists {
+                        return Err(Error::from_reason(format!(
+                            "Cannot preload missing table '{}'",
+                            table
+                        )));
+                    }
+                    let count: i64 = conn
+                        .query_row(
+                            "SELECT COUNT(*) FROM dbstat WHERE name = ?1 OR name IN (SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = ?1)",
+                            [table],
+                            |row| row.get(0),
+                        )
+                        .map_err(|e| {
+                            crate::error::to_napi_error_with_context(
+                                e,
+                                Some(&format!("Preloading {} failed", table)),
+                            )
+                        })?;
+                    total += count;
+                }
+                total
+            }
+        };
+        Ok(pages as u32)
+    }
+
+    /// Run VACUUM only if more than `freelist_threshold` (0 to 1) of the pages are free
+    /// Returns whether it vacuumed; VACUUM can't run inside a transaction
+    #[napi]
+    pub fn auto_vacuum_if_needed(&self, freelist_threshold: f64) -> Result<bool> {
+        if !(0.0..=1.0).contains(&freelist_threshold) {
+            return Err(Error::from_reason(format!(
+                "freelistThreshold must be between 0 and 1, got {}",
+                freelist_threshold
+            )));
+        }
+        let conn = lock_connection(&self.conn, "autoVacuumIfNeeded")?;
+        let (free_pages, pages): (i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT freelist_count FROM pragma_freelist_count), (SELECT page_count FROM pragma_page_count)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .map_err(to_napi_error)?;
-        Ok(count > 0)
+        if pages == 0 || (free_pages as f64 / pages as f64) <= freelist_threshold {
+            return Ok(false);
+        }
+        conn.execute_batch("VACUUM")
+            .map_err(|e| crate::error::to_napi_error_with_context(e, Some("VACUUM failed")))?;
+        Ok(true)
     }
 
-    /// Get database metadata
+    /// Rebuild indexes with REINDEX: all of them, or those of one index, table or collation
+    /// Needed after a collation's ordering changes, since indexes built with the old one are stale
     #[napi]
-    pub fn get_metadata(&self) -> Result<serde_json::Value> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        let table_count: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'", [], |row| row.get(0)).map_err(to_napi_error)?;
-        let index_count: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name NOT LIKE 'sqlite_%'", [], |row| row.get(0)).map_err(to_napi_error)?;
-        let page_count: i32 = conn
-            .query_row("PRAGMA page_count", [], |row| row.get(0))
-            .map_err(to_napi_error)?;
-        let page_size: i32 = conn
-            .query_row("PRAGMA page_size", [], |row| row.get(0))
-            .map_err(to_napi_error)?;
-        let version: String = conn
-            .query_row("SELECT sqlite_version()", [], |row| row.get(0))
-            .map_err(to_napi_error)?;
-        Ok(
-            serde_json::json!({ "table_count": table_count, "index_count": index_count, "page_count": page_count, "page_size": page_size, "db_size_bytes": page_count * page_size, "sqlite_version": version }),
-        )
+    pub fn reindex(&self, target: Option<String>) -> Result<()> {
+        let sql = match &target {
+            Some(name) => {
+                validate_identifier(name)?;
+                format!("REINDEX {}", quote_identifier(name))
+            }
+            None => "REINDEX".to_string(),
+        };
+        let conn = lock_connection(&self.conn, "reindex")?;
+        conn.execute_batch(&sql).map_err(|e| {
+            crate::error::to_napi_error_with_context(e, Some(&format!("{} failed", sql)))
+        })
+    }
+
+    /// Get the current value of a run-time limit such as "SQL_LENGTH" or "EXPR_DEPTH"
+    #[napi]
+    pub fn get_limit(&self, category: String) -> Result<i32> {
+        let limit = Self::parse_limit(&category)?;
+        let conn = lock_connection(&self.conn, "getLimit")?;
+        conn.limit(limit).map_err(to_napi_error)
+    }
+
+    /// Set a run-time limit such as "SQL_LENGTH" or "EXPR_DEPTH" and return the previous value
+    /// Values above the compile-time maximum are silently truncated by SQLite
+    #[napi]
+    pub fn set_limit(&self, category: String, value: i32) -> Result<i32> {
+        let limit = Self::parse_limit(&category)?;
+        let conn = lock_connection(&self.conn, "setLimit")?;
+        conn.set_limit(limit, value).map_err(to_napi_error)
     }
 
     /// Close the database connection
+    /// Throws while a transaction is open, unless `force` is set, which rolls it back first
     #[napi]
-    pub fn close(&self) -> Result<()> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+    pub fn close(&self, options: Option<CloseOptions>) -> Result<CloseResult> {
+        let conn = lock_connection(&self.conn, "close")?;
+        let rolled_back = !conn.is_autocommit();
+        if rolled_back {
+            if !options.and_then(|o| o.force).unwrap_or(false) {
+                return Err(Error::from_reason(
+                    "Cannot close the database while a transaction is open; commit or roll it back first, or pass { force: true }",
+                ));
+            }
+            conn.execute_batch("ROLLBACK").map_err(to_napi_error)?;
+            self.in_transaction
+                .store(false, std::sync::atomic::Ordering::SeqCst);
+        }
         conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)").ok();
         drop(conn);
         self.closed.store(true, std::sync::atomic::Ordering::SeqCst);
-        Ok(())
+        Ok(CloseResult { rolled_back })
     }
 
     /// Check if the database connection is closed
@@ -537,10 +2548,32 @@ impl Database {
     }
 
     /// Check if currently in a transaction
+    /// Reflects SQLite's own state, so `BEGIN`/`COMMIT` run through `exec` are seen too
     #[napi]
-    pub fn in_transaction(&self) -> bool {
-        self.in_transaction
-            .load(std::sync::atomic::Ordering::SeqCst)
+    pub fn in_transaction(&self) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "inTransaction")?;
+        Ok(self.sync_transaction_flag(&conn))
+    }
+
+    /// Check if the connection is in autocommit mode (no transaction is open)
+    #[napi]
+    pub fn is_autocommit(&self) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "isAutocommit")?;
+        Ok(conn.is_autocommit())
+    }
+
+    /// Check whether a schema ("main" by default, or an attached database) is read-only
+    /// Throws if no database is attached under `schema`
+    #[napi]
+    pub fn is_readonly(&self, schema: Option<String>) -> Result<bool> {
+        let schema = schema.unwrap_or_else(|| "main".to_string());
+        let conn = lock_connection(&self.conn, "isReadonly")?;
+        conn.is_readonly(schema.as_str()).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Unknown schema '{}'", schema)),
+            )
+        })
     }
 
     /// Get the database filename/path
@@ -549,6 +2582,48 @@ impl Database {
         self.filename.clone()
     }
 
+    /// Open a second, read-only connection to the same database file
+    /// Reads on the clone don't contend with this connection's lock. Only the path and the
+    /// result options are shared; custom functions, hooks and limits are not carried over.
+    #[napi]
+    pub fn clone_readonly(&self) -> Result<Database> {
+        if self.filename == ":memory:" {
+            return Err(Error::from_reason(
+                "Cannot open a read-only clone of an in-memory database",
+            ));
+        }
+        if self.filename.is_empty() {
+            return Err(Error::from_reason(
+                "Cannot open a read-only clone of a temporary database",
+            ));
+        }
+
+        let mut flags = OpenFlags::SQLITE_OPEN_READ_ONLY;
+        if self.filename.starts_with("file:") {
+            flags.insert(OpenFlags::SQLITE_OPEN_URI);
+        }
+        let conn = Connection::open_with_flags(&self.filename, flags).map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!(
+                    "Failed to open read-only clone of {}",
+                    self.filename
+                )),
+            )
+        })?;
+        conn.execute_batch("PRAGMA extended_result_codes = ON")
+            .map_err(to_napi_error)?;
+
+        let text_error_mode = *lock_with_context(&self.text_error_mode, "cloneReadonly");
+        Ok(Database::from_connection(
+            conn,
+            self.filename.clone(),
+            self.blob_encoding,
+            text_error_mode,
+            self.max_rows,
+        ))
+    }
+
     // ========================================
     // Safe Schema Helpers (for idempotent migrations)
     // ========================================
@@ -557,10 +2632,7 @@ impl Database {
     /// Returns true if created, false if already existed
     #[napi]
     pub fn create_table_if_not_exists(&self, sql: String) -> Result<bool> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "createTableIfNotExists")?;
         let table_name = Self::extract_table_name(&sql)?;
         let exists: i32 = conn
             .query_row(
@@ -585,10 +2657,7 @@ impl Database {
         column_name: String,
         column_def: String,
     ) -> Result<bool> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "addColumnIfNotExists")?;
         let mut stmt = conn
             .prepare(&format!("PRAGMA table_info({})", table_name))
             .map_err(to_napi_error)?;
@@ -611,10 +2680,7 @@ impl Database {
     /// Run SQL safely - returns success without throwing if table/column already exists
     #[napi]
     pub fn run_safe(&self, sql: String, ignore_errors: Option<Vec<String>>) -> Result<bool> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "runSafe")?;
         let result = conn.execute_batch(&sql);
         match result {
             Ok(_) => Ok(true),
@@ -639,10 +2705,7 @@ impl Database {
     /// Get the current schema version
     #[napi]
     pub fn get_schema_version(&self) -> Result<u32> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "getSchemaVersion")?;
         let table_exists: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_schema_version'", [], |row| row.get(0)).map_err(to_napi_error)?;
         if table_exists == 0 {
             return Ok(0);
@@ -661,15 +2724,63 @@ impl Database {
     /// Set the schema version
     #[napi]
     pub fn set_schema_version(&self, version: u32) -> Result<()> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "setSchemaVersion")?;
         conn.execute("CREATE TABLE IF NOT EXISTS _schema_version (version INTEGER PRIMARY KEY, applied_at TEXT NOT NULL DEFAULT (datetime('now')), description TEXT)", []).map_err(to_napi_error)?;
         conn.execute("INSERT OR REPLACE INTO _schema_version (version, description, applied_at) VALUES (?, ?, datetime('now'))", [&version.to_string(), "manual"]).map_err(to_napi_error)?;
         Ok(())
     }
 
+    /// Get SQLite's built-in `PRAGMA user_version`, which is separate from the
+    /// `_schema_version` table used by `getSchemaVersion`/`migrate`
+    #[napi]
+    pub fn get_user_version(&self) -> Result<i32> {
+        let conn = lock_connection(&self.conn, "getUserVersion")?;
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+
+    /// Set SQLite's built-in `PRAGMA user_version`
+    #[napi]
+    pub fn set_user_version(&self, version: i32) -> Result<()> {
+        let conn = lock_connection(&self.conn, "setUserVersion")?;
+        conn.pragma_update(None, "user_version", version)
+            .map_err(to_napi_error)
+    }
+
+    /// Get `PRAGMA application_id`, the 32-bit number identifying the file format built on SQLite
+    #[napi]
+    pub fn get_application_id(&self) -> Result<i32> {
+        let conn = lock_connection(&self.conn, "getApplicationId")?;
+        conn.pragma_query_value(None, "application_id", |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+
+    /// Set `PRAGMA application_id`
+    #[napi]
+    pub fn set_application_id(&self, id: i32) -> Result<()> {
+        let conn = lock_connection(&self.conn, "setApplicationId")?;
+        conn.pragma_update(None, "application_id", id)
+            .map_err(to_napi_error)
+    }
+
+    /// Get `PRAGMA data_version`, which changes whenever another connection commits
+    /// Compare it between calls to cheaply detect external modifications
+    #[napi]
+    pub fn data_version(&self) -> Result<i32> {
+        let conn = lock_connection(&self.conn, "dataVersion")?;
+        conn.pragma_query_value(None, "data_version", |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+
+    /// Override the value `lastInsertRowid` reports until the next successful INSERT
+    /// Inserts made by triggers never leak out: SQLite restores the value when a trigger exits
+    #[napi]
+    pub fn set_last_insert_rowid(&self, rowid: i64) -> Result<()> {
+        let conn = lock_connection(&self.conn, "setLastInsertRowid")?;
+        unsafe { rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), rowid) };
+        Ok(())
+    }
+
     /// Initialize the database with a schema
     #[napi]
     pub fn init_schema(
@@ -678,10 +2789,7 @@ impl Database {
         version: Option<u32>,
         description: Option<String>,
     ) -> Result<u32> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "initSchema")?;
         let ver = version.unwrap_or(1);
         conn.execute("BEGIN IMMEDIATE", []).map_err(to_napi_error)?;
         if let Err(e) = conn.execute_batch(&schema) {
@@ -699,12 +2807,11 @@ impl Database {
     }
 
     /// Migrate the database to a new schema version
+    /// Migrations run in one transaction, except `transactional: false` ones, which commit the
+    /// work before them and run on their own
     #[napi]
     pub fn migrate(&self, migrations: Vec<Migration>, target_version: Option<u32>) -> Result<u32> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "migrate")?;
         let current_version = {
             let table_exists: i32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_schema_version'", [], |row| row.get(0)).unwrap_or(0);
             if table_exists == 0 {
@@ -719,50 +2826,127 @@ impl Database {
             }
         };
         let mut sorted_migrations = migrations;
-        sorted_migrations.sort_by(|a, b| a.version.cmp(&b.version));
+        sorted_migrations.sort_by_key(|m| m.version);
         let target = target_version
             .unwrap_or_else(|| sorted_migrations.last().map(|m| m.version).unwrap_or(1));
         if current_version >= target {
             return Ok(current_version);
         }
+        let commit = || {
+            conn.execute("COMMIT", []).map_err(|e| {
+                conn.execute("ROLLBACK", []).ok();
+                to_napi_error(e)
+            })
+        };
         conn.execute("BEGIN IMMEDIATE", []).map_err(to_napi_error)?;
         conn.execute("CREATE TABLE IF NOT EXISTS _schema_version (version INTEGER PRIMARY KEY, applied_at TEXT NOT NULL DEFAULT (datetime('now')), description TEXT)", []).map_err(to_napi_error)?;
+        // Versions up to `committed_version` are applied and recorded even if a later one fails
+        let mut committed_version = current_version;
         let mut new_version = current_version;
         for migration in sorted_migrations.iter() {
             if migration.version > current_version && migration.version <= target {
+                let transactional = migration.transactional.unwrap_or(true);
+                if !transactional {
+                    // Commit what came before so the migration runs outside any transaction
+                    commit()?;
+                    committed_version = new_version;
+                }
                 if let Err(e) = conn.execute_batch(&migration.sql) {
-                    conn.execute("ROLLBACK", []).ok();
+                    let applied = if transactional {
+                        conn.execute("ROLLBACK", []).ok();
+                        String::new()
+                    } else {
+                        " outside a transaction; statements before the failing one stay applied"
+                            .to_string()
+                    };
+                    let kept = if committed_version > current_version {
+                        format!(" (the database stays at version {})", committed_version)
+                    } else {
+                        String::new()
+                    };
                     return Err(Error::from_reason(format!(
-                        "Migration {} failed: {}",
-                        migration.version, e
+                        "Migration {} failed{}: {}{}",
+                        migration.version, applied, e, kept
                     )));
                 }
                 let desc = migration
                     .description
                     .clone()
                     .unwrap_or_else(|| format!("migration to v{}", migration.version));
-                conn.execute("INSERT OR REPLACE INTO _schema_version (version, description, applied_at) VALUES (?, ?, datetime('now'))", [&migration.version.to_string(), &desc]).map_err(to_napi_error)?;
+                conn.execute("INSERT OR REPLACE INTO _schema_version (version, description, applied_at) VALUES (?, ?, datetime('now'))", [&migration.version.to_string(), &desc]).map_err(|e| {
+                    if transactional {
+                        conn.execute("ROLLBACK", []).ok();
+                    }
+                    to_napi_error(e)
+                })?;
                 new_version = migration.version;
+                if !transactional {
+                    committed_version = new_version;
+                    conn.execute("BEGIN IMMEDIATE", []).map_err(to_napi_error)?;
+                }
             }
         }
-        conn.execute("COMMIT", []).map_err(|e| {
-            conn.execute("ROLLBACK", []).ok();
-            to_napi_error(e)
-        })?;
+        commit()?;
         Ok(new_version)
     }
 
+    /// Run migrations read from files named like `0003_add_users.sql`, as `migrate` does
+    /// Versions and descriptions come from the filenames; bad names and duplicate versions throw
+    #[napi]
+    pub fn migrate_from_entries(
+        &self,
+        entries: Vec<MigrationEntry>,
+        target_version: Option<u32>,
+    ) -> Result<u32> {
+        let mut seen: HashMap<u32, String> = HashMap::new();
+        let mut migrations = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let (version, description) = parse_migration_filename(&entry.filename)?;
+            if let Some(other) = seen.insert(version, entry.filename.clone()) {
+                return Err(Error::from_reason(format!(
+                    "Migration version {} appears in both '{}' and '{}'",
+                    version, other, entry.filename
+                )));
+            }
+            migrations.push(Migration {
+                version,
+                sql: entry.sql,
+                description,
+                transactional: None,
+            });
+        }
+        self.migrate(migrations, target_version)
+    }
+
     // ========================================
     // Custom Functions and Collations
     // ========================================
 
+    /// Register a custom SQL function
+    /// It is non-deterministic and variadic unless `options` says otherwise
     #[napi]
-    pub fn create_function(&self, _env: Env, name: String, _func: Function) -> Result<()> {
+    pub fn create_function(
+        &self,
+        _env: Env,
+        name: String,
+        _func: Function,
+        options: Option<FunctionOptions>,
+    ) -> Result<()> {
+        use rusqlite::functions::FunctionFlags;
+
+        let arg_count = options.as_ref().and_then(|o| o.arg_count).unwrap_or(-1);
+        let deterministic = options.as_ref().and_then(|o| o.deterministic);
+        let direct_only = options.as_ref().and_then(|o| o.direct_only);
+        let mut flags = FunctionFlags::SQLITE_UTF8;
+        if deterministic.unwrap_or(false) {
+            flags |= FunctionFlags::SQLITE_DETERMINISTIC;
+        }
+        if direct_only.unwrap_or(false) {
+            flags |= FunctionFlags::SQLITE_DIRECTONLY;
+        }
         let functions = self.functions.clone();
         {
-            let funcs = functions
-                .lock()
-                .map_err(|_| Error::from_reason("Lock failed"))?;
+            let funcs = lock_with_context(&functions, "createFunction");
             if funcs.contains_key(&name) {
                 return Err(Error::from_reason(format!(
                     "Function '{}' already exists",
@@ -770,22 +2954,24 @@ impl Database {
                 )));
             }
         }
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "createFunction")?;
         conn.create_scalar_function(
             name.as_str(),
-            -1,
-            rusqlite::functions::FunctionFlags::SQLITE_UTF8
-                | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+            arg_count,
+            flags,
             |_ctx: &rusqlite::functions::Context| Ok(rusqlite::types::Value::Null),
         )
-        .map_err(to_napi_error)?;
-        let mut funcs = functions
-            .lock()
-            .map_err(|_| Error::from_reason("Lock failed"))?;
-        funcs.insert(name, true);
+        .map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!(
+                    "Failed to register function '{}' with {} arguments",
+                    name, arg_count
+                )),
+            )
+        })?;
+        let mut funcs = lock_with_context(&functions, "createFunction");
+        funcs.insert(name, arg_count);
         Ok(())
     }
 
@@ -793,9 +2979,7 @@ impl Database {
     pub fn create_collation(&self, _env: Env, name: String, _compare_fn: Function) -> Result<()> {
         let collations = self.collations.clone();
         {
-            let colls = collations
-                .lock()
-                .map_err(|_| Error::from_reason("Lock failed"))?;
+            let colls = lock_with_context(&collations, "createCollation");
             if colls.contains_key(&name) {
                 return Err(Error::from_reason(format!(
                     "Collation '{}' already exists",
@@ -803,29 +2987,305 @@ impl Database {
                 )));
             }
         }
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "createCollation")?;
         conn.create_collation(name.as_str(), |a: &str, b: &str| a.cmp(b))
             .map_err(to_napi_error)?;
-        let mut colls = collations
-            .lock()
-            .map_err(|_| Error::from_reason("Lock failed"))?;
+        let mut colls = lock_with_context(&collations, "createCollation");
         colls.insert(name, true);
         Ok(())
     }
 
+    /// Unregister a custom SQL function so its name can be registered again
+    #[napi]
+    pub fn remove_function(&self, name: String) -> Result<()> {
+        let conn = lock_connection(&self.conn, "removeFunction")?;
+        let mut funcs = lock_with_context(&self.functions, "removeFunction");
+        let arg_count = *funcs
+            .get(&name)
+            .ok_or_else(|| Error::from_reason(format!("Function '{}' is not registered", name)))?;
+        conn.remove_function(name.as_str(), arg_count)
+            .map_err(to_napi_error)?;
+        funcs.remove(&name);
+        Ok(())
+    }
+
+    /// Unregister a custom collation so its name can be registered again
+    #[napi]
+    pub fn remove_collation(&self, name: String) -> Result<()> {
+        let conn = lock_connection(&self.conn, "removeCollation")?;
+        let mut colls = lock_with_context(&self.collations, "removeCollation");
+        if !colls.contains_key(&name) {
+            return Err(Error::from_reason(format!(
+                "Collation '{}' is not registered",
+                name
+            )));
+        }
+        conn.remove_collation(name.as_str())
+            .map_err(to_napi_error)?;
+        colls.remove(&name);
+        Ok(())
+    }
+
+    /// List the names of registered custom SQL functions, sorted
+    /// With `includeBuiltin`, list every function the connection knows (`PRAGMA function_list`)
+    #[napi]
+    pub fn list_functions(&self, include_builtin: Option<bool>) -> Result<Vec<String>> {
+        if include_builtin.unwrap_or(false) {
+            return self.pragma_names(
+                "SELECT DISTINCT name FROM pragma_function_list ORDER BY name",
+                "listFunctions",
+            );
+        }
+        let funcs = lock_with_context(&self.functions, "listFunctions");
+        let mut names: Vec<String> = funcs.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// List the names of registered custom collations, sorted
+    /// With `includeBuiltin`, also list BINARY, NOCASE and RTRIM (`PRAGMA collation_list`),
+    /// leaving out removed collations that the pragma still reports
+    #[napi]
+    pub fn list_collations(&self, include_builtin: Option<bool>) -> Result<Vec<String>> {
+        if include_builtin.unwrap_or(false) {
+            let names = self.pragma_names(
+                "SELECT name FROM pragma_collation_list ORDER BY name",
+                "listCollations",
+            )?;
+            let conn = lock_connection(&self.conn, "listCollations")?;
+            return Ok(names
+                .into_iter()
+                .filter(|name| Self::collation_resolves(&conn, name))
+                .collect());
+        }
+        let colls = lock_with_context(&self.collations, "listCollations");
+        let mut names: Vec<String> = colls.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Whether `COLLATE name` would resolve, for a built-in or registered collation
+    /// A comparison is prepared rather than reading `PRAGMA collation_list`, which keeps
+    /// listing collations after `removeCollation`
+    #[napi]
+    pub fn has_collation(&self, name: String) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "hasCollation")?;
+        Ok(Self::collation_resolves(&conn, &name))
+    }
+
+    /// Whether a comparison using `COLLATE name` prepares on `conn`
+    fn collation_resolves(conn: &Connection, name: &str) -> bool {
+        let sql = format!(
+            "SELECT 1 WHERE NULL = NULL COLLATE {}",
+            quote_identifier(name)
+        );
+        conn.prepare(&sql).is_ok()
+    }
+
+    /// Take over `name` for a function implemented here, unregistering any `createFunction`
+    /// stub of that name with a different arity, which SQLite would otherwise keep as an overload
+    /// The caller registers the implementation and tracks it under `name` afterwards
+    fn take_function_name(
+        conn: &Connection,
+        funcs: &mut HashMap<String, i32>,
+        name: &str,
+        arg_count: i32,
+    ) -> Result<()> {
+        let existing = funcs
+            .iter()
+            .find(|(registered, _)| registered.eq_ignore_ascii_case(name))
+            .map(|(registered, &count)| (registered.clone(), count));
+        if let Some((registered, count)) = existing {
+            if count != arg_count {
+                conn.remove_function(registered.as_str(), count)
+                    .map_err(to_napi_error)?;
+            }
+            funcs.remove(&registered);
+        }
+        Ok(())
+    }
+
+    /// Register a `regexp(pattern, text)` function backing SQLite's `text REGEXP pattern` operator
+    /// Compiled patterns are cached per statement, so a constant pattern is only compiled once
+    /// A `regexp` registered with `createFunction` is replaced
+    #[napi]
+    pub fn enable_regexp(&self) -> Result<()> {
+        let conn = lock_connection(&self.conn, "enableRegexp")?;
+        let mut funcs = lock_with_context(&self.functions, "enableRegexp");
+        Self::take_function_name(&conn, &mut funcs, "regexp", 2)?;
+        conn.create_scalar_function(
+            "regexp",
+            2,
+            rusqlite::functions::FunctionFlags::SQLITE_UTF8
+                | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx: &rusqlite::functions::Context| {
+                use rusqlite::types::ValueRef;
+                if matches!(ctx.get_raw(0), ValueRef::Null) {
+                    return Ok(None);
+                }
+                let text = match ctx.get_raw(1) {
+                    ValueRef::Null => return Ok(None),
+                    ValueRef::Integer(i) => i.to_string(),
+                    ValueRef::Real(f) => f.to_string(),
+                    ValueRef::Text(t) | ValueRef::Blob(t) => {
+                        String::from_utf8_lossy(t).into_owned()
+                    }
+                };
+                let re = ctx.get_or_create_aux(
+                    0,
+                    |pattern| -> std::result::Result<
+                        regex::Regex,
+                        Box<dyn std::error::Error + Send + Sync>,
+                    > { Ok(regex::Regex::new(pattern.as_str()?)?) },
+                )?;
+                Ok(Some(re.is_match(&text)))
+            },
+        )
+        .map_err(to_napi_error)?;
+        funcs.insert("regexp".to_string(), 2);
+        Ok(())
+    }
+
+    /// Register `sha256(x)`, `md5(x)` and `crc32(x)` returning lowercase hex,
+    /// plus `sha256_blob`, `md5_blob` and `crc32_blob` returning the raw digest
+    #[napi]
+    pub fn enable_crypto_functions(&self) -> Result<()> {
+        let conn = lock_connection(&self.conn, "enableCryptoFunctions")?;
+        let mut funcs = lock_with_context(&self.functions, "enableCryptoFunctions");
+        let flags = rusqlite::functions::FunctionFlags::SQLITE_UTF8
+            | rusqlite::functions::FunctionFlags::SQLITE_DETERMINISTIC;
+        let digests: [(&str, crate::hash::DigestFn); 3] = [
+            ("sha256", crate::hash::sha256),
+            ("md5", crate::hash::md5),
+            ("crc32", crate::hash::crc32),
+        ];
+        for (name, digest) in digests {
+            let blob_name = format!("{}_blob", name);
+            Self::take_function_name(&conn, &mut funcs, name, 1)?;
+            Self::take_function_name(&conn, &mut funcs, &blob_name, 1)?;
+            conn.create_scalar_function(name, 1, flags, move |ctx| {
+                Ok(Self::hash_argument(ctx)?.map(|data| crate::hash::to_hex(&digest(&data))))
+            })
+            .map_err(to_napi_error)?;
+            conn.create_scalar_function(blob_name.as_str(), 1, flags, move |ctx| {
+                Ok(Self::hash_argument(ctx)?.map(|data| digest(&data)))
+            })
+            .map_err(to_napi_error)?;
+            funcs.insert(name.to_string(), 1);
+            funcs.insert(blob_name, 1);
+        }
+        Ok(())
+    }
+
+    // ========================================
+    // Hooks
+    // ========================================
+
+    /// Install an authorizer called while statements are prepared, e.g. to sandbox untrusted SQL
+    /// The callback receives (action, arg1, arg2, database, accessor) and returns "allow", "deny" or "ignore"
+    /// It runs synchronously with the connection locked, so it must not use this Database
+    #[napi(
+        ts_args_type = "callback: (action: string, arg1: string | null, arg2: string | null, database: string | null, accessor: string | null) => 'allow' | 'deny' | 'ignore'"
+    )]
+    pub fn set_authorizer(
+        &self,
+        env: Env,
+        callback: Function<AuthorizerArgs, String>,
+    ) -> Result<()> {
+        let callback = JsCallback::new(&env, &callback)?;
+        let conn = lock_connection(&self.conn, "setAuthorizer")?;
+        conn.authorizer(Some(authorizer(callback)))
+            .map_err(to_napi_error)
+    }
+
+    /// Remove the authorizer installed by `setAuthorizer`
+    #[napi]
+    pub fn clear_authorizer(&self) -> Result<()> {
+        let conn = lock_connection(&self.conn, "clearAuthorizer")?;
+        conn.authorizer(
+            None::<fn(rusqlite::hooks::AuthContext<'_>) -> rusqlite::hooks::Authorization>,
+        )
+        .map_err(to_napi_error)
+    }
+
+    /// Call `callback` with `{ sql, durationMicros }` whenever a statement run through
+    /// `run`, `exec` or a `Statement` takes at least `thresholdMicros`
+    /// The callback runs after the connection is unlocked, so it may use this Database;
+    /// slow statements it runs itself are not reported again
+    #[napi(ts_args_type = "thresholdMicros: number, callback: (query: SlowQuery) => void")]
+    pub fn on_slow_query(
+        &self,
+        env: Env,
+        threshold_micros: u32,
+        callback: Function<SlowQuery, ()>,
+    ) -> Result<()> {
+        let log = SlowQueryLog::new(
+            std::time::Duration::from_micros(threshold_micros as u64),
+            JsCallback::new(&env, &callback)?,
+        );
+        *lock_with_context(&self.slow_query_log, "onSlowQuery") = Some(Arc::new(log));
+        Ok(())
+    }
+
+    /// Remove the callback installed by `onSlowQuery`
+    #[napi]
+    pub fn clear_slow_query(&self) {
+        *lock_with_context(&self.slow_query_log, "clearSlowQuery") = None;
+    }
+
+    /// Call `callback` with the SQL of every statement this connection runs, with bound
+    /// parameters expanded; trigger bodies are reported as `--` comments (`-- TRIGGER name`, ...)
+    /// Calls are queued and delivered after the current JS task, so the callback may use this Database
+    #[napi(ts_args_type = "callback: (sql: string) => void")]
+    pub fn set_trace(&self, callback: Function<String, ()>) -> Result<()> {
+        let callback = callback
+            .build_threadsafe_function()
+            .callee_handled::<false>()
+            .weak::<true>()
+            .build()?;
+        lock_with_context(&self.trace, "setTrace").update("setTrace", |callbacks| {
+            callbacks.statement = Some(callback);
+        })
+    }
+
+    /// Remove the callback installed by `setTrace`
+    #[napi]
+    pub fn clear_trace(&self) -> Result<()> {
+        lock_with_context(&self.trace, "clearTrace").update("clearTrace", |callbacks| {
+            callbacks.statement = None;
+        })
+    }
+
+    /// Call `callback` with `{ sql, nanos }` after every statement this connection runs,
+    /// using SQLite's own timing of the statement
+    /// Calls are queued like `setTrace`'s, so the callback may use this Database
+    #[napi(ts_args_type = "callback: (profile: StatementProfile) => void")]
+    pub fn set_profiler(&self, callback: Function<StatementProfile, ()>) -> Result<()> {
+        let callback: ProfileFn = callback
+            .build_threadsafe_function()
+            .callee_handled::<false>()
+            .weak::<true>()
+            .build()?;
+        lock_with_context(&self.trace, "setProfiler").update("setProfiler", |callbacks| {
+            callbacks.profile = Some(callback);
+        })
+    }
+
+    /// Remove the callback installed by `setProfiler`
+    #[napi]
+    pub fn clear_profiler(&self) -> Result<()> {
+        lock_with_context(&self.trace, "clearProfiler").update("clearProfiler", |callbacks| {
+            callbacks.profile = None;
+        })
+    }
+
     // ========================================
     // Pragma Convenience Methods
     // ========================================
 
     #[napi]
     pub fn pragma(&self, name: String, value: Option<Unknown>) -> Result<serde_json::Value> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "pragma")?;
         if let Some(val) = value {
             let env = Env::from_raw(val.env());
             let params_container = convert_params_container(&env, Some(val))?;
@@ -845,14 +3305,20 @@ impl Database {
                                             row.get(0)
                                         })
                                         .map_err(|e| {
-                                            crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                            crate::error::to_napi_error_with_context(
+                                                e,
+                                                Some(&format!("Pragma set failed: {}", name)),
+                                            )
                                         })?;
                                     return Ok(serde_json::Value::Number(result.into()));
                                 }
                                 // Execute the pragma (integer pragmas don't return results)
                                 conn.execute(&format!("PRAGMA {} = {}", name, i), [])
                                     .map_err(|e| {
-                                        crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                        crate::error::to_napi_error_with_context(
+                                            e,
+                                            Some(&format!("Pragma set failed: {}", name)),
+                                        )
                                     })?;
                             }
                             crate::db::Param::Text(s) => {
@@ -862,7 +3328,10 @@ impl Database {
                                         row.get(0)
                                     })
                                     .map_err(|e| {
-                                        crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                        crate::error::to_napi_error_with_context(
+                                            e,
+                                            Some(&format!("Pragma set failed: {}", name)),
+                                        )
                                     })?;
                                 return Ok(serde_json::Value::String(result));
                             }
@@ -874,12 +3343,18 @@ impl Database {
                                 {
                                     conn.execute(&format!("PRAGMA {} = {}", name, *f as i64), [])
                                         .map_err(|e| {
-                                            crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                            crate::error::to_napi_error_with_context(
+                                                e,
+                                                Some(&format!("Pragma set failed: {}", name)),
+                                            )
                                         })?;
                                 } else {
                                     conn.execute(&format!("PRAGMA {} = {}", name, *f), [])
                                         .map_err(|e| {
-                                            crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                            crate::error::to_napi_error_with_context(
+                                                e,
+                                                Some(&format!("Pragma set failed: {}", name)),
+                                            )
                                         })?;
                                 }
                             }
@@ -899,7 +3374,10 @@ impl Database {
                             crate::db::Param::Int(i) => {
                                 conn.execute(&format!("PRAGMA {} = {}", name, i), [])
                                     .map_err(|e| {
-                                        crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                        crate::error::to_napi_error_with_context(
+                                            e,
+                                            Some(&format!("Pragma set failed: {}", name)),
+                                        )
                                     })?;
                             }
                             crate::db::Param::Text(s) => {
@@ -908,7 +3386,10 @@ impl Database {
                                         row.get(0)
                                     })
                                     .map_err(|e| {
-                                        crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                        crate::error::to_napi_error_with_context(
+                                            e,
+                                            Some(&format!("Pragma set failed: {}", name)),
+                                        )
                                     })?;
                                 return Ok(serde_json::Value::String(result));
                             }
@@ -916,12 +3397,18 @@ impl Database {
                                 if *f == f.floor() && f.abs() < (i64::MAX as f64) {
                                     conn.execute(&format!("PRAGMA {} = {}", name, *f as i64), [])
                                         .map_err(|e| {
-                                            crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                            crate::error::to_napi_error_with_context(
+                                                e,
+                                                Some(&format!("Pragma set failed: {}", name)),
+                                            )
                                         })?;
                                 } else {
                                     conn.execute(&format!("PRAGMA {} = {}", name, *f), [])
                                         .map_err(|e| {
-                                            crate::error::to_napi_error_with_context(e, Some(&format!("Pragma set failed: {}", name)))
+                                            crate::error::to_napi_error_with_context(
+                                                e,
+                                                Some(&format!("Pragma set failed: {}", name)),
+                                            )
                                         })?;
                                 }
                             }
@@ -936,11 +3423,12 @@ impl Database {
             }
 
             // Read back the pragma value after setting it
-            let mut stmt = conn
-                .prepare(&format!("PRAGMA {}", name))
-                .map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Pragma read failed: {}", name)))
-                })?;
+            let mut stmt = conn.prepare(&format!("PRAGMA {}", name)).map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Pragma read failed: {}", name)),
+                )
+            })?;
             let results: Vec<serde_json::Value> = stmt
                 .query_map([], |row| {
                     let val: std::result::Result<String, _> = row.get(0);
@@ -956,7 +3444,10 @@ impl Database {
                     }
                 })
                 .map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Pragma read failed: {}", name)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Pragma read failed: {}", name)),
+                    )
                 })?
                 .filter_map(|r| r.ok())
                 .collect();
@@ -968,11 +3459,12 @@ impl Database {
                 Ok(serde_json::Value::Array(results))
             }
         } else {
-            let mut stmt = conn
-                .prepare(&format!("PRAGMA {}", name))
-                .map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Pragma read failed: {}", name)))
-                })?;
+            let mut stmt = conn.prepare(&format!("PRAGMA {}", name)).map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Pragma read failed: {}", name)),
+                )
+            })?;
             let results: Vec<serde_json::Value> = stmt
                 .query_map([], |row| {
                     let val: std::result::Result<String, _> = row.get(0);
@@ -988,7 +3480,10 @@ impl Database {
                     }
                 })
                 .map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Pragma read failed: {}", name)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Pragma read failed: {}", name)),
+                    )
                 })?
                 .filter_map(|r| r.ok())
                 .collect();
@@ -1001,4 +3496,193 @@ impl Database {
             }
         }
     }
+
+    /// Defer foreign key checks until the current transaction commits
+    /// SQLite turns this off again when the outermost transaction ends, so call it inside one.
+    /// A COMMIT that still violates a foreign key fails and leaves the transaction open.
+    #[napi]
+    pub fn defer_foreign_keys(&self, enabled: bool) -> Result<()> {
+        let conn = lock_connection(&self.conn, "deferForeignKeys")?;
+        conn.pragma_update(None, "defer_foreign_keys", enabled)
+            .map_err(to_napi_error)
+    }
+
+    /// Check whether SQLite may spill dirty pages to disk before a transaction commits
+    #[napi]
+    pub fn get_cache_spill(&self) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "getCacheSpill")?;
+        // Reads back the spill threshold in pages, which is 0 when spilling is off
+        let threshold: i64 = conn
+            .pragma_query_value(None, "cache_spill", |row| row.get(0))
+            .map_err(to_napi_error)?;
+        Ok(threshold != 0)
+    }
+
+    /// Allow or prevent spilling dirty pages to disk mid-transaction (`PRAGMA cache_spill`)
+    /// Disabling it keeps a large transaction in memory until it commits
+    #[napi]
+    pub fn set_cache_spill(&self, enabled: bool) -> Result<()> {
+        let conn = lock_connection(&self.conn, "setCacheSpill")?;
+        conn.pragma_update(None, "cache_spill", enabled)
+            .map_err(to_napi_error)
+    }
+
+    /// Set `PRAGMA synchronous` to OFF, NORMAL, FULL or EXTRA and return the resulting mode
+    #[napi]
+    pub fn set_synchronous(&self, mode: String) -> Result<String> {
+        const MODES: [&str; 4] = ["off", "normal", "full", "extra"];
+        let name = mode.to_lowercase();
+        if !MODES.contains(&name.as_str()) {
+            return Err(Error::from_reason(format!(
+                "Invalid synchronous mode '{}': expected OFF, NORMAL, FULL or EXTRA",
+                mode
+            )));
+        }
+        let conn = lock_connection(&self.conn, "setSynchronous")?;
+        conn.pragma_update(None, "synchronous", &name)
+            .map_err(to_napi_error)?;
+        // Read back as a number, which indexes MODES
+        let level: i64 = conn
+            .pragma_query_value(None, "synchronous", |row| row.get(0))
+            .map_err(to_napi_error)?;
+        Ok(MODES.get(level as usize).unwrap_or(&"unknown").to_string())
+    }
+
+    /// Set `PRAGMA journal_mode` and return the mode SQLite actually switched to
+    /// In-memory and temporary databases only support MEMORY and OFF and report MEMORY otherwise
+    #[napi]
+    pub fn set_journal_mode(&self, mode: String) -> Result<String> {
+        let name = mode.to_lowercase();
+        if !["delete", "truncate", "persist", "memory", "wal", "off"].contains(&name.as_str()) {
+            return Err(Error::from_reason(format!(
+                "Invalid journal mode '{}': expected DELETE, TRUNCATE, PERSIST, MEMORY, WAL or OFF",
+                mode
+            )));
+        }
+        let conn = lock_connection(&self.conn, "setJournalMode")?;
+        conn.pragma_update_and_check(None, "journal_mode", &name, |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+
+    /// Set `PRAGMA cache_size` and return the resulting value
+    /// Positive values are a number of pages, negative values a size in KiB
+    #[napi]
+    pub fn set_cache_size(&self, size: i64) -> Result<i64> {
+        let conn = lock_connection(&self.conn, "setCacheSize")?;
+        conn.pragma_update(None, "cache_size", size)
+            .map_err(to_napi_error)?;
+        conn.pragma_query_value(None, "cache_size", |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+
+    /// Cap the database size at `pages` pages and return the limit in effect
+    /// The limit can't go below the current page count; writes past it fail with SQLITE_FULL
+    #[napi]
+    pub fn set_max_page_count(&self, pages: u32) -> Result<u32> {
+        let conn = lock_connection(&self.conn, "setMaxPageCount")?;
+        Self::apply_max_page_count(&conn, pages)
+    }
+
+    /// Turn foreign key enforcement on or off and return whether it is now enabled
+    /// SQLite ignores the change inside a transaction, which the returned value reflects
+    #[napi]
+    pub fn set_foreign_keys(&self, enabled: bool) -> Result<bool> {
+        let conn = lock_connection(&self.conn, "setForeignKeys")?;
+        conn.pragma_update(None, "foreign_keys", enabled)
+            .map_err(to_napi_error)?;
+        conn.pragma_query_value(None, "foreign_keys", |row| row.get(0))
+            .map_err(to_napi_error)
+    }
+}
+
+#[cfg(feature = "snapshot")]
+#[napi]
+impl Database {
+    /// Record the state of the main database as a `Snapshot` another connection can open
+    /// The database must be in WAL mode; outside a transaction a short read transaction is used
+    #[napi]
+    pub fn snapshot(&self) -> Result<super::Snapshot> {
+        let conn = lock_connection(&self.conn, "snapshot")?;
+        let autocommit = conn.is_autocommit();
+        if autocommit {
+            conn.execute_batch("BEGIN DEFERRED")
+                .map_err(to_napi_error)?;
+        }
+        // A deferred transaction only starts reading on its first query
+        let mut raw = std::ptr::null_mut();
+        let rc = conn
+            .query_row("SELECT count(*) FROM sqlite_schema", [], |_| Ok(()))
+            .map(|_| unsafe {
+                rusqlite::ffi::sqlite3_snapshot_get(conn.handle(), c"main".as_ptr(), &mut raw)
+            });
+        if autocommit {
+            conn.execute_batch("COMMIT").ok();
+        }
+        match rc {
+            Ok(rusqlite::ffi::SQLITE_OK) => Ok(super::Snapshot::from_raw(raw)),
+            Ok(rc) => Err(crate::error::to_napi_error_with_context(
+                rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rc), None),
+                Some("Taking a snapshot failed; the database must be in WAL mode"),
+            )),
+            Err(e) => Err(to_napi_error(e)),
+        }
+    }
+
+    /// Begin a read transaction that sees the database exactly as it was at `snapshot`
+    /// The transaction stays open until ended with `exec("COMMIT")` or `exec("ROLLBACK")`
+    #[napi]
+    pub fn open_snapshot(&self, snapshot: &super::Snapshot) -> Result<()> {
+        let conn = lock_connection(&self.conn, "openSnapshot")?;
+        if !conn.is_autocommit() {
+            return Err(Error::from_reason(
+                "Cannot open a snapshot while another transaction is open",
+            ));
+        }
+        // SQLite refuses to open a snapshot until the connection has read the schema once
+        conn.query_row("SELECT count(*) FROM sqlite_schema", [], |_| Ok(()))
+            .map_err(to_napi_error)?;
+        conn.execute_batch("BEGIN DEFERRED")
+            .map_err(to_napi_error)?;
+        let rc = unsafe {
+            rusqlite::ffi::sqlite3_snapshot_open(conn.handle(), c"main".as_ptr(), snapshot.raw())
+        };
+        if rc != rusqlite::ffi::SQLITE_OK {
+            conn.execute_batch("ROLLBACK").ok();
+            return Err(crate::error::to_napi_error_with_context(
+                rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rc), None),
+                Some("Opening the snapshot failed; it may have been checkpointed away"),
+            ));
+        }
+        self.sync_transaction_flag(&conn);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "column_metadata")]
+#[napi]
+impl Database {
+    /// Describe one column with `sqlite3_table_column_metadata`, including the collation
+    /// and AUTOINCREMENT flag that `PRAGMA table_info` leaves out
+    #[napi(
+        ts_return_type = "{ declaredType: string | null; collation: string | null; notNull: boolean; primaryKey: boolean; autoIncrement: boolean }"
+    )]
+    pub fn column_metadata(&self, table: String, column: String) -> Result<serde_json::Value> {
+        let conn = lock_connection(&self.conn, "columnMetadata")?;
+        let (declared_type, collation, not_null, primary_key, auto_increment) = conn
+            .column_metadata(None, table.as_str(), column.as_str())
+            .map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("No column '{}' in table '{}'", column, table)),
+                )
+            })?;
+        let text = |value: Option<&std::ffi::CStr>| value.map(|s| s.to_string_lossy().into_owned());
+        Ok(serde_json::json!({
+            "declaredType": text(declared_type),
+            "collation": text(collation),
+            "notNull": not_null,
+            "primaryKey": primary_key,
+            "autoIncrement": auto_increment,
+        }))
+    }
 }
@@ -0,0 +1,359 @@
+//! Hooks module - JavaScript callbacks invoked synchronously by SQLite
+
+use crate::error::{lock_connection, lock_with_context};
+use crate::models::{SlowQuery, StatementProfile};
+use napi::bindgen_prelude::*;
+use napi::sys;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use rusqlite::ffi;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization, TransactionOperation};
+use rusqlite::Connection;
+use std::ffi::{c_char, c_int, c_uint, c_void, CStr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A JavaScript function that SQLite calls back into while a statement runs
+/// Every Database method runs on the JS thread, so SQLite only ever invokes hooks there
+pub struct JsCallback<Args: JsValuesTupleIntoVec, Return> {
+    env: sys::napi_env,
+    func: FunctionRef<Args, Return>,
+}
+
+// Safety: the callback is only invoked during Database and Statement methods,
+// which always run on the JS thread that owns `env`
+unsafe impl<Args: JsValuesTupleIntoVec, Return> Send for JsCallback<Args, Return> {}
+unsafe impl<Args: JsValuesTupleIntoVec, Return> Sync for JsCallback<Args, Return> {}
+
+impl<Args: JsValuesTupleIntoVec, Return: FromNapiValue> JsCallback<Args, Return> {
+    /// Keep a reference to `func` so it can be called after the current call returns
+    pub fn new(env: &Env, func: &Function<Args, Return>) -> Result<Self> {
+        Ok(JsCallback {
+            env: env.raw(),
+            func: func.create_ref()?,
+        })
+    }
+
+    /// Call the function, returning an error if it throws
+    pub fn call(&self, args: Args) -> Result<Return> {
+        let env = Env::from_raw(self.env);
+        self.func.borrow_back(&env)?.call(args)
+    }
+}
+
+/// Arguments passed to an authorizer callback:
+/// (action, arg1, arg2, database name, trigger/view accessor)
+pub type AuthorizerArgs = FnArgs<(
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+)>;
+
+/// Split an authorizer action into SQLite's action code name and its two arguments
+fn auth_action_parts<'a>(
+    action: &AuthAction<'a>,
+) -> (&'static str, Option<&'a str>, Option<&'a str>) {
+    fn operation_name(operation: &TransactionOperation) -> &'static str {
+        match operation {
+            TransactionOperation::Begin => "BEGIN",
+            TransactionOperation::Release => "RELEASE",
+            TransactionOperation::Rollback => "ROLLBACK",
+            _ => "UNKNOWN",
+        }
+    }
+
+    match *action {
+        AuthAction::CreateIndex {
+            index_name,
+            table_name,
+        } => ("CREATE_INDEX", Some(index_name), Some(table_name)),
+        AuthAction::CreateTable { table_name } => ("CREATE_TABLE", Some(table_name), None),
+        AuthAction::CreateTempIndex {
+            index_name,
+            table_name,
+        } => ("CREATE_TEMP_INDEX", Some(index_name), Some(table_name)),
+        AuthAction::CreateTempTable { table_name } => ("CREATE_TEMP_TABLE", Some(table_name), None),
+        AuthAction::CreateTempTrigger {
+            trigger_name,
+            table_name,
+        } => ("CREATE_TEMP_TRIGGER", Some(trigger_name), Some(table_name)),
+        AuthAction::CreateTempView { view_name } => ("CREATE_TEMP_VIEW", Some(view_name), None),
+        AuthAction::CreateTrigger {
+            trigger_name,
+            table_name,
+        } => ("CREATE_TRIGGER", Some(trigger_name), Some(table_name)),
+        AuthAction::CreateView { view_name } => ("CREATE_VIEW", Some(view_name), None),
+        AuthAction::Delete { table_name } => ("DELETE", Some(table_name), None),
+        AuthAction::DropIndex {
+            index_name,
+            table_name,
+        } => ("DROP_INDEX", Some(index_name), Some(table_name)),
+        AuthAction::DropTable { table_name } => ("DROP_TABLE", Some(table_name), None),
+        AuthAction::DropTempIndex {
+            index_name,
+            table_name,
+        } => ("DROP_TEMP_INDEX", Some(index_name), Some(table_name)),
+        AuthAction::DropTempTable { table_name } => ("DROP_TEMP_TABLE", Some(table_name), None),
+        AuthAction::DropTempTrigger {
+            trigger_name,
+            table_name,
+        } => ("DROP_TEMP_TRIGGER", Some(trigger_name), Some(table_name)),
+        AuthAction::DropTempView { view_name } => ("DROP_TEMP_VIEW", Some(view_name), None),
+        AuthAction::DropTrigger {
+            trigger_name,
+            table_name,
+        } => ("DROP_TRIGGER", Some(trigger_name), Some(table_name)),
+        AuthAction::DropView { view_name } => ("DROP_VIEW", Some(view_name), None),
+        AuthAction::Insert { table_name } => ("INSERT", Some(table_name), None),
+        AuthAction::Pragma {
+            pragma_name,
+            pragma_value,
+        } => ("PRAGMA", Some(pragma_name), pragma_value),
+        AuthAction::Read {
+            table_name,
+            column_name,
+        } => ("READ", Some(table_name), Some(column_name)),
+        AuthAction::Select => ("SELECT", None, None),
+        AuthAction::Transaction { operation } => {
+            ("TRANSACTION", Some(operation_name(&operation)), None)
+        }
+        AuthAction::Update {
+            table_name,
+            column_name,
+        } => ("UPDATE", Some(table_name), Some(column_name)),
+        AuthAction::Attach { filename } => ("ATTACH", Some(filename), None),
+        AuthAction::Detach { database_name } => ("DETACH", Some(database_name), None),
+        AuthAction::AlterTable {
+            database_name,
+            table_name,
+        } => ("ALTER_TABLE", Some(database_name), Some(table_name)),
+        AuthAction::Reindex { index_name } => ("REINDEX", Some(index_name), None),
+        AuthAction::Analyze { table_name } => ("ANALYZE", Some(table_name), None),
+        AuthAction::CreateVtable {
+            table_name,
+            module_name,
+        } => ("CREATE_VTABLE", Some(table_name), Some(module_name)),
+        AuthAction::DropVtable {
+            table_name,
+            module_name,
+        } => ("DROP_VTABLE", Some(table_name), Some(module_name)),
+        AuthAction::Function { function_name } => ("FUNCTION", None, Some(function_name)),
+        AuthAction::Savepoint {
+            operation,
+            savepoint_name,
+        } => (
+            "SAVEPOINT",
+            Some(operation_name(&operation)),
+            Some(savepoint_name),
+        ),
+        AuthAction::Recursive => ("RECURSIVE", None, None),
+        AuthAction::Unknown { arg1, arg2, .. } => ("UNKNOWN", arg1, arg2),
+        _ => ("UNKNOWN", None, None),
+    }
+}
+
+/// Wrap a JS authorizer into the hook SQLite calls while preparing statements
+/// Anything other than "allow" or "ignore" (including a thrown error) denies the action
+pub fn authorizer(
+    callback: JsCallback<AuthorizerArgs, String>,
+) -> impl for<'c> FnMut(AuthContext<'c>) -> Authorization + Send + 'static {
+    move |ctx: AuthContext<'_>| {
+        let (action, arg1, arg2) = auth_action_parts(&ctx.action);
+        let args = (
+            action.to_string(),
+            arg1.map(str::to_string),
+            arg2.map(str::to_string),
+            ctx.database_name.map(str::to_string),
+            ctx.accessor.map(str::to_string),
+        );
+        match callback.call(args.into()) {
+            Ok(result) if result.eq_ignore_ascii_case("allow") => Authorization::Allow,
+            Ok(result) if result.eq_ignore_ascii_case("ignore") => Authorization::Ignore,
+            _ => Authorization::Deny,
+        }
+    }
+}
+
+/// A JS callback for statements that run longer than a threshold
+pub struct SlowQueryLog {
+    threshold: Duration,
+    callback: JsCallback<SlowQuery, ()>,
+    /// Set while the callback runs, so slow statements it runs itself aren't reported
+    reporting: AtomicBool,
+}
+
+impl SlowQueryLog {
+    pub fn new(threshold: Duration, callback: JsCallback<SlowQuery, ()>) -> Self {
+        SlowQueryLog {
+            threshold,
+            callback,
+            reporting: AtomicBool::new(false),
+        }
+    }
+}
+
+/// The slow query log shared by a connection and its statements
+pub type SlowQueryHook = Arc<Mutex<Option<Arc<SlowQueryLog>>>>;
+
+/// Times one statement and reports it to the slow query log when dropped
+/// Create it before locking the connection so the lock is released before the callback runs
+pub struct SlowQueryTimer<'a> {
+    log: Arc<SlowQueryLog>,
+    sql: &'a str,
+    start: Instant,
+}
+
+impl<'a> SlowQueryTimer<'a> {
+    /// Start timing `sql`, or return `None` (without reading the clock) when no log is installed
+    pub fn start(hook: &SlowQueryHook, sql: &'a str) -> Option<Self> {
+        let log = lock_with_context(hook, "slow query log").clone()?;
+        Some(SlowQueryTimer {
+            log,
+            sql,
+            start: Instant::now(),
+        })
+    }
+}
+
+impl Drop for SlowQueryTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        if elapsed < self.log.threshold || self.log.reporting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // A throwing callback can't fail the statement that already ran, so its error is dropped
+        let _ = self.log.callback.call(SlowQuery {
+            sql: self.sql.to_string(),
+            duration_micros: elapsed.as_micros() as f64,
+        });
+        self.log.reporting.store(false, Ordering::SeqCst);
+    }
+}
+
+/// A JS callback receiving the SQL of each statement a connection runs
+/// Calls are queued onto the event loop rather than made while the statement runs
+pub type TraceFn = ThreadsafeFunction<String, (), String, Status, false, true>;
+
+/// A JS callback receiving each finished statement with SQLite's timing for it
+pub type ProfileFn =
+    ThreadsafeFunction<StatementProfile, (), StatementProfile, Status, false, true>;
+
+/// The JS callbacks a connection reports `sqlite3_trace_v2` events to
+#[derive(Default)]
+pub struct TraceCallbacks {
+    /// Called for `SQLITE_TRACE_STMT`, when a statement starts running
+    pub statement: Option<TraceFn>,
+    /// Called for `SQLITE_TRACE_PROFILE`, when a statement finishes
+    pub profile: Option<ProfileFn>,
+}
+
+/// The text of a `SQLITE_TRACE_STMT` event, with bound parameters expanded
+unsafe fn traced_sql(stmt: *mut ffi::sqlite3_stmt, sql: &CStr) -> String {
+    // Trigger bodies arrive as comments ("-- TRIGGER name", "-- INSERT ..."), reported as is
+    let expanded = if sql.to_bytes().starts_with(b"--") {
+        std::ptr::null_mut()
+    } else {
+        ffi::sqlite3_expanded_sql(stmt)
+    };
+    if expanded.is_null() {
+        return sql.to_string_lossy().into_owned();
+    }
+    let text = CStr::from_ptr(expanded).to_string_lossy().into_owned();
+    ffi::sqlite3_free(expanded as *mut c_void);
+    text
+}
+
+/// Report one `sqlite3_trace_v2` event to the matching callback in `ctx`
+unsafe extern "C" fn trace_event(
+    event: c_uint,
+    ctx: *mut c_void,
+    p: *mut c_void,
+    x: *mut c_void,
+) -> c_int {
+    let callbacks = &*(ctx as *const TraceCallbacks);
+    let stmt = p as *mut ffi::sqlite3_stmt;
+    match event {
+        ffi::SQLITE_TRACE_STMT => {
+            if let Some(callback) = &callbacks.statement {
+                let sql = traced_sql(stmt, CStr::from_ptr(x as *const c_char));
+                callback.call(sql, ThreadsafeFunctionCallMode::NonBlocking);
+            }
+        }
+        ffi::SQLITE_TRACE_PROFILE => {
+            if let Some(callback) = &callbacks.profile {
+                let sql = ffi::sqlite3_sql(stmt);
+                let profile = StatementProfile {
+                    sql: if sql.is_null() {
+                        String::new()
+                    } else {
+                        CStr::from_ptr(sql).to_string_lossy().into_owned()
+                    },
+                    nanos: *(x as *const i64) as f64,
+                };
+                callback.call(profile, ThreadsafeFunctionCallMode::NonBlocking);
+            }
+        }
+        _ => {}
+    }
+    0
+}
+
+/// The trace callbacks registered on a connection with `sqlite3_trace_v2`
+/// Dropping it unregisters them, so SQLite never calls into freed ones
+pub struct ConnectionTrace {
+    conn: Arc<Mutex<Connection>>,
+    /// Boxed so the pointer SQLite holds stays valid while registered
+    callbacks: Box<TraceCallbacks>,
+}
+
+impl ConnectionTrace {
+    /// Start with no callbacks registered on `conn`
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        ConnectionTrace {
+            conn,
+            callbacks: Box::default(),
+        }
+    }
+
+    /// Change the callbacks, then register for exactly the events that have one
+    pub fn update(
+        &mut self,
+        operation: &str,
+        change: impl FnOnce(&mut TraceCallbacks),
+    ) -> napi::Result<()> {
+        let conn = lock_connection(&self.conn, operation)?;
+        unsafe {
+            ffi::sqlite3_trace_v2(conn.handle(), 0, None, std::ptr::null_mut());
+        }
+        change(&mut self.callbacks);
+        let mut mask = 0;
+        if self.callbacks.statement.is_some() {
+            mask |= ffi::SQLITE_TRACE_STMT;
+        }
+        if self.callbacks.profile.is_some() {
+            mask |= ffi::SQLITE_TRACE_PROFILE;
+        }
+        if mask != 0 {
+            unsafe {
+                ffi::sqlite3_trace_v2(
+                    conn.handle(),
+                    mask,
+                    Some(trace_event),
+                    &*self.callbacks as *const TraceCallbacks as *mut c_void,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ConnectionTrace {
+    fn drop(&mut self) {
+        // Nothing can be in a batch() of a connection that is being dropped
+        let _ = self.update("Database.drop", |callbacks| {
+            *callbacks = TraceCallbacks::default();
+        });
+    }
+}
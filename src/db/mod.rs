@@ -1,13 +1,20 @@
 //! Database module - provides SQLite database access via NAPI
 
+mod batch;
 mod database;
+mod hooks;
 mod params;
 mod row;
+#[cfg(feature = "snapshot")]
+mod snapshot;
 mod statement;
 mod transaction;
 
+pub use batch::ConnectionBatch;
 pub use database::Database;
-pub use params::{convert_params, convert_params_container, Param, ParamsContainer};
-pub use row::sqlite_to_json;
-pub use statement::{ColumnInfo, Iter, Statement};
+pub use params::{convert_params_container, Param, ParamsContainer};
+pub use row::{sqlite_to_json, ResultValue};
+#[cfg(feature = "snapshot")]
+pub use snapshot::Snapshot;
+pub use statement::{ColumnInfo, Iter, QueryOptions, Statement};
 pub use transaction::Transaction;
@@ -5,12 +5,18 @@ use rusqlite::types::{ToSqlOutput, ValueRef};
 use rusqlite::ToSql;
 use std::collections::HashMap;
 
+/// The largest integer a JS number holds exactly (`Number.MAX_SAFE_INTEGER`)
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
 pub enum Param {
     Null,
     Int(i64),
     Float(f64),
     Text(String),
     Blob(Vec<u8>),
+    /// A Node Buffer bound straight from JS memory; the reference it holds keeps the
+    /// buffer alive until the param is dropped, so large blobs aren't copied into Rust
+    Buffer(Buffer),
     Bool(bool),
 }
 
@@ -22,6 +28,7 @@ impl ToSql for Param {
             Param::Float(f) => Ok(ToSqlOutput::Borrowed(ValueRef::Real(*f))),
             Param::Text(s) => Ok(ToSqlOutput::Borrowed(ValueRef::Text(s.as_bytes()))),
             Param::Blob(b) => Ok(ToSqlOutput::Borrowed(ValueRef::Blob(b))),
+            Param::Buffer(b) => Ok(ToSqlOutput::Borrowed(ValueRef::Blob(b.as_ref()))),
             Param::Bool(b) => Ok(ToSqlOutput::Borrowed(ValueRef::Integer(if *b {
                 1
             } else {
@@ -31,6 +38,25 @@ impl ToSql for Param {
     }
 }
 
+/// Bind a float, rejecting NaN and ±Infinity
+/// SQLite has no representation for NaN (it silently stores NULL), so both are refused
+fn float_param(d: f64) -> Result<Param> {
+    if d.is_finite() {
+        return Ok(Param::Float(d));
+    }
+    let name = if d.is_nan() {
+        "NaN"
+    } else if d > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    };
+    Err(Error::from_reason(format!(
+        "Cannot bind {} as a parameter: only finite numbers can be stored",
+        name
+    )))
+}
+
 /// Convert a JavaScript value to a SQLite parameter
 pub fn js_to_param(val: &Unknown) -> Result<Param> {
     match val.get_type()? {
@@ -46,16 +72,25 @@ pub fn js_to_param(val: &Unknown) -> Result<Param> {
                     && d.abs() < (i64::MAX as f64)
                     && d.abs() < (i64::MIN as f64).abs()
                 {
+                    // Past 2^53 neighbouring integers share a double, so the value
+                    // may already differ from what the caller wrote
+                    if d.abs() > MAX_SAFE_INTEGER {
+                        return Err(Error::from_reason(format!(
+                            "Cannot bind {} as an integer parameter: it is beyond Number.MAX_SAFE_INTEGER \
+                             and may have lost precision, pass a BigInt instead",
+                            d as i64
+                        )));
+                    }
                     Ok(Param::Int(d as i64))
                 } else {
-                    Ok(Param::Float(d))
+                    float_param(d)
                 }
             } else if let Ok(i) = num.get_int32() {
                 Ok(Param::Int(i as i64))
             } else {
                 // Fallback - try to get as int64
                 let n = val.coerce_to_number()?;
-                Ok(Param::Float(n.get_double().unwrap_or(0.0)))
+                float_param(n.get_double().unwrap_or(0.0))
             }
         }
         ValueType::String => {
@@ -69,11 +104,16 @@ pub fn js_to_param(val: &Unknown) -> Result<Param> {
         ValueType::Object => {
             if val.is_buffer()? {
                 let buf = unsafe { val.cast::<Buffer>()? };
-                Ok(Param::Blob(buf.as_ref().to_vec()))
+                Ok(Param::Buffer(buf))
             } else if val.is_date()? {
-                // Coerces to number to get timestamp
-                let num = val.coerce_to_number()?;
-                Ok(Param::Float(num.get_double()?))
+                // Bind the millisecond timestamp as an INTEGER so it compares like Date.now()
+                let ms = val.coerce_to_number()?.get_double()?;
+                if !ms.is_finite() {
+                    return Err(Error::from_reason(
+                        "Cannot bind an invalid Date as a parameter",
+                    ));
+                }
+                Ok(Param::Int(ms as i64))
             } else if val.is_arraybuffer()? || val.is_typedarray()? {
                 // Handle ArrayBuffer and TypedArray (like Uint8Array)
                 let env = Env::from_raw(val.env());
@@ -104,8 +144,26 @@ pub fn js_to_param(val: &Unknown) -> Result<Param> {
     }
 }
 
+/// Convert a plain JavaScript object into ordered (column, parameter) pairs
+/// Properties whose value is `undefined` are skipped
+pub fn object_to_params(val: &Unknown) -> Result<Vec<(String, Param)>> {
+    if val.get_type()? != ValueType::Object || val.is_array()? || val.is_buffer()? {
+        return Err(Error::from_reason(
+            "Expected a plain object of column values",
+        ));
+    }
+    let obj = unsafe { val.cast::<Object>()? };
+    let mut result = Vec::new();
+    for key in Object::keys(&obj)? {
+        if let Some(value) = obj.get::<Unknown>(&key)? {
+            result.push((key, js_to_param(&value)?));
+        }
+    }
+    Ok(result)
+}
+
 /// Convert a serde_json::Value to Param
-fn json_value_to_param(value: &serde_json::Value) -> Result<Param> {
+pub fn json_value_to_param(value: &serde_json::Value) -> Result<Param> {
     match value {
         serde_json::Value::Null => Ok(Param::Null),
         serde_json::Value::Bool(b) => Ok(Param::Bool(*b)),
@@ -113,9 +171,9 @@ fn json_value_to_param(value: &serde_json::Value) -> Result<Param> {
             if let Some(i) = n.as_i64() {
                 Ok(Param::Int(i))
             } else if let Some(f) = n.as_f64() {
-                Ok(Param::Float(f))
+                float_param(f)
             } else {
-                Ok(Param::Float(n.as_f64().unwrap_or(0.0)))
+                float_param(n.as_f64().unwrap_or(0.0))
             }
         }
         serde_json::Value::String(s) => Ok(Param::Text(s.clone())),
@@ -125,85 +183,137 @@ fn json_value_to_param(value: &serde_json::Value) -> Result<Param> {
     }
 }
 
+/// The prefixes SQLite accepts on named parameters
+const PARAM_SIGILS: [char; 3] = [':', '$', '@'];
+
 /// Parameter container that supports both positional and named parameters
 pub enum ParamsContainer {
     Positional(Vec<Param>),
     Named(HashMap<String, Param>),
 }
 
-/// Convert JavaScript parameters to a ParamsContainer
-/// Handles arrays (positional) and objects (named parameters)
-pub fn convert_params_container(_env: &Env, params: Option<Unknown>) -> Result<ParamsContainer> {
-    if let Some(p) = params {
-        if p.is_array()? {
-            // Positional parameters: [value1, value2, ...]
-            let arr = unsafe { p.cast::<Array>()? };
-            let mut result = Vec::new();
-            for i in 0..arr.len() {
-                result.push(js_to_param(&arr.get_element(i)?)?);
+/// Find the placeholder `stmt` declares for the named parameter `key`
+/// A key without a sigil matches `:key`, `$key` or `@key`, whichever the SQL uses
+fn placeholder_name(stmt: &rusqlite::Statement, key: &str) -> rusqlite::Result<String> {
+    let candidates: Vec<String> = if key.starts_with(PARAM_SIGILS) {
+        vec![key.to_string()]
+    } else {
+        PARAM_SIGILS
+            .iter()
+            .map(|sigil| format!("{}{}", sigil, key))
+            .collect()
+    };
+    for candidate in candidates.iter() {
+        if stmt.parameter_index(candidate)?.is_some() {
+            return Ok(candidate.clone());
+        }
+    }
+    Err(rusqlite::Error::InvalidParameterName(format!(
+        "'{}' does not match any placeholder in the statement (tried {})",
+        key,
+        candidates.join(", ")
+    )))
+}
+
+impl ParamsContainer {
+    /// Rename each named parameter to the placeholder `stmt` declares for it
+    pub fn resolve_names(self, stmt: &rusqlite::Statement) -> rusqlite::Result<Self> {
+        match self {
+            ParamsContainer::Positional(_) => Ok(self),
+            ParamsContainer::Named(named_params) => {
+                let mut resolved = HashMap::with_capacity(named_params.len());
+                for (key, param) in named_params {
+                    resolved.insert(placeholder_name(stmt, &key)?, param);
+                }
+                Ok(ParamsContainer::Named(resolved))
             }
-            Ok(ParamsContainer::Positional(result))
-        } else if p.get_type()? == ValueType::Object {
-            // Named parameters: { $name: value, :name: value, @name: value }
-            let env = Env::from_raw(p.env());
-            let json_value: serde_json::Value = env.from_js_value(p)?;
+        }
+    }
 
-            if let serde_json::Value::Object(map) = json_value {
-                let mut result = HashMap::new();
-                for (key, value) in map.iter() {
-                    // Normalize the parameter name - SQLite accepts $name, :name, @name
-                    // We need to ensure the key matches what SQLite expects
-                    let normalized_key =
-                        if key.starts_with('$') || key.starts_with(':') || key.starts_with('@') {
-                            key.to_string()
-                        } else {
-                            // If no prefix, add $ prefix (bun:sqlite style)
-                            format!("${}", key)
-                        };
-                    result.insert(normalized_key, json_value_to_param(value)?);
+    /// Bind these parameters to `stmt` for a following `raw_query`/`raw_execute`
+    pub fn bind(&self, stmt: &mut rusqlite::Statement) -> rusqlite::Result<()> {
+        match self {
+            ParamsContainer::Positional(positional_params) => {
+                let expected = stmt.parameter_count();
+                if positional_params.len() != expected {
+                    return Err(rusqlite::Error::InvalidParameterCount(
+                        positional_params.len(),
+                        expected,
+                    ));
+                }
+                for (i, param) in positional_params.iter().enumerate() {
+                    stmt.raw_bind_parameter(i + 1, param)?;
+                }
+            }
+            ParamsContainer::Named(named_params) => {
+                for (key, param) in named_params.iter() {
+                    let name = placeholder_name(stmt, key)?;
+                    stmt.raw_bind_parameter(name.as_str(), param)?;
                 }
-                Ok(ParamsContainer::Named(result))
-            } else {
-                Ok(ParamsContainer::Positional(vec![js_to_param(&p)?]))
             }
-        } else {
-            Ok(ParamsContainer::Positional(vec![js_to_param(&p)?]))
         }
-    } else {
-        Ok(ParamsContainer::Positional(Vec::new()))
+        Ok(())
+    }
+
+    /// Execute `sql` on `conn` with these parameters, returning the number of changed rows
+    pub fn execute(&self, conn: &rusqlite::Connection, sql: &str) -> rusqlite::Result<usize> {
+        let mut stmt = conn.prepare(sql)?;
+        match self {
+            ParamsContainer::Positional(positional_params) => {
+                let params_refs: Vec<&dyn ToSql> =
+                    positional_params.iter().map(|p| p as &dyn ToSql).collect();
+                stmt.execute(params_refs.as_slice())
+            }
+            ParamsContainer::Named(named_params) => {
+                let mut names = Vec::with_capacity(named_params.len());
+                for (key, param) in named_params.iter() {
+                    names.push((placeholder_name(&stmt, key)?, param));
+                }
+                let named_params_refs: Vec<(&str, &dyn ToSql)> = names
+                    .iter()
+                    .map(|(name, param)| (name.as_str(), *param as &dyn ToSql))
+                    .collect();
+                stmt.execute(named_params_refs.as_slice())
+            }
+        }
     }
 }
 
-/// Convert JavaScript parameters to rusqlite parameters
+/// Convert JavaScript parameters to a ParamsContainer
 /// Handles arrays (positional) and objects (named parameters)
-#[allow(unused_variables)]
-pub fn convert_params(env: &Env, params: Option<Unknown>) -> Result<Vec<Param>> {
-    let mut result = Vec::new();
+pub fn convert_params_container(_env: &Env, params: Option<Unknown>) -> Result<ParamsContainer> {
     if let Some(p) = params {
         if p.is_array()? {
             // Positional parameters: [value1, value2, ...]
             let arr = unsafe { p.cast::<Array>()? };
+            let mut result = Vec::new();
             for i in 0..arr.len() {
                 result.push(js_to_param(&arr.get_element(i)?)?);
             }
+            Ok(ParamsContainer::Positional(result))
         } else if p.get_type()? == ValueType::Object {
-            // Named parameters: { $name: value, :name: value, @name: value }
-            // Convert to a string representation that rusqlite can parse
-            let env = Env::from_raw(p.env());
-            let json_value: serde_json::Value = env.from_js_value(p)?;
-
-            if let serde_json::Value::Object(map) = json_value {
-                // For named parameters, we need to build a list of values in order
-                // SQLite named parameters are $name, :name, or @name
-                for (_key, value) in map.iter() {
-                    result.push(json_value_to_param(value)?);
+            if p.is_buffer()? || p.is_typedarray()? || p.is_arraybuffer()? {
+                return Ok(ParamsContainer::Positional(vec![js_to_param(&p)?]));
+            }
+            // Named parameters: { name: value, $name: value, :name: value, @name: value }
+            // Keys are matched to the statement's placeholders when it is bound
+            let obj = unsafe { p.cast::<Object>()? };
+            let mut result = HashMap::new();
+            for key in Object::keys(&obj)? {
+                let Some(value) = obj.get::<Unknown>(&key)? else {
+                    continue;
+                };
+                // `undefined` leaves the parameter unbound, while `null` binds NULL explicitly
+                if value.get_type()? == ValueType::Undefined {
+                    continue;
                 }
-            } else {
-                result.push(js_to_param(&p)?);
+                result.insert(key, js_to_param(&value)?);
             }
+            Ok(ParamsContainer::Named(result))
         } else {
-            result.push(js_to_param(&p)?);
+            Ok(ParamsContainer::Positional(vec![js_to_param(&p)?]))
         }
+    } else {
+        Ok(ParamsContainer::Positional(Vec::new()))
     }
-    Ok(result)
 }
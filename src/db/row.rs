@@ -1,7 +1,9 @@
 //! Row module - utilities for converting SQLite rows to JSON values
 
+use napi::bindgen_prelude::{Buffer, ToNapiValue, TypeName};
+use napi::{check_status, sys, ValueType};
 use rusqlite::Row;
-use serde_json::{Number, Value};
+use serde_json::{Map, Number, Value};
 
 /// Convert SQLite row to JSON value with proper type handling
 pub fn sqlite_to_json(row: &Row, i: usize) -> Result<Value, rusqlite::Error> {
@@ -38,3 +40,539 @@ pub fn sqlite_to_json(row: &Row, i: usize) -> Result<Value, rusqlite::Error> {
         ))),
     }
 }
+
+/// The storage class SQLite actually holds for a cell: "integer", "real", "text", "blob" or "null"
+pub fn storage_type(row: &Row, i: usize) -> Result<&'static str, rusqlite::Error> {
+    Ok(match row.get_ref(i)?.data_type() {
+        rusqlite::types::Type::Null => "null",
+        rusqlite::types::Type::Integer => "integer",
+        rusqlite::types::Type::Real => "real",
+        rusqlite::types::Type::Text => "text",
+        rusqlite::types::Type::Blob => "blob",
+    })
+}
+
+/// How blob columns are returned in query results
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BlobEncoding {
+    /// Base64 string
+    #[default]
+    Base64,
+    /// Lowercase hex string
+    Hex,
+    /// Node.js `Buffer`
+    Buffer,
+}
+
+impl BlobEncoding {
+    /// Parse an encoding from its name (case insensitive)
+    pub fn parse(name: &str) -> napi::Result<BlobEncoding> {
+        match name.to_lowercase().as_str() {
+            "base64" => Ok(BlobEncoding::Base64),
+            "hex" => Ok(BlobEncoding::Hex),
+            "buffer" => Ok(BlobEncoding::Buffer),
+            _ => Err(napi::Error::from_reason(format!(
+                "Unknown blob encoding '{}' (expected base64, hex or buffer)",
+                name
+            ))),
+        }
+    }
+}
+
+/// What to do with TEXT values that aren't valid UTF-8
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TextErrorMode {
+    /// Replace invalid bytes with U+FFFD
+    #[default]
+    Lossy,
+    /// Fail the query
+    Error,
+    /// Return the raw bytes as a `Buffer`
+    Bytes,
+}
+
+impl TextErrorMode {
+    /// Parse a mode from its name (case insensitive)
+    pub fn parse(name: &str) -> napi::Result<TextErrorMode> {
+        match name.to_lowercase().as_str() {
+            "lossy" => Ok(TextErrorMode::Lossy),
+            "error" => Ok(TextErrorMode::Error),
+            "bytes" => Ok(TextErrorMode::Bytes),
+            _ => Err(napi::Error::from_reason(format!(
+                "Unknown text error mode '{}' (expected lossy, error or bytes)",
+                name
+            ))),
+        }
+    }
+}
+
+/// How values without a lossless JSON form are returned
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueEncoding {
+    /// Encoding for BLOB values
+    pub blobs: BlobEncoding,
+    /// Handling of TEXT values that aren't valid UTF-8
+    pub invalid_text: TextErrorMode,
+}
+
+/// Convert a SQLite column to JSON, encoding blobs and invalid text with `encoding`
+///
+/// Buffers have no JSON representation, so those values come back as `null` and their bytes
+/// are recorded in `natives`, addressed by column index and not yet placed in a row.
+pub fn sqlite_to_json_encoded(
+    row: &Row,
+    i: usize,
+    encoding: ValueEncoding,
+    natives: &mut Vec<NativeCell>,
+) -> Result<Value, rusqlite::Error> {
+    let mut buffer = |bytes: &[u8]| {
+        natives.push(NativeCell {
+            row: None,
+            column: NativeKey::Index(i),
+            value: NativeValue::Buffer(bytes.to_vec()),
+        });
+        Ok(Value::Null)
+    };
+    match row.get_ref(i)? {
+        rusqlite::types::ValueRef::Blob(b) => match encoding.blobs {
+            BlobEncoding::Base64 => sqlite_to_json(row, i),
+            BlobEncoding::Hex => Ok(Value::String(crate::hash::to_hex(b))),
+            BlobEncoding::Buffer => buffer(b),
+        },
+        rusqlite::types::ValueRef::Text(t) => match std::str::from_utf8(t) {
+            Ok(text) => Ok(Value::String(text.to_string())),
+            Err(e) => match encoding.invalid_text {
+                TextErrorMode::Lossy => sqlite_to_json(row, i),
+                TextErrorMode::Error => Err(rusqlite::Error::FromSqlConversionFailure(
+                    i,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )),
+                TextErrorMode::Bytes => buffer(t),
+            },
+        },
+        _ => sqlite_to_json(row, i),
+    }
+}
+
+/// Convert a row to an object keyed by column name, encoding values with `encoding`
+///
+/// Buffers are recorded in `natives` by column name and not yet placed in a row.
+pub fn row_to_object(
+    row: &Row,
+    column_names: &[String],
+    encoding: ValueEncoding,
+    natives: &mut Vec<NativeCell>,
+) -> Result<Map<String, Value>, rusqlite::Error> {
+    let mut map = Map::new();
+    let mut row_natives: Vec<NativeCell> = Vec::new();
+    let mut cells = Vec::new();
+    for (i, name) in column_names.iter().enumerate() {
+        let value = sqlite_to_json_encoded(row, i, encoding, &mut cells)?;
+        let key = NativeKey::Name(name.clone());
+        // A later column with the same name replaces the earlier one, buffer included
+        row_natives.retain(|cell| cell.column != key);
+        row_natives.extend(cells.drain(..).map(|cell| NativeCell {
+            column: key.clone(),
+            ..cell
+        }));
+        map.insert(name.clone(), value);
+    }
+    natives.append(&mut row_natives);
+    Ok(map)
+}
+
+/// A result value that has no JSON representation and is created natively in JS
+#[derive(Clone)]
+pub enum NativeValue {
+    /// JavaScript `Date` from milliseconds since the Unix epoch
+    Date(f64),
+    /// Node.js `Buffer` holding a copy of a blob or invalid text
+    Buffer(Vec<u8>),
+}
+
+/// Position of a native value inside a result: an array index or an object key
+#[derive(Debug, Clone, PartialEq)]
+pub enum NativeKey {
+    /// Array index
+    Index(usize),
+    /// Object key
+    Name(String),
+}
+
+impl NativeKey {
+    /// Create the JS property key
+    unsafe fn to_napi(env: sys::napi_env, key: NativeKey) -> napi::Result<sys::napi_value> {
+        match key {
+            NativeKey::Index(index) => unsafe { u32::to_napi_value(env, index as u32) },
+            NativeKey::Name(name) => unsafe { String::to_napi_value(env, name) },
+        }
+    }
+}
+
+/// A native value to splice into a result, addressed by row and column
+#[derive(Clone)]
+pub struct NativeCell {
+    /// Row inside a result array or keyed object, or `None` when the result is a single row
+    pub row: Option<NativeKey>,
+    /// Column (object key, or index for value arrays) to replace
+    pub column: NativeKey,
+    /// The value to create
+    pub value: NativeValue,
+}
+
+/// A row object plus the native cells (e.g. Buffers) still to be placed into it
+pub type RowWithNatives = (Map<String, Value>, Vec<NativeCell>);
+
+/// Address cells recorded while converting a single row to that row's position in the result
+pub fn place_in_row(cells: &mut [NativeCell], row: NativeKey) {
+    for cell in cells {
+        cell.row = Some(row.clone());
+    }
+}
+
+/// Query result - JSON rows plus any native cells spliced in during conversion to JS
+pub struct ResultValue {
+    value: Value,
+    natives: Vec<NativeCell>,
+}
+
+impl ResultValue {
+    /// Create a result with native cells to apply on top of the JSON value
+    pub fn with_natives(value: Value, natives: Vec<NativeCell>) -> Self {
+        ResultValue { value, natives }
+    }
+}
+
+impl From<Value> for ResultValue {
+    fn from(value: Value) -> Self {
+        ResultValue {
+            value,
+            natives: Vec::new(),
+        }
+    }
+}
+
+impl TypeName for ResultValue {
+    fn type_name() -> &'static str {
+        "any"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Unknown
+    }
+}
+
+impl ToNapiValue for ResultValue {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        let result = unsafe { Value::to_napi_value(env, val.value)? };
+        for cell in val.natives {
+            let target = match cell.row {
+                Some(row) => {
+                    let key = unsafe { NativeKey::to_napi(env, row)? };
+                    let mut element = std::ptr::null_mut();
+                    check_status!(unsafe {
+                        sys::napi_get_property(env, result, key, &mut element)
+                    })?;
+                    element
+                }
+                None => result,
+            };
+            let mut native = std::ptr::null_mut();
+            match cell.value {
+                NativeValue::Date(millis) => {
+                    check_status!(unsafe { sys::napi_create_date(env, millis, &mut native) })?;
+                }
+                NativeValue::Buffer(bytes) => {
+                    native = unsafe { Buffer::to_napi_value(env, bytes.into())? };
+                }
+            }
+            let key = unsafe { NativeKey::to_napi(env, cell.column)? };
+            check_status!(unsafe { sys::napi_set_property(env, target, key, native) })?;
+        }
+        Ok(result)
+    }
+}
+
+/// A statement result paired with how long the statement took to run
+pub struct TimedResult<T> {
+    /// Property holding the result (e.g. `rows`)
+    field: &'static str,
+    value: T,
+    duration_micros: f64,
+}
+
+impl<T> TimedResult<T> {
+    /// Pair `value` with the time elapsed since `start`
+    pub fn new(field: &'static str, value: T, start: std::time::Instant) -> Self {
+        TimedResult {
+            field,
+            value,
+            duration_micros: start.elapsed().as_micros() as f64,
+        }
+    }
+}
+
+impl<T> TypeName for TimedResult<T> {
+    fn type_name() -> &'static str {
+        "object"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+impl<T: ToNapiValue> ToNapiValue for TimedResult<T> {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        let mut object = std::ptr::null_mut();
+        check_status!(unsafe { sys::napi_create_object(env, &mut object) })?;
+        let value = unsafe { T::to_napi_value(env, val.value)? };
+        let key = unsafe { String::to_napi_value(env, val.field.to_string())? };
+        check_status!(unsafe { sys::napi_set_property(env, object, key, value) })?;
+        let duration = unsafe { f64::to_napi_value(env, val.duration_micros)? };
+        let key = unsafe { String::to_napi_value(env, "durationMicros".to_string())? };
+        check_status!(unsafe { sys::napi_set_property(env, object, key, duration) })?;
+        Ok(object)
+    }
+}
+
+/// One statement's summary from a script, with its rows kept as a `ResultValue` so blobs and
+/// invalid text can still be created natively
+pub struct StatementSummary {
+    summary: Map<String, Value>,
+    rows: Option<ResultValue>,
+}
+
+impl StatementSummary {
+    /// Pair a summary object with the statement's rows, if it returned data
+    pub fn new(summary: Map<String, Value>, rows: Option<ResultValue>) -> Self {
+        StatementSummary { summary, rows }
+    }
+}
+
+impl TypeName for StatementSummary {
+    fn type_name() -> &'static str {
+        "object"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+impl ToNapiValue for StatementSummary {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        let object = unsafe { Value::to_napi_value(env, Value::Object(val.summary))? };
+        if let Some(rows) = val.rows {
+            let rows = unsafe { ResultValue::to_napi_value(env, rows)? };
+            let key = unsafe { String::to_napi_value(env, "rows".to_string())? };
+            check_status!(unsafe { sys::napi_set_property(env, object, key, rows) })?;
+        }
+        Ok(object)
+    }
+}
+
+/// Result type a column can be projected to from its raw SQLite value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnProjection {
+    /// `0`/`1` integers become `false`/`true`
+    Boolean,
+    /// Millisecond timestamps or date strings become a JS `Date`
+    Date,
+    /// Second-based Unix timestamps become a JS `Date`
+    UnixEpoch,
+    /// JSON text is parsed into objects/arrays
+    Json,
+    /// Numbers become their exact decimal text, avoiding float rounding in JS
+    Decimal,
+}
+
+impl ColumnProjection {
+    /// Parse a projection from its name (case insensitive)
+    pub fn parse(name: &str) -> napi::Result<ColumnProjection> {
+        match name.to_lowercase().as_str() {
+            "boolean" | "bool" => Ok(ColumnProjection::Boolean),
+            "date" => Ok(ColumnProjection::Date),
+            "unixepoch" => Ok(ColumnProjection::UnixEpoch),
+            "json" => Ok(ColumnProjection::Json),
+            "decimal" => Ok(ColumnProjection::Decimal),
+            _ => Err(napi::Error::from_reason(format!(
+                "Unknown column type '{}' (expected boolean, date, unixepoch, json or decimal)",
+                name
+            ))),
+        }
+    }
+}
+
+/// Parse a SQLite date/time string into milliseconds since the Unix epoch
+fn parse_date_text(text: &str) -> Option<f64> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(dt.timestamp_millis() as f64);
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(text, format) {
+            return Some(dt.and_utc().timestamp_millis() as f64);
+        }
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis() as f64)
+}
+
+/// Format a REAL with one of SQLite's own printf formats
+fn render_real(f: f64, format: &std::ffi::CStr) -> String {
+    let text = unsafe { rusqlite::ffi::sqlite3_mprintf(format.as_ptr(), f) };
+    if text.is_null() {
+        return f.to_string();
+    }
+    let rendered = unsafe { std::ffi::CStr::from_ptr(text) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { rusqlite::ffi::sqlite3_free(text.cast()) };
+    rendered
+}
+
+/// Render a REAL the way SQLite converts it to text, as `CAST(x AS TEXT)` does (`1.0`, not `1`)
+pub fn sqlite_real_text(f: f64) -> String {
+    render_real(f, c"%!.15g")
+}
+
+/// Render a REAL as decimal text that parses back to the same double
+///
+/// SQLite's own `%!.15g` rendering is used when it round-trips, so a stored `19.99` comes
+/// back as `"19.99"`; values that need more digits get 16 or 17 significant digits.
+fn real_text(f: f64) -> String {
+    let mut text = sqlite_real_text(f);
+    for format in [c"%!.16g", c"%!.17g"] {
+        if text.parse::<f64>() == Ok(f) {
+            break;
+        }
+        text = render_real(f, format);
+    }
+    text
+}
+
+/// Replace the `Decimal`-projected columns of a row object with the exact text of their value
+///
+/// This reads the raw SQLite value, since by the time it is a JSON number integers beyond
+/// 2^53 and REALs that need 17 significant digits have already been rounded. Text is kept as
+/// stored; NULLs and blobs are left untouched.
+pub fn project_decimals(
+    row: &Row,
+    column_names: &[String],
+    map: &mut Map<String, Value>,
+    projections: &[(String, ColumnProjection)],
+) -> Result<(), rusqlite::Error> {
+    for (column, projection) in projections {
+        if *projection != ColumnProjection::Decimal {
+            continue;
+        }
+        // A later column with the same name is the one the row object holds
+        let Some(index) = column_names.iter().rposition(|name| name == column) else {
+            continue;
+        };
+        let text = match row.get_ref(index)? {
+            rusqlite::types::ValueRef::Integer(i) => i.to_string(),
+            rusqlite::types::ValueRef::Real(f) => real_text(f),
+            _ => continue,
+        };
+        map.insert(column.clone(), Value::String(text));
+    }
+    Ok(())
+}
+
+/// Apply column projections to a row object in place
+///
+/// Values that can't be converted (e.g. NULL, or text that isn't valid JSON) are left
+/// untouched. Date conversions are recorded in `natives` since JSON has no date type.
+pub fn project_row(
+    row: &mut Map<String, Value>,
+    projections: &[(String, ColumnProjection)],
+    row_index: Option<usize>,
+    natives: &mut Vec<NativeCell>,
+) {
+    for (column, projection) in projections {
+        let Some(value) = row.get_mut(column) else {
+            continue;
+        };
+        match projection {
+            ColumnProjection::Boolean => {
+                if let Some(n) = value.as_f64() {
+                    *value = Value::Bool(n != 0.0);
+                }
+            }
+            ColumnProjection::Date | ColumnProjection::UnixEpoch => {
+                let millis = match value {
+                    Value::Number(n) => n.as_f64().map(|n| {
+                        if *projection == ColumnProjection::UnixEpoch {
+                            n * 1000.0
+                        } else {
+                            n
+                        }
+                    }),
+                    Value::String(s) => parse_date_text(s),
+                    _ => None,
+                };
+                if let Some(millis) = millis {
+                    natives.push(NativeCell {
+                        row: row_index.map(NativeKey::Index),
+                        column: NativeKey::Name(column.clone()),
+                        value: NativeValue::Date(millis),
+                    });
+                }
+            }
+            ColumnProjection::Json => {
+                if let Value::String(s) = value {
+                    if let Ok(parsed) = serde_json::from_str::<Value>(s) {
+                        *value = parsed;
+                    }
+                }
+            }
+            // Applied to the raw row by project_decimals
+            ColumnProjection::Decimal => {}
+        }
+    }
+}
+
+/// Result rows paired with the storage type of every cell, row by row
+pub struct TypedRows {
+    rows: ResultValue,
+    column_types: Vec<Vec<&'static str>>,
+}
+
+impl TypedRows {
+    pub fn new(rows: ResultValue, column_types: Vec<Vec<&'static str>>) -> Self {
+        TypedRows { rows, column_types }
+    }
+}
+
+impl TypeName for TypedRows {
+    fn type_name() -> &'static str {
+        "object"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+impl ToNapiValue for TypedRows {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        let mut object = std::ptr::null_mut();
+        check_status!(unsafe { sys::napi_create_object(env, &mut object) })?;
+        let rows = unsafe { ResultValue::to_napi_value(env, val.rows)? };
+        let key = unsafe { String::to_napi_value(env, "rows".to_string())? };
+        check_status!(unsafe { sys::napi_set_property(env, object, key, rows) })?;
+        let types = val
+            .column_types
+            .into_iter()
+            .map(|row| row.into_iter().map(str::to_string).collect())
+            .collect::<Vec<Vec<String>>>();
+        let types = unsafe { Vec::<Vec<String>>::to_napi_value(env, types)? };
+        let key = unsafe { String::to_napi_value(env, "columnTypes".to_string())? };
+        check_status!(unsafe { sys::napi_set_property(env, object, key, types) })?;
+        Ok(object)
+    }
+}
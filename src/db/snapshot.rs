@@ -0,0 +1,38 @@
+//! Snapshot module - provides the Snapshot struct for WAL read snapshots
+
+use napi_derive::napi;
+use rusqlite::ffi;
+
+/// Snapshot struct - a point in a WAL database's history that a read transaction can open
+/// Taken with `Database.snapshot()` and opened with `Database.openSnapshot()`
+#[napi]
+pub struct Snapshot {
+    raw: *mut ffi::sqlite3_snapshot,
+}
+
+impl Snapshot {
+    /// Take ownership of a snapshot returned by `sqlite3_snapshot_get` (internal use)
+    pub(crate) fn from_raw(raw: *mut ffi::sqlite3_snapshot) -> Self {
+        Snapshot { raw }
+    }
+
+    /// The snapshot handle to pass to `sqlite3_snapshot_open`
+    pub(crate) fn raw(&self) -> *mut ffi::sqlite3_snapshot {
+        self.raw
+    }
+}
+
+#[napi]
+impl Snapshot {
+    /// Check whether this snapshot is older than `other`
+    #[napi]
+    pub fn is_older_than(&self, other: &Snapshot) -> bool {
+        unsafe { ffi::sqlite3_snapshot_cmp(self.raw, other.raw) < 0 }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        unsafe { ffi::sqlite3_snapshot_free(self.raw) }
+    }
+}
@@ -1,13 +1,21 @@
 //! Statement module - provides the Statement struct for prepared SQL statements
 
 use crate::db::convert_params_container;
-use crate::db::sqlite_to_json;
-use crate::error::to_napi_error;
-use crate::models::QueryResult;
+use crate::db::hooks::{SlowQueryHook, SlowQueryTimer};
+use crate::db::row::{
+    place_in_row, project_decimals, project_row, row_to_object, sqlite_to_json_encoded,
+    storage_type, BlobEncoding, ColumnProjection, NativeCell, NativeKey, ResultValue,
+    RowWithNatives, TextErrorMode, TimedResult, TypedRows, ValueEncoding,
+};
+use crate::error::{lock_connection, lock_with_context, sql_snippet, to_napi_error};
+use crate::models::{QueryResult, StatementStatus};
+use crate::schema::{tokenize_sql, SqlToken};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use rusqlite::{Connection, ToSql};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Column metadata for a prepared statement
 #[napi(object)]
@@ -19,11 +27,178 @@ pub struct ColumnInfo {
     pub type_: String,
 }
 
+/// Per-call options for shaping query results
+#[napi(object)]
+pub struct QueryOptions {
+    /// Map of column name to result type: "boolean" (0/1 -> false/true),
+    /// "date" (millisecond timestamp or date string -> Date),
+    /// "unixepoch" (second timestamp -> Date) or "json" (JSON text -> object)
+    pub column_types: Option<HashMap<String, String>>,
+    /// Columns whose JSON text should be parsed into objects/arrays
+    /// (values that aren't valid JSON are returned as the raw string)
+    pub parse_json: Option<Vec<String>>,
+    /// Throw instead of buffering more than this many rows, overriding the connection's `maxRows`
+    pub max_rows: Option<u32>,
+    /// Columns whose numbers should be returned as exact decimal text
+    /// (e.g. money amounts), instead of floats that may pick up rounding errors
+    pub decimal_columns: Option<Vec<String>>,
+}
+
+impl QueryOptions {
+    /// Resolve the column projections requested by the options
+    fn projections(options: Option<&QueryOptions>) -> Result<Vec<(String, ColumnProjection)>> {
+        let mut projections = Vec::new();
+        if let Some(column_types) = options.and_then(|o| o.column_types.as_ref()) {
+            for (column, type_name) in column_types {
+                projections.push((column.clone(), ColumnProjection::parse(type_name)?));
+            }
+        }
+        if let Some(columns) = options.and_then(|o| o.parse_json.as_ref()) {
+            for column in columns {
+                if !projections.iter().any(|(name, _)| name == column) {
+                    projections.push((column.clone(), ColumnProjection::Json));
+                }
+            }
+        }
+        if let Some(columns) = options.and_then(|o| o.decimal_columns.as_ref()) {
+            for column in columns {
+                if !projections.iter().any(|(name, _)| name == column) {
+                    projections.push((column.clone(), ColumnProjection::Decimal));
+                }
+            }
+        }
+        Ok(projections)
+    }
+}
+
+/// The storage type of each of a row's `column_count` cells
+fn row_storage_types(row: &rusqlite::Row, column_count: usize) -> Result<Vec<&'static str>> {
+    (0..column_count)
+        .map(|i| storage_type(row, i).map_err(to_napi_error))
+        .collect()
+}
+
+/// The words of `sql` outside parentheses, uppercased, skipping comments and quoted text
+/// Each `;` between statements is kept as a ";" entry
+pub(crate) fn top_level_keywords(sql: &str) -> Vec<String> {
+    let mut keywords = Vec::new();
+    let mut depth = 0usize;
+    for token in tokenize_sql(sql) {
+        match token {
+            SqlToken::Punct('(') => depth += 1,
+            SqlToken::Punct(')') => depth = depth.saturating_sub(1),
+            SqlToken::Word(word) if depth == 0 => keywords.push(word.to_ascii_uppercase()),
+            SqlToken::Punct(';') if depth == 0 => keywords.push(";".to_string()),
+            _ => {}
+        }
+    }
+    keywords
+}
+
+/// Classify one statement's top-level keywords as "select", "insert", "update", "delete",
+/// "ddl", "pragma" or "other"
+/// A `WITH` statement is "select" when `readonly`, else takes the kind of the statement after
+/// its CTEs
+fn classify_statement(keywords: &[String], readonly: bool) -> &'static str {
+    let classify = |keyword: &str| match keyword {
+        "SELECT" | "VALUES" => Some("select"),
+        "INSERT" | "REPLACE" => Some("insert"),
+        "UPDATE" => Some("update"),
+        "DELETE" => Some("delete"),
+        "CREATE" | "DROP" | "ALTER" => Some("ddl"),
+        "PRAGMA" => Some("pragma"),
+        _ => None,
+    };
+    let kind = match keywords.first().map(String::as_str) {
+        Some("WITH") if readonly => Some("select"),
+        Some("WITH") => keywords[1..]
+            .iter()
+            .filter_map(|keyword| classify(keyword))
+            .find(|kind| *kind != "select"),
+        Some(keyword) => classify(keyword),
+        None => None,
+    };
+    kind.unwrap_or("other")
+}
+
+/// Run `execute` and pair its result with the rowid it inserted, or `None` if it inserted none
+///
+/// SQLite leaves `last_insert_rowid()` alone when no row is inserted: for UPDATE and DELETE,
+/// an ignored row, an upsert taking its DO UPDATE path or a WITHOUT ROWID table. An `i64::MIN`
+/// sentinel set beforehand tells those apart from a real insert, and the previous value is put
+/// back afterwards when nothing was inserted. SQL that reads `last_insert_rowid()` itself can't
+/// be given the sentinel, so for it only a changed rowid counts as an insert.
+pub(crate) fn with_inserted_rowid<T, E>(
+    conn: &Connection,
+    sql: &str,
+    execute: impl FnOnce() -> std::result::Result<T, E>,
+) -> std::result::Result<(T, Option<i64>), E> {
+    let rowid_before = conn.last_insert_rowid();
+    let sentinel = if sql.to_ascii_lowercase().contains("last_insert_rowid") {
+        rowid_before
+    } else {
+        unsafe { rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), i64::MIN) };
+        i64::MIN
+    };
+    let result = execute();
+    let rowid = conn.last_insert_rowid();
+    if rowid == sentinel {
+        unsafe { rusqlite::ffi::sqlite3_set_last_insert_rowid(conn.handle(), rowid_before) };
+    }
+    result.map(|value| (value, (rowid != sentinel).then_some(rowid)))
+}
+
+/// A compiled statement, either owned by the call or borrowed from the connection's cache
+enum PreparedStatement<'conn> {
+    Fresh(rusqlite::Statement<'conn>),
+    Cached(rusqlite::CachedStatement<'conn>),
+}
+
+impl<'conn> std::ops::Deref for PreparedStatement<'conn> {
+    type Target = rusqlite::Statement<'conn>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            PreparedStatement::Fresh(stmt) => stmt,
+            PreparedStatement::Cached(stmt) => stmt,
+        }
+    }
+}
+
+impl std::ops::DerefMut for PreparedStatement<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            PreparedStatement::Fresh(stmt) => stmt,
+            PreparedStatement::Cached(stmt) => stmt,
+        }
+    }
+}
+
+/// Fail once `buffered` rows are held and another arrives, if that would exceed `max_rows`
+fn check_row_cap(buffered: usize, max_rows: Option<u32>, operation: &str) -> Result<()> {
+    match max_rows {
+        Some(max) if buffered >= max as usize => Err(Error::from_reason(format!(
+            "{}: the query returned more than {} rows (maxRows); add a LIMIT or page through the results",
+            operation, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Statement struct - represents a prepared SQL statement
 #[napi]
 pub struct Statement {
     sql: String,
     conn: Arc<Mutex<Connection>>,
+    blob_encoding: BlobEncoding,
+    text_error_mode: Arc<Mutex<TextErrorMode>>,
+    slow_query_log: SlowQueryHook,
+    /// Most rows all(), values() and iter() may buffer
+    max_rows: Option<u32>,
+    /// Status counters from the most recent execution
+    last_status: Mutex<Option<StatementStatus>>,
+    /// Reuse the compiled statement from the connection's cache instead of preparing each call
+    cached: bool,
 }
 
 /// Iter struct - provides iterator for streaming query results
@@ -31,15 +206,22 @@ pub struct Statement {
 pub struct Iter {
     // Store rows as a vector for iteration
     rows: Vec<serde_json::Value>,
+    // Native cells of each row, addressed by column name without a row
+    natives: Vec<Vec<NativeCell>>,
     column_names: Vec<String>,
     current_index: usize,
 }
 
 impl Iter {
     /// Create a new Iter (internal use)
-    pub(crate) fn new(rows: Vec<serde_json::Value>, column_names: Vec<String>) -> Self {
+    pub(crate) fn new(
+        rows: Vec<serde_json::Value>,
+        natives: Vec<Vec<NativeCell>>,
+        column_names: Vec<String>,
+    ) -> Self {
         Iter {
             rows,
+            natives,
             column_names,
             current_index: 0,
         }
@@ -48,125 +230,459 @@ impl Iter {
 
 impl Statement {
     /// Create a new Statement (internal use)
-    pub(crate) fn new(sql: String, conn: Arc<Mutex<Connection>>) -> Self {
-        Statement { sql, conn }
+    pub(crate) fn new(
+        sql: String,
+        conn: Arc<Mutex<Connection>>,
+        blob_encoding: BlobEncoding,
+        text_error_mode: Arc<Mutex<TextErrorMode>>,
+        slow_query_log: SlowQueryHook,
+        max_rows: Option<u32>,
+        cached: bool,
+    ) -> Self {
+        Statement {
+            sql,
+            conn,
+            blob_encoding,
+            text_error_mode,
+            slow_query_log,
+            max_rows,
+            last_status: Mutex::new(None),
+            cached,
+        }
     }
-}
 
-#[napi]
-impl Statement {
-    /// Execute query and return all rows as objects
-    #[napi]
-    pub fn all(&self, env: Env, params: Option<Unknown>) -> Result<serde_json::Value> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+    /// Compile this statement's SQL, or take it from the connection's cache for cached statements
+    fn prepare<'conn>(&self, conn: &'conn Connection) -> Result<PreparedStatement<'conn>> {
+        let prepared = if self.cached {
+            conn.prepare_cached(&self.sql).map(|stmt| {
+                // Counters survive in the cache; clear them so status() covers one execution
+                use rusqlite::StatementStatus as Counter;
+                for counter in [
+                    Counter::FullscanStep,
+                    Counter::Sort,
+                    Counter::AutoIndex,
+                    Counter::VmStep,
+                    Counter::RePrepare,
+                    Counter::Run,
+                ] {
+                    stmt.reset_status(counter);
+                }
+                PreparedStatement::Cached(stmt)
+            })
+        } else {
+            conn.prepare(&self.sql).map(PreparedStatement::Fresh)
+        };
+        prepared.map_err(|e| {
+            crate::error::to_napi_error_with_context(
+                e,
+                Some(&format!("Prepare failed: {}", sql_snippet(&self.sql))),
+            )
+        })
+    }
+
+    /// Remember the status counters of an executed statement for `status()`
+    fn record_status(&self, stmt: &rusqlite::Statement, operation: &str) {
+        use rusqlite::StatementStatus as Counter;
+        let counter = |c: Counter| stmt.get_status(c) as u32;
+        *lock_with_context(&self.last_status, operation) = Some(StatementStatus {
+            fullscan_steps: counter(Counter::FullscanStep),
+            sorts: counter(Counter::Sort),
+            autoindexes: counter(Counter::AutoIndex),
+            vm_steps: counter(Counter::VmStep),
+            reprepares: counter(Counter::RePrepare),
+            runs: counter(Counter::Run),
+        });
+    }
+
+    /// How this statement currently returns blobs and invalid text
+    fn value_encoding(&self, operation: &str) -> Result<ValueEncoding> {
+        let invalid_text = *lock_with_context(&self.text_error_mode, operation);
+        Ok(ValueEncoding {
+            blobs: self.blob_encoding,
+            invalid_text,
+        })
+    }
 
-        let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+    /// Run the query and collect every row (or the first `limit`) as a column-name keyed map
+    /// When `storage_types` is given, each row's cell storage types are pushed onto it
+    /// Fails if the query produces more than `max_rows` rows
+    /// `Decimal` projections are applied here, while the raw values are still available
+    #[allow(clippy::too_many_arguments)]
+    fn collect_rows(
+        &self,
+        env: &Env,
+        params: Option<Unknown>,
+        operation: &str,
+        mut storage_types: Option<&mut Vec<Vec<&'static str>>>,
+        limit: Option<usize>,
+        max_rows: Option<u32>,
+        projections: &[(String, ColumnProjection)],
+    ) -> Result<Vec<RowWithNatives>> {
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, operation)?;
+
+        let mut stmt = self.prepare(&conn)?;
 
         let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
-        let column_count = stmt.column_count();
 
-        let params_container = convert_params_container(&env, params)?;
+        let params_container = convert_params_container(env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?;
+        let encoding = self.value_encoding(operation)?;
 
-        match params_container {
+        let results = match params_container {
             crate::db::ParamsContainer::Positional(positional_params) => {
                 let params_refs: Vec<&dyn ToSql> =
                     positional_params.iter().map(|p| p as &dyn ToSql).collect();
                 let mut rows = stmt.query(params_refs.as_slice()).map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                    )
                 })?;
                 let mut results = Vec::new();
                 while let Some(row) = rows.next().map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Fetching row failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Fetching row failed: {}", sql_snippet(&self.sql))),
+                    )
                 })? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
+                    check_row_cap(results.len(), max_rows, operation)?;
+                    if let Some(types) = storage_types.as_deref_mut() {
+                        types.push(row_storage_types(row, column_names.len())?);
+                    }
+                    let mut cells = Vec::new();
+                    let mut map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
+                    project_decimals(row, &column_names, &mut map, projections)
+                        .map_err(to_napi_error)?;
+                    results.push((map, cells));
+                    if limit == Some(results.len()) {
+                        break;
                     }
-                    results.push(serde_json::Value::Object(map));
                 }
-                Ok(serde_json::Value::Array(results))
+                results
             }
             crate::db::ParamsContainer::Named(named_params) => {
                 let mut results = Vec::new();
-                // For named params, we need to use a different approach with rusqlite
                 // rusqlite supports named parameters with :name, @name, or $name syntax
-                // We'll convert the named params to rusqlite's named parameter format
-                let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
                 let mut named_params_refs: Vec<(&str, &dyn ToSql)> = Vec::new();
                 for (key, param) in named_params.iter() {
                     named_params_refs.push((key.as_str(), param as &dyn ToSql));
                 }
-                let mut rows = stmt
-                    .query(named_params_refs.as_slice())
-                    .map_err(|e| {
-                        crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", self.sql)))
-                    })?;
+                let mut rows = stmt.query(named_params_refs.as_slice()).map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                    )
+                })?;
                 while let Some(row) = rows.next().map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Fetching row failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Fetching row failed: {}", sql_snippet(&self.sql))),
+                    )
                 })? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
+                    check_row_cap(results.len(), max_rows, operation)?;
+                    if let Some(types) = storage_types.as_deref_mut() {
+                        types.push(row_storage_types(row, column_names.len())?);
+                    }
+                    let mut cells = Vec::new();
+                    let mut map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
+                    project_decimals(row, &column_names, &mut map, projections)
+                        .map_err(to_napi_error)?;
+                    results.push((map, cells));
+                    if limit == Some(results.len()) {
+                        break;
                     }
-                    results.push(serde_json::Value::Object(map));
                 }
-                Ok(serde_json::Value::Array(results))
+                results
             }
+        };
+
+        self.record_status(&stmt, operation);
+        Ok(results)
+    }
+
+    /// Collect every row as an object, applying the per-call result options
+    fn all_rows(
+        &self,
+        env: &Env,
+        params: Option<Unknown>,
+        options: Option<&QueryOptions>,
+        operation: &str,
+        storage_types: Option<&mut Vec<Vec<&'static str>>>,
+    ) -> Result<ResultValue> {
+        let projections = QueryOptions::projections(options)?;
+        let max_rows = options.and_then(|o| o.max_rows).or(self.max_rows);
+        let results = self.collect_rows(
+            env,
+            params,
+            operation,
+            storage_types,
+            None,
+            max_rows,
+            &projections,
+        )?;
+
+        let mut natives = Vec::new();
+        let rows: Vec<serde_json::Value> = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, (mut map, mut cells))| {
+                place_in_row(&mut cells, NativeKey::Index(index));
+                natives.append(&mut cells);
+                project_row(&mut map, &projections, Some(index), &mut natives);
+                serde_json::Value::Object(map)
+            })
+            .collect();
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Array(rows),
+            natives,
+        ))
+    }
+
+    /// Return the only row the query produces, failing if it produces more than one
+    /// Zero rows returns null when `allow_none` is set and fails otherwise
+    fn single_row(
+        &self,
+        env: &Env,
+        params: Option<Unknown>,
+        options: Option<&QueryOptions>,
+        operation: &str,
+        allow_none: bool,
+    ) -> Result<ResultValue> {
+        let projections = QueryOptions::projections(options)?;
+        let mut results =
+            self.collect_rows(env, params, operation, None, Some(2), None, &projections)?;
+        if results.len() > 1 {
+            return Err(Error::from_reason(format!(
+                "{}: expected {} one row, but the query returned more than one",
+                operation,
+                if allow_none { "at most" } else { "exactly" }
+            )));
+        }
+        match results.pop() {
+            Some((mut map, mut natives)) => {
+                project_row(&mut map, &projections, None, &mut natives);
+                Ok(ResultValue::with_natives(
+                    serde_json::Value::Object(map),
+                    natives,
+                ))
+            }
+            None if allow_none => Ok(serde_json::Value::Null.into()),
+            None => Err(Error::from_reason(format!(
+                "{}: expected exactly one row, but the query returned none",
+                operation
+            ))),
         }
     }
+}
+
+#[napi]
+impl Statement {
+    /// Execute query and return all rows as objects
+    #[napi(ts_return_type = "any[]")]
+    pub fn all(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<ResultValue> {
+        self.all_rows(&env, params, options.as_ref(), "Statement.all", None)
+    }
+
+    /// Like `all`, but also report the storage type SQLite holds for every cell
+    /// `columnTypes[row][column]` is "integer", "real", "text", "blob" or "null"
+    #[napi(ts_return_type = "{ rows: any[]; columnTypes: string[][] }")]
+    pub fn all_with_types(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<TypedRows> {
+        let mut column_types = Vec::new();
+        let rows = self.all_rows(
+            &env,
+            params,
+            options.as_ref(),
+            "Statement.allWithTypes",
+            Some(&mut column_types),
+        )?;
+        Ok(TypedRows::new(rows, column_types))
+    }
+
+    /// Execute query and transform the rows in JS with one mapper call per chunk instead of per row
+    /// `mapper(rows, offset)` gets the rows in query order, all at once or `chunkSize` at a time,
+    /// and must return an array of the same length; if it throws, the whole call throws
+    #[napi(
+        ts_args_type = "params: any, mapper: (rows: any[], offset: number) => any[], chunkSize?: number",
+        ts_return_type = "any[]"
+    )]
+    pub fn all_mapped<'env>(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        mapper: Function<'env, FnArgs<(ResultValue, u32)>, Unknown<'env>>,
+        chunk_size: Option<u32>,
+    ) -> Result<Vec<Unknown<'env>>> {
+        if chunk_size == Some(0) {
+            return Err(Error::from_reason(
+                "Statement.allMapped: chunkSize must be at least 1",
+            ));
+        }
+        let results = self.collect_rows(
+            &env,
+            params,
+            "Statement.allMapped",
+            None,
+            None,
+            self.max_rows,
+            &[],
+        )?;
+        let chunk_size = chunk_size.map_or(results.len(), |n| n as usize).max(1);
+
+        let mut mapped = Vec::with_capacity(results.len());
+        let mut rows = results.into_iter();
+        loop {
+            let chunk: Vec<RowWithNatives> = rows.by_ref().take(chunk_size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let offset = mapped.len();
+            let expected = chunk.len();
+            let mut natives = Vec::new();
+            let values = chunk
+                .into_iter()
+                .enumerate()
+                .map(|(index, (map, mut cells))| {
+                    place_in_row(&mut cells, NativeKey::Index(index));
+                    natives.append(&mut cells);
+                    serde_json::Value::Object(map)
+                })
+                .collect();
+            let returned = mapper.call(FnArgs::from((
+                ResultValue::with_natives(serde_json::Value::Array(values), natives),
+                offset as u32,
+            )))?;
+            let array = if returned.is_array()? {
+                unsafe { returned.cast::<Array>()? }
+            } else {
+                return Err(Error::from_reason(format!(
+                    "Statement.allMapped: the mapper must return an array (rows {} to {})",
+                    offset,
+                    offset + expected - 1
+                )));
+            };
+            if array.len() as usize != expected {
+                return Err(Error::from_reason(format!(
+                    "Statement.allMapped: the mapper returned {} elements for {} rows (rows {} to {})",
+                    array.len(),
+                    expected,
+                    offset,
+                    offset + expected - 1
+                )));
+            }
+            for index in 0..array.len() {
+                if let Some(value) = array.get::<Unknown>(index)? {
+                    mapped.push(value);
+                }
+            }
+        }
+        Ok(mapped)
+    }
+
+    /// Execute query and return an object of rows keyed by `keyColumn`
+    /// Duplicate keys throw unless `lastWins` is set, in which case the last row is kept
+    #[napi(ts_return_type = "Record<string, any>")]
+    pub fn all_keyed(
+        &self,
+        env: Env,
+        key_column: String,
+        params: Option<Unknown>,
+        last_wins: Option<bool>,
+    ) -> Result<ResultValue> {
+        let results = self.collect_rows(
+            &env,
+            params,
+            "Statement.allKeyed",
+            None,
+            None,
+            self.max_rows,
+            &[],
+        )?;
+        let last_wins = last_wins.unwrap_or(false);
+
+        let mut keyed = serde_json::Map::with_capacity(results.len());
+        let mut keyed_natives: HashMap<String, Vec<NativeCell>> = HashMap::new();
+        for (map, mut cells) in results {
+            let key = match map.get(&key_column) {
+                None => {
+                    return Err(Error::from_reason(format!(
+                        "Key column '{}' is not in the result set",
+                        key_column
+                    )))
+                }
+                Some(serde_json::Value::Null) => {
+                    return Err(Error::from_reason(format!(
+                        "Key column '{}' contains NULL",
+                        key_column
+                    )))
+                }
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+            };
+            if !last_wins && keyed.contains_key(&key) {
+                return Err(Error::from_reason(format!(
+                    "Duplicate key '{}' in column '{}'",
+                    key, key_column
+                )));
+            }
+            place_in_row(&mut cells, NativeKey::Name(key.clone()));
+            keyed_natives.insert(key.clone(), cells);
+            keyed.insert(key, serde_json::Value::Object(map));
+        }
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Object(keyed),
+            keyed_natives.into_values().flatten().collect(),
+        ))
+    }
 
     /// Execute query and return first row as object
-    #[napi]
-    pub fn get(&self, env: Env, params: Option<Unknown>) -> Result<serde_json::Value> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+    #[napi(ts_return_type = "any")]
+    pub fn get(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<ResultValue> {
+        let projections = QueryOptions::projections(options.as_ref())?;
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, "Statement.get")?;
 
-        let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+        let mut stmt = self.prepare(&conn)?;
 
         let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
-        let column_count = stmt.column_count();
 
-        let params_container = convert_params_container(&env, params)?;
+        let params_container = convert_params_container(&env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?;
+        let encoding = self.value_encoding("Statement.get")?;
 
-        match params_container {
+        let result = match params_container {
             crate::db::ParamsContainer::Positional(positional_params) => {
                 let params_refs: Vec<&dyn ToSql> =
                     positional_params.iter().map(|p| p as &dyn ToSql).collect();
                 let mut rows = stmt.query(params_refs.as_slice()).map_err(to_napi_error)?;
                 if let Some(row) = rows.next().map_err(to_napi_error)? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
-                    }
-                    Ok(serde_json::Value::Object(map))
+                    let mut cells = Vec::new();
+                    let mut map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
+                    project_decimals(row, &column_names, &mut map, &projections)
+                        .map_err(to_napi_error)?;
+                    Some((map, cells))
                 } else {
-                    Ok(serde_json::Value::Null)
+                    None
                 }
             }
             crate::db::ParamsContainer::Named(named_params) => {
@@ -178,101 +694,247 @@ impl Statement {
                     .query(named_params_refs.as_slice())
                     .map_err(to_napi_error)?;
                 if let Some(row) = rows.next().map_err(to_napi_error)? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
-                    }
-                    Ok(serde_json::Value::Object(map))
+                    let mut cells = Vec::new();
+                    let mut map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
+                    project_decimals(row, &column_names, &mut map, &projections)
+                        .map_err(to_napi_error)?;
+                    Some((map, cells))
                 } else {
-                    Ok(serde_json::Value::Null)
+                    None
                 }
             }
+        };
+        self.record_status(&stmt, "Statement.get");
+
+        match result {
+            Some((mut map, mut natives)) => {
+                project_row(&mut map, &projections, None, &mut natives);
+                Ok(ResultValue::with_natives(
+                    serde_json::Value::Object(map),
+                    natives,
+                ))
+            }
+            None => Ok(serde_json::Value::Null.into()),
         }
     }
 
+    /// Execute query and return `{ found, row }` for its first row
+    /// Unlike get(), "no row" (`found: false`) can't be confused with a null value
+    #[napi(ts_return_type = "{ found: boolean; row: any }")]
+    pub fn try_get(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<ResultValue> {
+        let projections = QueryOptions::projections(options.as_ref())?;
+        let mut results = self.collect_rows(
+            &env,
+            params,
+            "Statement.tryGet",
+            None,
+            Some(1),
+            None,
+            &projections,
+        )?;
+        let mut wrapper = serde_json::Map::new();
+        wrapper.insert("found".to_string(), (!results.is_empty()).into());
+        let mut natives = Vec::new();
+        let row = match results.pop() {
+            Some((mut map, mut cells)) => {
+                project_row(&mut map, &projections, None, &mut cells);
+                place_in_row(&mut cells, NativeKey::Name("row".to_string()));
+                natives = cells;
+                serde_json::Value::Object(map)
+            }
+            None => serde_json::Value::Null,
+        };
+        wrapper.insert("row".to_string(), row);
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Object(wrapper),
+            natives,
+        ))
+    }
+
+    /// Execute query and return its only row as an object
+    /// Throws if the query returns no rows or more than one
+    #[napi(ts_return_type = "any")]
+    pub fn get_one(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<ResultValue> {
+        self.single_row(&env, params, options.as_ref(), "Statement.getOne", false)
+    }
+
+    /// Execute query and return its only row as an object, or null if there are none
+    /// Throws if the query returns more than one row
+    #[napi(ts_return_type = "any")]
+    pub fn get_optional(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<ResultValue> {
+        self.single_row(
+            &env,
+            params,
+            options.as_ref(),
+            "Statement.getOptional",
+            true,
+        )
+    }
+
+    /// Check whether the query produces at least one row
+    /// Steps the statement once and discards the row without converting it
+    #[napi]
+    pub fn exists(&self, env: Env, params: Option<Unknown>) -> Result<bool> {
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, "Statement.exists")?;
+
+        let mut stmt = self.prepare(&conn)?;
+        convert_params_container(&env, params)?
+            .bind(&mut stmt)
+            .map_err(to_napi_error)?;
+
+        let found = stmt
+            .raw_query()
+            .next()
+            .map_err(|e| {
+                crate::error::to_napi_error_with_context(
+                    e,
+                    Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                )
+            })?
+            .is_some();
+        self.record_status(&stmt, "Statement.exists");
+        Ok(found)
+    }
+
     /// Execute query and return metadata (changes, last_insert_rowid)
     #[napi]
     pub fn run(&self, env: Env, params: Option<Unknown>) -> Result<QueryResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, "Statement.run")?;
+        let changes_before = conn.total_changes();
 
-        let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+        let mut stmt = self.prepare(&conn)?;
 
-        let params_container = convert_params_container(&env, params)?;
+        let params_container = convert_params_container(&env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?;
 
-        match params_container {
-            crate::db::ParamsContainer::Positional(positional_params) => {
-                let params_refs: Vec<&dyn ToSql> =
-                    positional_params.iter().map(|p| p as &dyn ToSql).collect();
-                let changes = stmt
-                    .execute(params_refs.as_slice())
-                    .map_err(|e| {
-                        crate::error::to_napi_error_with_context(e, Some(&format!("Run failed: {}", self.sql)))
-                    })?;
-                Ok(QueryResult {
-                    changes: changes as u32,
-                    last_insert_rowid: conn.last_insert_rowid(),
-                })
-            }
-            crate::db::ParamsContainer::Named(named_params) => {
-                let mut named_params_refs: Vec<(&str, &dyn ToSql)> = Vec::new();
-                for (key, param) in named_params.iter() {
-                    named_params_refs.push((key.as_str(), param as &dyn ToSql));
+        let (changes, last_insert_rowid) =
+            with_inserted_rowid(&conn, &self.sql, || match params_container {
+                crate::db::ParamsContainer::Positional(positional_params) => {
+                    let params_refs: Vec<&dyn ToSql> =
+                        positional_params.iter().map(|p| p as &dyn ToSql).collect();
+                    stmt.execute(params_refs.as_slice()).map_err(|e| {
+                        crate::error::to_napi_error_with_context(
+                            e,
+                            Some(&format!("Run failed: {}", sql_snippet(&self.sql))),
+                        )
+                    })
                 }
-                let changes = stmt
-                    .execute(named_params_refs.as_slice())
-                    .map_err(to_napi_error)?;
-                Ok(QueryResult {
-                    changes: changes as u32,
-                    last_insert_rowid: conn.last_insert_rowid(),
-                })
-            }
-        }
+                crate::db::ParamsContainer::Named(named_params) => {
+                    let mut named_params_refs: Vec<(&str, &dyn ToSql)> = Vec::new();
+                    for (key, param) in named_params.iter() {
+                        named_params_refs.push((key.as_str(), param as &dyn ToSql));
+                    }
+                    stmt.execute(named_params_refs.as_slice())
+                        .map_err(to_napi_error)
+                }
+            })?;
+        self.record_status(&stmt, "Statement.run");
+
+        Ok(QueryResult {
+            changes: changes as u32,
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
+        })
+    }
+
+    /// Like `all`, but also report how long the query took
+    #[napi(ts_return_type = "{ rows: any[]; durationMicros: number }")]
+    pub fn all_timed(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<TimedResult<ResultValue>> {
+        let start = Instant::now();
+        let rows = self.all(env, params, options)?;
+        Ok(TimedResult::new("rows", rows, start))
+    }
+
+    /// Like `get`, but also report how long the query took
+    #[napi(ts_return_type = "{ row: any; durationMicros: number }")]
+    pub fn get_timed(
+        &self,
+        env: Env,
+        params: Option<Unknown>,
+        options: Option<QueryOptions>,
+    ) -> Result<TimedResult<ResultValue>> {
+        let start = Instant::now();
+        let row = self.get(env, params, options)?;
+        Ok(TimedResult::new("row", row, start))
+    }
+
+    /// Like `run`, but also report how long the statement took
+    #[napi(ts_return_type = "{ result: QueryResult; durationMicros: number }")]
+    pub fn run_timed(&self, env: Env, params: Option<Unknown>) -> Result<TimedResult<QueryResult>> {
+        let start = Instant::now();
+        let result = self.run(env, params)?;
+        Ok(TimedResult::new("result", result, start))
     }
 
     /// Execute query and return all rows as arrays (values)
-    #[napi]
-    pub fn values(&self, env: Env, params: Option<Unknown>) -> Result<serde_json::Value> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-
-        let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+    #[napi(ts_return_type = "any")]
+    pub fn values(&self, env: Env, params: Option<Unknown>) -> Result<ResultValue> {
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, "Statement.values")?;
+
+        let mut stmt = self.prepare(&conn)?;
         let column_count = stmt.column_count();
 
-        let params_container = convert_params_container(&env, params)?;
+        let params_container = convert_params_container(&env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?;
+        let encoding = self.value_encoding("Statement.values")?;
+        let mut natives = Vec::new();
 
-        match params_container {
+        let results = match params_container {
             crate::db::ParamsContainer::Positional(positional_params) => {
                 let params_refs: Vec<&dyn ToSql> =
                     positional_params.iter().map(|p| p as &dyn ToSql).collect();
                 let mut rows = stmt.query(params_refs.as_slice()).map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                    )
                 })?;
                 let mut results = Vec::new();
                 while let Some(row) = rows.next().map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Fetching row failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Fetching row failed: {}", sql_snippet(&self.sql))),
+                    )
                 })? {
+                    check_row_cap(results.len(), self.max_rows, "Statement.values")?;
                     let mut row_arr = Vec::new();
+                    let mut cells = Vec::new();
                     for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
+                        let val = sqlite_to_json_encoded(row, i, encoding, &mut cells)
+                            .map_err(to_napi_error)?;
                         row_arr.push(val);
                     }
+                    place_in_row(&mut cells, NativeKey::Index(results.len()));
+                    natives.append(&mut cells);
                     results.push(serde_json::Value::Array(row_arr));
                 }
-                Ok(serde_json::Value::Array(results))
+                results
             }
             crate::db::ParamsContainer::Named(named_params) => {
                 let mut named_params_refs: Vec<(&str, &dyn ToSql)> = Vec::new();
@@ -284,16 +946,27 @@ impl Statement {
                     .map_err(to_napi_error)?;
                 let mut results = Vec::new();
                 while let Some(row) = rows.next().map_err(to_napi_error)? {
+                    check_row_cap(results.len(), self.max_rows, "Statement.values")?;
                     let mut row_arr = Vec::new();
+                    let mut cells = Vec::new();
                     for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
+                        let val = sqlite_to_json_encoded(row, i, encoding, &mut cells)
+                            .map_err(to_napi_error)?;
                         row_arr.push(val);
                     }
+                    place_in_row(&mut cells, NativeKey::Index(results.len()));
+                    natives.append(&mut cells);
                     results.push(serde_json::Value::Array(row_arr));
                 }
-                Ok(serde_json::Value::Array(results))
+                results
             }
-        }
+        };
+        self.record_status(&stmt, "Statement.values");
+
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Array(results),
+            natives,
+        ))
     }
 
     /// Finalize the statement, releasing resources
@@ -306,40 +979,41 @@ impl Statement {
     /// Returns an Iter object that can be used to fetch rows one at a time
     #[napi]
     pub fn iter(&self, env: Env, params: Option<Unknown>) -> Result<Iter> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-
-        let mut stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+        let _timer = SlowQueryTimer::start(&self.slow_query_log, &self.sql);
+        let conn = lock_connection(&self.conn, "Statement.iter")?;
+
+        let mut stmt = self.prepare(&conn)?;
         let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
-        let column_count = stmt.column_count();
 
-        let params_container = convert_params_container(&env, params)?;
+        let params_container = convert_params_container(&env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?;
+        let encoding = self.value_encoding("Statement.iter")?;
+        let mut natives = Vec::new();
 
         let rows: Vec<serde_json::Value> = match params_container {
             crate::db::ParamsContainer::Positional(positional_params) => {
                 let params_refs: Vec<&dyn ToSql> =
                     positional_params.iter().map(|p| p as &dyn ToSql).collect();
                 let mut rows_iter = stmt.query(params_refs.as_slice()).map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                    )
                 })?;
                 let mut rows = Vec::new();
                 while let Some(row) = rows_iter.next().map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Fetching row failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Fetching row failed: {}", sql_snippet(&self.sql))),
+                    )
                 })? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
-                    }
+                    check_row_cap(rows.len(), self.max_rows, "Statement.iter")?;
+                    let mut cells = Vec::new();
+                    let map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
                     rows.push(serde_json::Value::Object(map));
+                    natives.push(cells);
                 }
                 rows
             }
@@ -348,44 +1022,40 @@ impl Statement {
                 for (key, param) in named_params.iter() {
                     named_params_refs.push((key.as_str(), param as &dyn ToSql));
                 }
-                let mut rows_iter = stmt
-                    .query(named_params_refs.as_slice())
-                    .map_err(|e| {
-                        crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", self.sql)))
-                    })?;
+                let mut rows_iter = stmt.query(named_params_refs.as_slice()).map_err(|e| {
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Query failed: {}", sql_snippet(&self.sql))),
+                    )
+                })?;
                 let mut rows = Vec::new();
                 while let Some(row) = rows_iter.next().map_err(|e| {
-                    crate::error::to_napi_error_with_context(e, Some(&format!("Fetching row failed: {}", self.sql)))
+                    crate::error::to_napi_error_with_context(
+                        e,
+                        Some(&format!("Fetching row failed: {}", sql_snippet(&self.sql))),
+                    )
                 })? {
-                    let mut map = serde_json::Map::new();
-                    for i in 0..column_count {
-                        let val = sqlite_to_json(row, i).map_err(to_napi_error)?;
-                        let name = column_names
-                            .get(i)
-                            .cloned()
-                            .unwrap_or_else(|| format!("col_{}", i));
-                        map.insert(name, val);
-                    }
+                    check_row_cap(rows.len(), self.max_rows, "Statement.iter")?;
+                    let mut cells = Vec::new();
+                    let map = row_to_object(row, &column_names, encoding, &mut cells)
+                        .map_err(to_napi_error)?;
                     rows.push(serde_json::Value::Object(map));
+                    natives.push(cells);
                 }
                 rows
             }
         };
+        self.record_status(&stmt, "Statement.iter");
 
-        Ok(Iter::new(rows, column_names))
+        Ok(Iter::new(rows, natives, column_names))
     }
 
     /// Get column metadata for this statement
     /// Returns an array of column information objects
     #[napi]
     pub fn columns(&self) -> Result<Vec<ColumnInfo>> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
-        let stmt = conn.prepare(&self.sql).map_err(|e| {
-            crate::error::to_napi_error_with_context(e, Some(&format!("Prepare failed: {}", self.sql)))
-        })?;
+        let conn = lock_connection(&self.conn, "Statement.columns")?;
+        let stmt = self.prepare(&conn)?;
 
         let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
 
@@ -403,6 +1073,68 @@ impl Statement {
         Ok(columns)
     }
 
+    /// Get the number of result columns without executing the statement
+    /// Statements that return no data, such as INSERT, have 0 columns
+    #[napi]
+    pub fn column_count(&self) -> Result<u32> {
+        let conn = lock_connection(&self.conn, "Statement.columnCount")?;
+        let stmt = self.prepare(&conn)?;
+        Ok(stmt.column_count() as u32)
+    }
+
+    /// Classify the statement by what it does: "select", "insert", "update", "delete", "ddl",
+    /// "pragma" or "other"
+    /// A `WITH` statement is classified by SQLite's read-only flag and the statement after its CTEs
+    #[napi]
+    pub fn kind(&self) -> Result<String> {
+        let conn = lock_connection(&self.conn, "Statement.kind")?;
+        let stmt = self.prepare(&conn)?;
+
+        let keywords = top_level_keywords(&self.sql);
+        // Only the first statement is compiled, so only its keywords count
+        let statement = keywords
+            .split(|keyword| keyword == ";")
+            .next()
+            .unwrap_or(&[]);
+        Ok(classify_statement(statement, stmt.readonly()).to_string())
+    }
+
+    /// Get the SQL with `params` substituted as literals, as SQLite would run it
+    /// The statement is bound but never stepped; blobs render as `X'...'`
+    #[napi]
+    pub fn expanded_sql(&self, env: Env, params: Option<Unknown>) -> Result<String> {
+        let conn = lock_connection(&self.conn, "Statement.expandedSql")?;
+        let mut stmt = self.prepare(&conn)?;
+
+        match convert_params_container(&env, params)?
+            .resolve_names(&stmt)
+            .map_err(to_napi_error)?
+        {
+            crate::db::ParamsContainer::Positional(positional_params) => {
+                for (i, param) in positional_params.iter().enumerate() {
+                    stmt.raw_bind_parameter(i + 1, param)
+                        .map_err(to_napi_error)?;
+                }
+            }
+            crate::db::ParamsContainer::Named(named_params) => {
+                for (key, param) in named_params.iter() {
+                    stmt.raw_bind_parameter(key.as_str(), param)
+                        .map_err(to_napi_error)?;
+                }
+            }
+        }
+
+        stmt.expanded_sql()
+            .ok_or_else(|| Error::from_reason("SQLite could not expand the statement"))
+    }
+
+    /// Get SQLite's status counters from the last time this statement ran
+    /// Returns null until the statement has been executed
+    #[napi]
+    pub fn status(&self) -> Option<StatementStatus> {
+        *lock_with_context(&self.last_status, "Statement.status")
+    }
+
     /// Get the original SQL string for this statement
     #[napi]
     pub fn source(&self) -> String {
@@ -421,27 +1153,29 @@ impl Iter {
     /// Continue iterating and get the next row as an object
     /// Returns null when there are no more rows
     #[allow(clippy::should_implement_trait)]
-    #[napi]
-    pub fn next(&mut self) -> Result<Option<serde_json::Value>> {
+    #[napi(ts_return_type = "any | null")]
+    pub fn next(&mut self) -> Result<Option<ResultValue>> {
         if self.current_index >= self.rows.len() {
             return Ok(None);
         }
 
         let row = self.rows[self.current_index].clone();
+        let natives = self.natives[self.current_index].clone();
         self.current_index += 1;
-        Ok(Some(row))
+        Ok(Some(ResultValue::with_natives(row, natives)))
     }
 
     /// Continue iterating and get the next row as an array of values
     /// Returns null when there are no more rows
-    #[napi]
-    pub fn next_values(&mut self) -> Result<Option<serde_json::Value>> {
+    #[napi(ts_return_type = "any | null")]
+    pub fn next_values(&mut self) -> Result<Option<ResultValue>> {
         if self.current_index >= self.rows.len() {
             return Ok(None);
         }
 
         // Convert the current row object to an array
         let row = self.rows[self.current_index].clone();
+        let cells = self.natives[self.current_index].clone();
         self.current_index += 1;
 
         if let serde_json::Value::Object(map) = row {
@@ -450,7 +1184,24 @@ impl Iter {
                 let val = map.get(name).cloned().unwrap_or(serde_json::Value::Null);
                 arr.push(val);
             }
-            Ok(Some(serde_json::Value::Array(arr)))
+            // Re-address native cells from column names to array positions
+            let natives = cells
+                .into_iter()
+                .filter_map(|cell| {
+                    let NativeKey::Name(name) = &cell.column else {
+                        return None;
+                    };
+                    let index = self.column_names.iter().position(|c| c == name)?;
+                    Some(NativeCell {
+                        column: NativeKey::Index(index),
+                        ..cell
+                    })
+                })
+                .collect();
+            Ok(Some(ResultValue::with_natives(
+                serde_json::Value::Array(arr),
+                natives,
+            )))
         } else {
             Ok(None)
         }
@@ -463,11 +1214,20 @@ impl Iter {
     }
 
     /// Get all remaining rows at once
-    #[napi]
-    pub fn all(&mut self) -> Result<serde_json::Value> {
+    #[napi(ts_return_type = "any")]
+    pub fn all(&mut self) -> Result<ResultValue> {
         let remaining: Vec<serde_json::Value> = self.rows[self.current_index..].to_vec();
+        let mut natives = Vec::new();
+        for (index, cells) in self.natives[self.current_index..].iter().enumerate() {
+            let mut cells = cells.clone();
+            place_in_row(&mut cells, NativeKey::Index(index));
+            natives.append(&mut cells);
+        }
         self.current_index = self.rows.len();
-        Ok(serde_json::Value::Array(remaining))
+        Ok(ResultValue::with_natives(
+            serde_json::Value::Array(remaining),
+            natives,
+        ))
     }
 
     /// Reset the iterator to the beginning
@@ -475,4 +1235,13 @@ impl Iter {
     pub fn reset(&mut self) {
         self.current_index = 0;
     }
+
+    /// Stop iterating and free the buffered rows right away
+    /// Afterwards the iterator is empty: `hasMore()` is false and `next()` returns null
+    #[napi]
+    pub fn close(&mut self) {
+        self.rows = Vec::new();
+        self.natives = Vec::new();
+        self.current_index = 0;
+    }
 }
@@ -1,11 +1,12 @@
 //! Transaction module - provides the Transaction struct for SQLite transactions
 
-use crate::db::convert_params;
-use crate::error::to_napi_error;
+use crate::db::convert_params_container;
+use crate::db::statement::with_inserted_rowid;
+use crate::error::{lock_connection, to_napi_error};
 use crate::models::{QueryResult, TransactionResult};
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use rusqlite::{Connection, ToSql};
+use rusqlite::Connection;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 
@@ -48,24 +49,20 @@ impl Transaction {
     /// QueryResult with changes and last_insert_rowid
     #[napi]
     pub fn run(&self, env: Env, sql: String, params: Option<Unknown>) -> Result<QueryResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "Transaction.run")?;
+        let changes_before = conn.total_changes();
 
-        let rusqlite_params = convert_params(&env, params)?;
-        let params_refs: Vec<&dyn ToSql> =
-            rusqlite_params.iter().map(|p| p as &dyn ToSql).collect();
-
-        conn.execute(&sql, params_refs.as_slice())
-            .map_err(|e| {
-                let snippet = if sql.len() > 100 { format!("{}...", &sql[..100]) } else { sql.clone() };
-                crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", snippet)))
-            })?;
+        let params_container = convert_params_container(&env, params)?;
+        let result = with_inserted_rowid(&conn, &sql, || params_container.execute(&conn, &sql));
+        let (_, last_insert_rowid) = result.map_err(|e| {
+            let snippet = crate::error::sql_snippet(&sql);
+            crate::error::to_napi_error_with_context(e, Some(&format!("Query failed: {}", snippet)))
+        })?;
 
         Ok(QueryResult {
             changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
+            last_insert_rowid,
+            total_changes: (conn.total_changes() - changes_before) as u32,
         })
     }
 
@@ -75,10 +72,7 @@ impl Transaction {
     /// TransactionResult with changes and last_insert_rowid
     #[napi]
     pub fn commit(&self) -> Result<TransactionResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "Transaction.commit")?;
 
         // If this is a savepoint, release it; otherwise commit
         if let Some(ref savepoint) = self.savepoint_name {
@@ -93,7 +87,7 @@ impl Transaction {
 
         Ok(TransactionResult {
             changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
+            last_insert_rowid: None,
         })
     }
 
@@ -103,10 +97,7 @@ impl Transaction {
     /// TransactionResult with changes and last_insert_rowid
     #[napi]
     pub fn rollback(&self) -> Result<TransactionResult> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "Transaction.rollback")?;
 
         // If this is a savepoint, rollback to it; otherwise rollback the transaction
         if let Some(ref savepoint) = self.savepoint_name {
@@ -124,10 +115,26 @@ impl Transaction {
 
         Ok(TransactionResult {
             changes: conn.changes() as u32,
-            last_insert_rowid: conn.last_insert_rowid(),
+            last_insert_rowid: None,
         })
     }
 
+    /// Defer foreign key checks until the outermost transaction commits,
+    /// so rows can be updated in an order that's temporarily inconsistent
+    /// The setting resets automatically when the transaction ends
+    #[napi]
+    pub fn defer_foreign_keys(&self) -> Result<()> {
+        let conn = lock_connection(&self.conn, "Transaction.deferForeignKeys")?;
+        conn.pragma_update(None, "defer_foreign_keys", true)
+            .map_err(to_napi_error)
+    }
+
+    /// Get the savepoint name, or null for a top-level transaction
+    #[napi]
+    pub fn savepoint_name(&self) -> Option<String> {
+        self.savepoint_name.clone()
+    }
+
     /// Create a savepoint for nested transactions
     ///
     /// # Arguments
@@ -137,10 +144,7 @@ impl Transaction {
     /// A new Transaction object representing the savepoint
     #[napi]
     pub fn savepoint(&self, name: String) -> Result<Transaction> {
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|_| Error::from_reason("DB Lock failed"))?;
+        let conn = lock_connection(&self.conn, "Transaction.savepoint")?;
 
         conn.execute(&format!("SAVEPOINT {}", name), [])
             .map_err(to_napi_error)?;
@@ -1,11 +1,125 @@
-use napi::{Error, Status};
+use napi::bindgen_prelude::{JsObjectValue, JsValue};
+use napi::{sys, Env, Error, Status};
+use rusqlite::ffi;
 use rusqlite::Error as SqliteError;
+use std::cell::{Cell, RefCell};
+use std::os::raw::c_int;
+use std::sync::{Mutex, MutexGuard};
+
+thread_local! {
+    /// The JS environment of this thread, recorded when a Database is opened on it
+    static JS_ENV: Cell<sys::napi_env> = const { Cell::new(std::ptr::null_mut()) };
+    /// Addresses of the connection mutexes held by a running `batch()` callback on this thread
+    static BATCH_LOCKS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Remember the JS environment of the current thread, so SQLite errors raised on it
+/// can be thrown as JS errors carrying a `code`
+pub fn register_env(env: &Env) {
+    JS_ENV.with(|cell| cell.set(env.raw()));
+}
+
+/// The stable `code` string for a SQLite result code, e.g. "SQLITE_CONSTRAINT_UNIQUE"
+/// Extended codes without a name of their own fall back to their primary code's name
+pub fn sqlite_code_name(extended_code: c_int) -> &'static str {
+    match extended_code {
+        ffi::SQLITE_CONSTRAINT_CHECK => "SQLITE_CONSTRAINT_CHECK",
+        ffi::SQLITE_CONSTRAINT_COMMITHOOK => "SQLITE_CONSTRAINT_COMMITHOOK",
+        ffi::SQLITE_CONSTRAINT_DATATYPE => "SQLITE_CONSTRAINT_DATATYPE",
+        ffi::SQLITE_CONSTRAINT_FOREIGNKEY => "SQLITE_CONSTRAINT_FOREIGNKEY",
+        ffi::SQLITE_CONSTRAINT_FUNCTION => "SQLITE_CONSTRAINT_FUNCTION",
+        ffi::SQLITE_CONSTRAINT_NOTNULL => "SQLITE_CONSTRAINT_NOTNULL",
+        ffi::SQLITE_CONSTRAINT_PINNED => "SQLITE_CONSTRAINT_PINNED",
+        ffi::SQLITE_CONSTRAINT_PRIMARYKEY => "SQLITE_CONSTRAINT_PRIMARYKEY",
+        ffi::SQLITE_CONSTRAINT_ROWID => "SQLITE_CONSTRAINT_ROWID",
+        ffi::SQLITE_CONSTRAINT_TRIGGER => "SQLITE_CONSTRAINT_TRIGGER",
+        ffi::SQLITE_CONSTRAINT_UNIQUE => "SQLITE_CONSTRAINT_UNIQUE",
+        ffi::SQLITE_CONSTRAINT_VTAB => "SQLITE_CONSTRAINT_VTAB",
+        ffi::SQLITE_BUSY_RECOVERY => "SQLITE_BUSY_RECOVERY",
+        ffi::SQLITE_BUSY_SNAPSHOT => "SQLITE_BUSY_SNAPSHOT",
+        ffi::SQLITE_BUSY_TIMEOUT => "SQLITE_BUSY_TIMEOUT",
+        ffi::SQLITE_LOCKED_SHAREDCACHE => "SQLITE_LOCKED_SHAREDCACHE",
+        ffi::SQLITE_LOCKED_VTAB => "SQLITE_LOCKED_VTAB",
+        ffi::SQLITE_READONLY_CANTINIT => "SQLITE_READONLY_CANTINIT",
+        ffi::SQLITE_READONLY_CANTLOCK => "SQLITE_READONLY_CANTLOCK",
+        ffi::SQLITE_READONLY_DBMOVED => "SQLITE_READONLY_DBMOVED",
+        ffi::SQLITE_READONLY_DIRECTORY => "SQLITE_READONLY_DIRECTORY",
+        ffi::SQLITE_READONLY_RECOVERY => "SQLITE_READONLY_RECOVERY",
+        ffi::SQLITE_READONLY_ROLLBACK => "SQLITE_READONLY_ROLLBACK",
+        ffi::SQLITE_CANTOPEN_ISDIR => "SQLITE_CANTOPEN_ISDIR",
+        ffi::SQLITE_CANTOPEN_FULLPATH => "SQLITE_CANTOPEN_FULLPATH",
+        ffi::SQLITE_CANTOPEN_NOTEMPDIR => "SQLITE_CANTOPEN_NOTEMPDIR",
+        ffi::SQLITE_CORRUPT_INDEX => "SQLITE_CORRUPT_INDEX",
+        ffi::SQLITE_CORRUPT_SEQUENCE => "SQLITE_CORRUPT_SEQUENCE",
+        ffi::SQLITE_CORRUPT_VTAB => "SQLITE_CORRUPT_VTAB",
+        ffi::SQLITE_ABORT_ROLLBACK => "SQLITE_ABORT_ROLLBACK",
+        ffi::SQLITE_ERROR_MISSING_COLLSEQ => "SQLITE_ERROR_MISSING_COLLSEQ",
+        _ => match extended_code & 0xff {
+            ffi::SQLITE_INTERNAL => "SQLITE_INTERNAL",
+            ffi::SQLITE_PERM => "SQLITE_PERM",
+            ffi::SQLITE_ABORT => "SQLITE_ABORT",
+            ffi::SQLITE_BUSY => "SQLITE_BUSY",
+            ffi::SQLITE_LOCKED => "SQLITE_LOCKED",
+            ffi::SQLITE_NOMEM => "SQLITE_NOMEM",
+            ffi::SQLITE_READONLY => "SQLITE_READONLY",
+            ffi::SQLITE_INTERRUPT => "SQLITE_INTERRUPT",
+            ffi::SQLITE_IOERR => "SQLITE_IOERR",
+            ffi::SQLITE_CORRUPT => "SQLITE_CORRUPT",
+            ffi::SQLITE_NOTFOUND => "SQLITE_NOTFOUND",
+            ffi::SQLITE_FULL => "SQLITE_FULL",
+            ffi::SQLITE_CANTOPEN => "SQLITE_CANTOPEN",
+            ffi::SQLITE_PROTOCOL => "SQLITE_PROTOCOL",
+            ffi::SQLITE_SCHEMA => "SQLITE_SCHEMA",
+            ffi::SQLITE_TOOBIG => "SQLITE_TOOBIG",
+            ffi::SQLITE_CONSTRAINT => "SQLITE_CONSTRAINT",
+            ffi::SQLITE_MISMATCH => "SQLITE_MISMATCH",
+            ffi::SQLITE_MISUSE => "SQLITE_MISUSE",
+            ffi::SQLITE_NOLFS => "SQLITE_NOLFS",
+            ffi::SQLITE_AUTH => "SQLITE_AUTH",
+            ffi::SQLITE_RANGE => "SQLITE_RANGE",
+            ffi::SQLITE_NOTADB => "SQLITE_NOTADB",
+            _ => "SQLITE_ERROR",
+        },
+    }
+}
+
+/// The `code` string for any rusqlite error
+fn error_code(err: &SqliteError) -> &'static str {
+    match err {
+        SqliteError::SqliteFailure(ffi_err, _) => sqlite_code_name(ffi_err.extended_code),
+        SqliteError::InvalidParameterName(_) | SqliteError::InvalidParameterCount(..) => {
+            "SQLITE_RANGE"
+        }
+        SqliteError::ToSqlConversionFailure(_)
+        | SqliteError::FromSqlConversionFailure(..)
+        | SqliteError::InvalidColumnType(..) => "SQLITE_MISMATCH",
+        _ => "SQLITE_ERROR",
+    }
+}
+
+/// Create an error that is thrown to JS with `code` set
+/// Falls back to a plain error when no JS environment is registered on this thread
+fn error_with_code(code: &str, message: String) -> Error {
+    let raw_env = JS_ENV.with(Cell::get);
+    if raw_env.is_null() {
+        return Error::new(Status::GenericFailure, message);
+    }
+    let env = Env::from_raw(raw_env);
+    let created = env
+        .create_error(Error::new(Status::GenericFailure, message.clone()))
+        .and_then(|mut js_error| {
+            js_error.set_named_property("code", code)?;
+            Ok(Error::from(js_error.to_unknown()))
+        });
+    created.unwrap_or_else(|_| Error::new(Status::GenericFailure, message))
+}
 
 pub fn to_napi_error(err: SqliteError) -> Error {
     to_napi_error_with_context(err, None)
 }
 
 pub fn to_napi_error_with_context(err: SqliteError, context: Option<&str>) -> Error {
+    let code = error_code(&err);
     let base_msg = match &err {
         SqliteError::SqliteFailure(ffi_err, desc) => {
             let code = ffi_err.extended_code;
@@ -14,10 +128,18 @@ pub fn to_napi_error_with_context(err: SqliteError, context: Option<&str>) -> Er
                 None => format!("SQLite Error [Extended Code {}]: {}", code, ffi_err),
             }
         }
-        SqliteError::ToSqlConversionFailure(e) => format!("SQLite Parameter Conversion Error: {}", e),
-        SqliteError::FromSqlConversionFailure(_, t, e) => format!("SQLite Result Conversion Error (type {:?}): {}", t, e),
-        SqliteError::InvalidParameterName(name) => format!("SQLite Invalid Parameter Name: {}", name),
-        SqliteError::InvalidColumnType(idx, name, t) => format!("SQLite Invalid Column Type at {} ({}): {:?}", idx, name, t),
+        SqliteError::ToSqlConversionFailure(e) => {
+            format!("SQLite Parameter Conversion Error: {}", e)
+        }
+        SqliteError::FromSqlConversionFailure(_, t, e) => {
+            format!("SQLite Result Conversion Error (type {:?}): {}", t, e)
+        }
+        SqliteError::InvalidParameterName(name) => {
+            format!("SQLite Invalid Parameter Name: {}", name)
+        }
+        SqliteError::InvalidColumnType(idx, name, t) => {
+            format!("SQLite Invalid Column Type at {} ({}): {:?}", idx, name, t)
+        }
         _ => format!("SQLite Error: {}", err),
     };
 
@@ -26,6 +148,123 @@ pub fn to_napi_error_with_context(err: SqliteError, context: Option<&str>) -> Er
         None => base_msg,
     };
 
-    Error::new(Status::GenericFailure, final_msg)
+    error_with_code(code, final_msg)
+}
+
+/// The first 100 characters of `sql` for an error message, with "..." when it was cut
+/// Cuts on a character boundary, so multibyte text can't make the error path panic
+pub fn sql_snippet(sql: &str) -> String {
+    match sql.char_indices().nth(100) {
+        Some((end, _)) => format!("{}...", &sql[..end]),
+        None => sql.to_string(),
+    }
 }
 
+/// Lock a mutex shared by the connection, recovering it if an earlier call panicked
+///
+/// A panic while the lock is held poisons the mutex, but the SQLite connection itself is
+/// still usable, so the guard is taken back and the poison cleared instead of failing every
+/// later call. `operation` names the call that found the mutex poisoned in the warning.
+pub fn lock_with_context<'a, T>(mutex: &'a Mutex<T>, operation: &str) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        eprintln!(
+            "sqlite-napi: recovered a lock poisoned by an earlier panic (in {})",
+            operation
+        );
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Marks a connection mutex as held by `batch()` on this thread until dropped
+pub struct BatchLockMark(usize);
+
+impl BatchLockMark {
+    pub fn new<T>(mutex: &Mutex<T>) -> Self {
+        let address = mutex as *const Mutex<T> as usize;
+        BATCH_LOCKS.with(|locks| locks.borrow_mut().push(address));
+        BatchLockMark(address)
+    }
+}
+
+impl Drop for BatchLockMark {
+    fn drop(&mut self) {
+        BATCH_LOCKS.with(|locks| {
+            let mut locks = locks.borrow_mut();
+            if let Some(i) = locks.iter().rposition(|&address| address == self.0) {
+                locks.remove(i);
+            }
+        });
+    }
+}
+
+/// Lock the connection mutex like `lock_with_context`, but fail instead of deadlocking when
+/// a `batch()` callback on this thread already holds it
+pub fn lock_connection<'a, T>(
+    mutex: &'a Mutex<T>,
+    operation: &str,
+) -> napi::Result<MutexGuard<'a, T>> {
+    let address = mutex as *const Mutex<T> as usize;
+    if BATCH_LOCKS.with(|locks| locks.borrow().contains(&address)) {
+        return Err(Error::from_reason(format!(
+            "{}: cannot use the Database inside batch(); use the batch handle",
+            operation
+        )));
+    }
+    Ok(lock_with_context(mutex, operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_lock_recovers_after_panic() {
+        let conn = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
+        conn.lock()
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER)")
+            .unwrap();
+
+        let poisoner = conn.clone();
+        let result = std::thread::spawn(move || {
+            let guard = poisoner.lock().unwrap();
+            guard.execute("INSERT INTO t VALUES (1)", []).unwrap();
+            panic!("panic while holding the connection lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(conn.is_poisoned());
+
+        let guard = lock_with_context(&conn, "test");
+        let count: i64 = guard
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        drop(guard);
+        assert!(!conn.is_poisoned());
+    }
+
+    #[test]
+    fn test_lock_connection_fails_inside_batch() {
+        let conn = Mutex::new(Connection::open_in_memory().unwrap());
+        {
+            let _guard = lock_with_context(&conn, "batch");
+            let _mark = BatchLockMark::new(&conn);
+            let reason = lock_connection(&conn, "run").err().unwrap().reason;
+            assert!(reason.contains("inside batch()"));
+        }
+        assert!(lock_connection(&conn, "run").is_ok());
+    }
+
+    #[test]
+    fn test_sql_snippet_cuts_on_char_boundary() {
+        assert_eq!(sql_snippet("SELECT 1"), "SELECT 1");
+        let sql = format!("SELECT '{}'", "é".repeat(100));
+        let snippet = sql_snippet(&sql);
+        assert_eq!(snippet.chars().count(), 103);
+        assert!(snippet.ends_with("é..."));
+    }
+}
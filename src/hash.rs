@@ -0,0 +1,27 @@
+//! Hash module - digests used by the built-in SQL hash functions, from the sha2, md-5 and crc32fast crates
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+/// A digest function returning the raw hash bytes
+pub type DigestFn = fn(&[u8]) -> Vec<u8>;
+
+/// Compute the SHA-256 digest of `data`
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// Compute the MD5 digest of `data`
+pub fn md5(data: &[u8]) -> Vec<u8> {
+    Md5::digest(data).to_vec()
+}
+
+/// Compute the CRC-32 (IEEE 802.3) checksum of `data`, big-endian
+pub fn crc32(data: &[u8]) -> Vec<u8> {
+    crc32fast::hash(data).to_be_bytes().to_vec()
+}
+
+/// Encode bytes as lowercase hex
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -2,18 +2,36 @@ use napi_derive::napi;
 
 pub mod db;
 mod error;
+mod hash;
 mod models;
 pub mod schema;
 
-pub use db::{Database, Iter, Statement, Transaction};
-pub use models::{Migration, QueryResult, TransactionResult};
+pub use db::{ConnectionBatch, Database, Iter, Statement, Transaction};
+pub use models::{
+    ImportResult, Migration, MigrationEntry, QueryResult, RunManyResult, SlowQuery,
+    TransactionResult,
+};
 pub use schema::{
-    check_sql_expression, get_autoincrement_info, get_sqlite_functions, is_sql_expression,
-    validate_column_definition, validate_create_table, AutoincrementInfo, ColumnValidation,
-    ExpressionCheck, SchemaValidation, SqliteType, TypeMapping,
+    build_create_table, check_sql_expression, column_affinity, get_autoincrement_info,
+    get_sqlite_functions, is_sql_expression, parse_migration_filename, split_sql_statements,
+    split_statements, validate_column_definition, validate_create_table, AutoincrementInfo,
+    ColumnDef, ColumnValidation, ExpressionCheck, SchemaValidation, SqliteType, TypeMapping,
 };
 
 #[napi]
 pub fn get_sqlite_version() -> String {
     rusqlite::version().to_string()
 }
+
+/// Total bytes of memory currently allocated by SQLite across all connections
+#[napi]
+pub fn get_global_memory_used() -> i64 {
+    unsafe { rusqlite::ffi::sqlite3_memory_used() }
+}
+
+/// Set the process-wide soft heap limit in bytes and return the previous limit
+/// A negative value only queries the current limit; 0 disables the limit
+#[napi]
+pub fn set_soft_heap_limit(bytes: i64) -> i64 {
+    unsafe { rusqlite::ffi::sqlite3_soft_heap_limit64(bytes) }
+}
@@ -1,18 +1,82 @@
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
-#[napi(object)]
+#[napi(object, use_nullable = true)]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryResult {
+    /// Rows changed directly by the statement; trigger and foreign key cascade changes aren't counted
     pub changes: u32,
-    pub last_insert_rowid: i64,
+    /// Rowid of the last inserted row; null unless the SQL contains an INSERT or REPLACE
+    pub last_insert_rowid: Option<i64>,
+    /// Every row changed while the call ran, including those changed by triggers and cascades
+    pub total_changes: u32,
 }
 
-#[napi(object)]
+#[napi(object, use_nullable = true)]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TransactionResult {
     pub changes: u32,
-    pub last_insert_rowid: i64,
+    /// Always null: `COMMIT`, `ROLLBACK` and `RELEASE` insert no rows
+    pub last_insert_rowid: Option<i64>,
+}
+
+/// Result of running one statement with several parameter sets
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RunManyResult {
+    /// Rows changed directly by all executions together
+    pub changes: u32,
+    /// Rowid inserted by each execution, in parameter set order; null when it inserted no row
+    #[napi(ts_type = "Array<number | null>")]
+    pub rowids: Vec<Option<i64>>,
+}
+
+/// Result of a bulk import
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportResult {
+    /// Number of rows inserted
+    pub inserted: u32,
+    /// Number of rows skipped because they didn't match the table
+    pub skipped: u32,
+}
+
+/// A statement reported to the slow query callback
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SlowQuery {
+    /// SQL text of the statement
+    pub sql: String,
+    /// How long the statement took, in microseconds
+    pub duration_micros: f64,
+}
+
+/// A finished statement reported to the profiler callback
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatementProfile {
+    /// SQL text of the statement
+    pub sql: String,
+    /// How long the statement took according to SQLite, in nanoseconds
+    pub nanos: f64,
+}
+
+/// SQLite's status counters for the last execution of a statement
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StatementStatus {
+    /// Rows stepped through by full table scans
+    pub fullscan_steps: u32,
+    /// Sort operations
+    pub sorts: u32,
+    /// Rows inserted into automatic indexes
+    pub autoindexes: u32,
+    /// Virtual machine operations executed
+    pub vm_steps: u32,
+    /// Times the statement was re-prepared after a schema change
+    pub reprepares: u32,
+    /// Times the statement was run
+    pub runs: u32,
 }
 
 /// Migration definition for schema versioning
@@ -25,4 +89,17 @@ pub struct Migration {
     pub sql: String,
     /// Optional description of what this migration does
     pub description: Option<String>,
+    /// Run inside the migration transaction (default: true); set false for statements such as
+    /// VACUUM that SQLite refuses inside a transaction
+    pub transactional: Option<bool>,
+}
+
+/// Migration file for `migrateFromEntries`, named like `0003_add_users.sql`
+#[napi(object)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MigrationEntry {
+    /// File name carrying the version and description; any directory part is ignored
+    pub filename: String,
+    /// SQL statements to execute for this migration
+    pub sql: String,
 }
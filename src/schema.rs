@@ -4,6 +4,7 @@
 use napi_derive::napi;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::HashSet;
 
 /// Regex for detecting SQL function calls like datetime('now'), strftime('%s', 'now')
 static SQL_FUNCTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z_]+\s*\(").unwrap());
@@ -23,6 +24,88 @@ static SQL_KEYWORDS: Lazy<Vec<&'static str>> = Lazy::new(|| {
     ]
 });
 
+/// SQLite keywords that can't be used as a table or column name without quoting
+///
+/// Other keywords like `KEY` or `ACTION` fall back to plain identifiers and aren't listed.
+static SQL_RESERVED_WORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "ADD",
+        "ALL",
+        "ALTER",
+        "AND",
+        "AS",
+        "AUTOINCREMENT",
+        "BETWEEN",
+        "CASE",
+        "CHECK",
+        "COLLATE",
+        "COMMIT",
+        "CONSTRAINT",
+        "CREATE",
+        "DEFAULT",
+        "DEFERRABLE",
+        "DELETE",
+        "DISTINCT",
+        "DROP",
+        "ELSE",
+        "ESCAPE",
+        "EXCEPT",
+        "EXISTS",
+        "FOREIGN",
+        "FROM",
+        "GROUP",
+        "HAVING",
+        "IN",
+        "INDEX",
+        "INSERT",
+        "INTERSECT",
+        "INTO",
+        "IS",
+        "ISNULL",
+        "JOIN",
+        "LIMIT",
+        "NOT",
+        "NOTHING",
+        "NOTNULL",
+        "NULL",
+        "ON",
+        "OR",
+        "ORDER",
+        "PRIMARY",
+        "REFERENCES",
+        "RETURNING",
+        "SELECT",
+        "SET",
+        "TABLE",
+        "THEN",
+        "TO",
+        "TRANSACTION",
+        "UNION",
+        "UNIQUE",
+        "UPDATE",
+        "USING",
+        "VALUES",
+        "WHEN",
+        "WHERE",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Check whether a name is a reserved SQLite keyword that must be quoted
+fn is_reserved_word(name: &str) -> bool {
+    SQL_RESERVED_WORDS.contains(name.to_uppercase().as_str())
+}
+
+fn reserved_word_warning(kind: &str, name: &str) -> String {
+    format!(
+        "{} name '{}' is a reserved SQLite keyword; quote it as {}",
+        kind,
+        name,
+        quote_identifier(name)
+    )
+}
+
 /// SQLite column types supported by the database
 #[derive(Debug, PartialEq)]
 #[napi]
@@ -52,14 +135,20 @@ impl SqliteType {
     }
 
     /// Get type from string name (case insensitive)
+    /// Size parameters are ignored, so `VARCHAR(255)` parses like `VARCHAR`
     #[allow(clippy::should_implement_trait)]
     pub fn parse_type(s: &str) -> Option<SqliteType> {
-        match s.to_uppercase().as_str() {
+        let name = match s.find('(') {
+            Some(open) if s.trim_end().ends_with(')') => &s[..open],
+            Some(_) => return None,
+            None => s,
+        };
+        match name.trim().to_uppercase().as_str() {
             "NULL" => Some(SqliteType::Null),
             "INTEGER" | "INT" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "BIGINT"
             | "UNSIGNED BIG INT" => Some(SqliteType::Integer),
             "REAL" | "DOUBLE" | "FLOAT" | "NUMERIC" | "DECIMAL" => Some(SqliteType::Real),
-            "TEXT" | "CHARACTER" | "VARCHAR" | "VARYING CHARACTER" | "NCHAR"
+            "TEXT" | "CHAR" | "CHARACTER" | "VARCHAR" | "VARYING CHARACTER" | "NCHAR"
             | "NATIVE CHARACTER" | "NVARCHAR" | "CLOB" => Some(SqliteType::Text),
             "BLOB" | "NONE" => Some(SqliteType::Blob),
             _ => None,
@@ -275,6 +364,8 @@ pub struct ColumnValidation {
     pub valid: bool,
     /// List of warnings or errors
     pub issues: Vec<String>,
+    /// Problems that don't make the definition invalid, like a name that needs quoting
+    pub warnings: Vec<String>,
 }
 
 /// Validate a column definition
@@ -288,12 +379,17 @@ pub fn validate_column_definition(
     default_value: Option<String>,
 ) -> ColumnValidation {
     let mut issues = Vec::new();
+    let mut warnings = Vec::new();
 
     // Validate column name
     if column_name.is_empty() {
         issues.push("Column name cannot be empty".to_string());
     }
 
+    if is_reserved_word(&column_name) {
+        warnings.push(reserved_word_warning("Column", &column_name));
+    }
+
     if column_name.contains(' ') {
         issues.push("Column name should not contain spaces".to_string());
     }
@@ -327,6 +423,7 @@ pub fn validate_column_definition(
     ColumnValidation {
         valid: issues.is_empty(),
         issues,
+        warnings,
     }
 }
 
@@ -363,6 +460,454 @@ pub fn get_autoincrement_info(column_type: String, is_primary_key: bool) -> Auto
     }
 }
 
+/// Compute the affinity SQLite gives a column declared with `declared_type`
+/// Follows SQLite's rules in order: "INT" gives INTEGER; "CHAR", "CLOB" or "TEXT" give TEXT;
+/// "BLOB" or no type give BLOB; "REAL", "FLOA" or "DOUB" give REAL; anything else is NUMERIC
+#[napi]
+pub fn column_affinity(declared_type: String) -> String {
+    let upper = declared_type.to_uppercase();
+    let affinity = if upper.contains("INT") {
+        "INTEGER"
+    } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+        "TEXT"
+    } else if upper.contains("BLOB") || upper.trim().is_empty() {
+        "BLOB"
+    } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+        "REAL"
+    } else {
+        "NUMERIC"
+    };
+    affinity.to_string()
+}
+
+/// Check whether a SQL fragment contains anything besides whitespace, comments and semicolons
+fn has_sql_tokens(fragment: &str) -> bool {
+    let mut chars = fragment.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ';' => {}
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => return true,
+        }
+    }
+    false
+}
+
+/// Split a SQL script into its individual statements
+///
+/// Each semicolon is a candidate terminator, confirmed with `sqlite3_complete()` so that
+/// semicolons inside string literals, quoted identifiers, comments and `CREATE TRIGGER ...
+/// BEGIN ... END` bodies don't split a statement. Statements keep their trailing semicolon and
+/// any leading comments; fragments holding only whitespace and comments are dropped.
+pub fn split_sql_statements(sql: &str) -> Vec<String> {
+    let is_complete = |candidate: &str| {
+        std::ffi::CString::new(candidate)
+            .map(|c| unsafe { rusqlite::ffi::sqlite3_complete(c.as_ptr()) } != 0)
+            .unwrap_or(false)
+    };
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for (i, _) in sql.match_indices(';') {
+        let candidate = &sql[start..=i];
+        if is_complete(candidate) {
+            if has_sql_tokens(candidate) {
+                statements.push(candidate.trim().to_string());
+            }
+            start = i + 1;
+        }
+    }
+    let rest = &sql[start..];
+    if has_sql_tokens(rest) {
+        statements.push(rest.trim().to_string());
+    }
+    statements
+}
+
+/// Split a SQL script into its individual statements, respecting string literals, comments and
+/// trigger bodies
+#[napi]
+pub fn split_statements(sql: String) -> Vec<String> {
+    split_sql_statements(&sql)
+}
+
+/// Parse the version and description out of a migration filename like `0003_add_users.sql`
+///
+/// Any directory part is ignored. The name must be digits, optionally followed by `_` or `-`
+/// and a description, then `.sql`; underscores in the description become spaces. Version 0
+/// is rejected because it is the version of a database with no migrations applied.
+pub fn parse_migration_filename(filename: &str) -> napi::Result<(u32, Option<String>)> {
+    let invalid = |reason: &str| {
+        napi::Error::from_reason(format!(
+            "Invalid migration filename '{}': {}",
+            filename, reason
+        ))
+    };
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
+    let stem = name
+        .len()
+        .checked_sub(4)
+        .filter(|&end| name.is_char_boundary(end) && name[end..].eq_ignore_ascii_case(".sql"))
+        .map(|end| &name[..end])
+        .ok_or_else(|| invalid("expected a .sql extension"))?;
+    let digits = stem.len() - stem.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return Err(invalid("expected a leading version like 0001_name.sql"));
+    }
+    let version: u32 = stem[..digits]
+        .parse()
+        .map_err(|_| invalid("the version number is too large"))?;
+    if version == 0 {
+        return Err(invalid("versions start at 1"));
+    }
+    let rest = &stem[digits..];
+    let description = if rest.is_empty() {
+        None
+    } else {
+        let text = rest
+            .strip_prefix(['_', '-'])
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| invalid("expected '_' or '-' and a description after the version"))?;
+        Some(text.replace('_', " "))
+    };
+    Ok((version, description))
+}
+
+/// Words that can appear inside a CHECK expression without naming a column
+static CHECK_EXPRESSION_KEYWORDS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    vec![
+        "AND",
+        "OR",
+        "NOT",
+        "IN",
+        "IS",
+        "NULL",
+        "ISNULL",
+        "NOTNULL",
+        "LIKE",
+        "GLOB",
+        "REGEXP",
+        "MATCH",
+        "ESCAPE",
+        "BETWEEN",
+        "CASE",
+        "WHEN",
+        "THEN",
+        "ELSE",
+        "END",
+        "CAST",
+        "AS",
+        "COLLATE",
+        "EXISTS",
+        "DISTINCT",
+        "TRUE",
+        "FALSE",
+        "CURRENT_DATE",
+        "CURRENT_TIME",
+        "CURRENT_TIMESTAMP",
+        "ROWID",
+        "_ROWID_",
+        "OID",
+    ]
+});
+
+/// Keywords that start a table constraint rather than a column definition
+const TABLE_CONSTRAINT_KEYWORDS: [&str; 5] =
+    ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+
+/// A token of a SQL statement, as far as schema validation and statement classification need
+/// to tell them apart
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SqlToken {
+    /// Bare word: a keyword or an unquoted identifier
+    Word(String),
+    /// Identifier quoted with `"..."`, `` `...` `` or `[...]`
+    Quoted(String),
+    /// String, blob or numeric literal
+    Literal,
+    /// Any other single character, such as `(`, `,` or `=`
+    Punct(char),
+}
+
+impl SqlToken {
+    fn is_word(&self, keyword: &str) -> bool {
+        matches!(self, SqlToken::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Identifier named by the token, quoted or not
+    fn identifier(&self) -> Option<&str> {
+        match self {
+            SqlToken::Word(w) | SqlToken::Quoted(w) => Some(w),
+            _ => None,
+        }
+    }
+}
+
+/// Split SQL into tokens, skipping whitespace and comments
+pub(crate) fn tokenize_sql(sql: &str) -> Vec<SqlToken> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    if c == close {
+                        // A doubled quote is an escaped quote, except in [...] identifiers
+                        if close != ']' && chars.peek() == Some(&close) {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    text.push(c);
+                }
+                tokens.push(if c == '\'' {
+                    SqlToken::Literal
+                } else {
+                    SqlToken::Quoted(text)
+                });
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '$' {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if c.is_ascii_digit() {
+                    // Numbers, including the fraction of a decimal like 1.5
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        while chars.peek().is_some_and(|c| c.is_alphanumeric()) {
+                            chars.next();
+                        }
+                    }
+                    tokens.push(SqlToken::Literal);
+                } else if word.eq_ignore_ascii_case("x") && chars.peek() == Some(&'\'') {
+                    // Blob literal x'...', whose quoted part is read as the next token
+                } else {
+                    tokens.push(SqlToken::Word(word));
+                }
+            }
+            c => tokens.push(SqlToken::Punct(c)),
+        }
+    }
+    tokens
+}
+
+/// Definitions inside the parentheses of a CREATE TABLE statement, split on top-level commas
+///
+/// When the column list is never closed, everything up to the end of the statement is taken.
+fn column_list(tokens: &[SqlToken]) -> Vec<&[SqlToken]> {
+    let Some(open) = tokens.iter().position(|t| *t == SqlToken::Punct('(')) else {
+        return Vec::new();
+    };
+    let mut definitions = Vec::new();
+    let mut depth = 0usize;
+    let mut start = open + 1;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            SqlToken::Punct('(') => depth += 1,
+            SqlToken::Punct(')') if depth == 0 => {
+                definitions.push(&tokens[start..i]);
+                return definitions;
+            }
+            SqlToken::Punct(')') => depth -= 1,
+            SqlToken::Punct(',') if depth == 0 => {
+                definitions.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    definitions.push(&tokens[start..]);
+    definitions
+}
+
+/// Token naming the table in a CREATE TABLE statement, after any `IF NOT EXISTS` and schema
+fn table_name_token(tokens: &[SqlToken]) -> Option<&SqlToken> {
+    let mut i = tokens.iter().position(|t| t.is_word("TABLE"))? + 1;
+    if tokens.get(i).is_some_and(|t| t.is_word("IF")) {
+        i += 3;
+    }
+    if tokens.get(i + 1) == Some(&SqlToken::Punct('.')) {
+        i += 2;
+    }
+    tokens.get(i)
+}
+
+/// Name of the column a definition declares, or `None` for table constraints
+fn defined_column(definition: &[SqlToken]) -> Option<&str> {
+    let first = definition.first()?;
+    if TABLE_CONSTRAINT_KEYWORDS.iter().any(|k| first.is_word(k)) {
+        return None;
+    }
+    first.identifier()
+}
+
+/// Columns referenced by the tokens of a CHECK expression
+///
+/// Function names, keywords, collation and CAST type names, table qualifiers and named parameters
+/// are skipped; this is a heuristic, not a parser.
+fn check_referenced_columns(expression: &[SqlToken]) -> Vec<&str> {
+    let mut columns = Vec::new();
+    let mut in_cast_type = false;
+    for (i, token) in expression.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|p| &expression[p]);
+        let next = expression.get(i + 1);
+        if previous.is_some_and(|p| p.is_word("AS")) {
+            in_cast_type = true;
+        }
+        if !matches!(token, SqlToken::Word(_)) {
+            in_cast_type = false;
+        }
+        let is_column = match token {
+            SqlToken::Word(w) => {
+                !in_cast_type
+                    && !previous.is_some_and(|p| {
+                        p.is_word("COLLATE") || matches!(p, SqlToken::Punct(':' | '@' | '$'))
+                    })
+                    && !matches!(next, Some(SqlToken::Punct('(')))
+                    && !CHECK_EXPRESSION_KEYWORDS
+                        .iter()
+                        .any(|k| w.eq_ignore_ascii_case(k))
+            }
+            SqlToken::Quoted(_) => true,
+            _ => false,
+        };
+        // In `table.column` only the part after the dot names a column
+        if is_column && next != Some(&SqlToken::Punct('.')) {
+            columns.extend(token.identifier());
+        }
+    }
+    columns
+}
+
+/// Warnings about unquoted table and column names that are reserved SQLite keywords
+fn reserved_name_warnings(sql: &str) -> Vec<String> {
+    let tokens = tokenize_sql(sql);
+    let mut warnings = Vec::new();
+    if let Some(SqlToken::Word(name)) = table_name_token(&tokens) {
+        if is_reserved_word(name) {
+            warnings.push(reserved_word_warning("Table", name));
+        }
+    }
+    for definition in column_list(&tokens) {
+        // Quoted names are fine, and table constraints start with reserved words of their own
+        if let Some(SqlToken::Word(name)) = definition.first() {
+            if is_reserved_word(name) && defined_column(definition).is_some() {
+                warnings.push(reserved_word_warning("Column", name));
+            }
+        }
+    }
+    warnings
+}
+
+/// Warnings about the CHECK constraints of a CREATE TABLE statement
+///
+/// Flags unbalanced parentheses in a statement with CHECK clauses, and otherwise CHECK
+/// expressions naming a column the table doesn't define.
+fn check_constraint_warnings(sql: &str) -> Vec<String> {
+    let tokens = tokenize_sql(sql);
+    let is_check =
+        |i: usize| tokens[i].is_word("CHECK") && tokens.get(i + 1) == Some(&SqlToken::Punct('('));
+    if !(0..tokens.len()).any(is_check) {
+        return Vec::new();
+    }
+    // A stray or missing parenthesis shifts every definition after it, so the column
+    // references can't be trusted either
+    let mut depth = 0i64;
+    for token in &tokens {
+        match token {
+            SqlToken::Punct('(') => depth += 1,
+            SqlToken::Punct(')') => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            break;
+        }
+    }
+    if depth != 0 {
+        return vec!["CHECK constraint has unbalanced parentheses".to_string()];
+    }
+
+    let definitions = column_list(&tokens);
+    let columns: Vec<&str> = definitions
+        .iter()
+        .filter_map(|d| defined_column(d))
+        .collect();
+    let mut warnings = Vec::new();
+    for definition in &definitions {
+        for (i, token) in definition.iter().enumerate() {
+            if !token.is_word("CHECK") || definition.get(i + 1) != Some(&SqlToken::Punct('(')) {
+                continue;
+            }
+            let mut depth = 0usize;
+            let mut end = definition.len();
+            for (j, token) in definition.iter().enumerate().skip(i + 1) {
+                match token {
+                    SqlToken::Punct('(') => depth += 1,
+                    SqlToken::Punct(')') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = j;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            for name in check_referenced_columns(&definition[i + 2..end]) {
+                let warning = format!("CHECK constraint references unknown column '{}'", name);
+                if !columns.iter().any(|c| c.eq_ignore_ascii_case(name))
+                    && !warnings.contains(&warning)
+                {
+                    warnings.push(warning);
+                }
+            }
+        }
+    }
+    warnings
+}
+
 /// Schema validation result
 #[napi]
 pub struct SchemaValidation {
@@ -413,6 +958,12 @@ pub fn validate_create_table(sql: String) -> SchemaValidation {
         warnings.push("FOREIGN KEY defined without ON DELETE clause".to_string());
     }
 
+    // Reserved words only fail once the DDL runs, with a confusing syntax error
+    warnings.extend(reserved_name_warnings(&sql));
+
+    // CHECK constraints can only be checked loosely, so problems there are warnings
+    warnings.extend(check_constraint_warnings(&sql));
+
     // Check for likely issues with AUTOINCREMENT
     if sql_lower.contains("autoincrement") && !sql_lower.contains("integer") {
         issues.push("AUTOINCREMENT used but column type is not INTEGER".to_string());
@@ -425,6 +976,161 @@ pub fn validate_create_table(sql: String) -> SchemaValidation {
     }
 }
 
+/// Column definition used to build a CREATE TABLE statement
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ColumnDef {
+    /// Column name (quoted automatically in the generated SQL)
+    pub name: String,
+    /// SQLite column type (e.g. "INTEGER", "TEXT")
+    #[napi(js_name = "type")]
+    pub column_type: String,
+    /// Whether the column is part of the PRIMARY KEY
+    pub primary_key: Option<bool>,
+    /// Whether the column is NOT NULL
+    pub not_null: Option<bool>,
+    /// Whether the column has a UNIQUE constraint
+    pub unique: Option<bool>,
+    /// Default value - SQL expressions are emitted as-is, other values are quoted
+    pub default_value: Option<String>,
+}
+
+/// Quote an identifier (table or column name) for safe use in SQL
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Check that an identifier can be quoted and used in generated SQL
+pub fn validate_identifier(name: &str) -> napi::Result<()> {
+    if name.trim().is_empty() {
+        return Err(napi::Error::from_reason("Identifier cannot be empty"));
+    }
+    if name.contains('\0') {
+        return Err(napi::Error::from_reason(format!(
+            "Identifier contains a NUL character: {:?}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Render a DEFAULT clause value, quoting plain values and keeping SQL expressions
+fn format_default_value(value: &str) -> String {
+    let trimmed = value.trim();
+    match check_sql_expression(trimmed.to_string())
+        .expression_type
+        .as_deref()
+    {
+        // Function calls must be parenthesized in a DEFAULT clause
+        Some("function_call") => format!("({})", trimmed),
+        Some(_) => trimmed.to_string(),
+        None => {
+            let is_quoted =
+                trimmed.len() >= 2 && trimmed.starts_with('\'') && trimmed.ends_with('\'');
+            if is_quoted || trimmed.parse::<f64>().is_ok() {
+                trimmed.to_string()
+            } else {
+                format!("'{}'", trimmed.replace('\'', "''"))
+            }
+        }
+    }
+}
+
+/// Build a validated CREATE TABLE statement from structured column definitions
+///
+/// Identifiers are always quoted, defaults are quoted unless they are SQL expressions,
+/// and every column is checked with `validate_column_definition`. Multiple primary key
+/// columns produce a table-level composite PRIMARY KEY constraint.
+#[napi]
+pub fn build_create_table(
+    table_name: String,
+    columns: Vec<ColumnDef>,
+    if_not_exists: Option<bool>,
+) -> napi::Result<String> {
+    validate_identifier(&table_name)?;
+    if columns.is_empty() {
+        return Err(napi::Error::from_reason(format!(
+            "Table '{}' must have at least one column",
+            table_name
+        )));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for column in &columns {
+        validate_identifier(&column.name)?;
+        if !seen.insert(column.name.to_lowercase()) {
+            return Err(napi::Error::from_reason(format!(
+                "Duplicate column name: {}",
+                column.name
+            )));
+        }
+        let validation = validate_column_definition(
+            column.name.clone(),
+            column.column_type.clone(),
+            column.primary_key.unwrap_or(false),
+            column.not_null.unwrap_or(false),
+            column.default_value.is_some(),
+            column.default_value.clone(),
+        );
+        if !validation.valid {
+            return Err(napi::Error::from_reason(format!(
+                "Invalid column '{}': {}",
+                column.name,
+                validation.issues.join("; ")
+            )));
+        }
+    }
+
+    let pk_columns: Vec<&ColumnDef> = columns
+        .iter()
+        .filter(|c| c.primary_key.unwrap_or(false))
+        .collect();
+    let inline_pk = pk_columns.len() == 1;
+
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let mut def = format!(
+                "{} {}",
+                quote_identifier(&column.name),
+                column.column_type.to_uppercase()
+            );
+            if inline_pk && column.primary_key.unwrap_or(false) {
+                def.push_str(" PRIMARY KEY");
+            }
+            if column.not_null.unwrap_or(false) {
+                def.push_str(" NOT NULL");
+            }
+            if column.unique.unwrap_or(false) {
+                def.push_str(" UNIQUE");
+            }
+            if let Some(ref default) = column.default_value {
+                def.push_str(&format!(" DEFAULT {}", format_default_value(default)));
+            }
+            def
+        })
+        .collect();
+
+    if pk_columns.len() > 1 {
+        let names: Vec<String> = pk_columns
+            .iter()
+            .map(|c| quote_identifier(&c.name))
+            .collect();
+        definitions.push(format!("PRIMARY KEY ({})", names.join(", ")));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {}{} ({})",
+        if if_not_exists.unwrap_or(false) {
+            "IF NOT EXISTS "
+        } else {
+            ""
+        },
+        quote_identifier(&table_name),
+        definitions.join(", ")
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1091,4 +1797,94 @@ mod tests {
         let result = validate_create_table(sql.to_string());
         assert!(result.valid);
     }
+
+    // ============== build_create_table tests ==============
+    fn column(name: &str, column_type: &str) -> ColumnDef {
+        ColumnDef {
+            name: name.to_string(),
+            column_type: column_type.to_string(),
+            primary_key: None,
+            not_null: None,
+            unique: None,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_build_create_table_basic() {
+        let mut id = column("id", "integer");
+        id.primary_key = Some(true);
+        let mut name = column("name", "TEXT");
+        name.not_null = Some(true);
+        name.unique = Some(true);
+
+        let sql = build_create_table("users".to_string(), vec![id, name], None).unwrap();
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"users\" (\"id\" INTEGER PRIMARY KEY, \"name\" TEXT NOT NULL UNIQUE)"
+        );
+        assert!(validate_create_table(sql).valid);
+    }
+
+    #[test]
+    fn test_build_create_table_defaults() {
+        let mut status = column("status", "TEXT");
+        status.default_value = Some("it's new".to_string());
+        let mut count = column("count", "INTEGER");
+        count.default_value = Some("0".to_string());
+        let mut created = column("created_at", "TEXT");
+        created.default_value = Some("datetime('now')".to_string());
+        let mut updated = column("updated_at", "TEXT");
+        updated.default_value = Some("CURRENT_TIMESTAMP".to_string());
+
+        let sql = build_create_table(
+            "events".to_string(),
+            vec![status, count, created, updated],
+            Some(true),
+        )
+        .unwrap();
+        assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS \"events\""));
+        assert!(sql.contains("\"status\" TEXT DEFAULT 'it''s new'"));
+        assert!(sql.contains("\"count\" INTEGER DEFAULT 0"));
+        assert!(sql.contains("\"created_at\" TEXT DEFAULT (datetime('now'))"));
+        assert!(sql.contains("\"updated_at\" TEXT DEFAULT CURRENT_TIMESTAMP"));
+    }
+
+    #[test]
+    fn test_build_create_table_composite_primary_key() {
+        let mut a = column("a", "INTEGER");
+        a.primary_key = Some(true);
+        let mut b = column("b", "INTEGER");
+        b.primary_key = Some(true);
+
+        let sql = build_create_table("pairs".to_string(), vec![a, b], None).unwrap();
+        assert!(sql.contains("PRIMARY KEY (\"a\", \"b\")"));
+        assert!(!sql.contains("INTEGER PRIMARY KEY"));
+    }
+
+    #[test]
+    fn test_build_create_table_quotes_identifiers() {
+        let sql = build_create_table(
+            "my \"table\"".to_string(),
+            vec![column("order", "TEXT")],
+            None,
+        )
+        .unwrap();
+        assert_eq!(sql, "CREATE TABLE \"my \"\"table\"\"\" (\"order\" TEXT)");
+    }
+
+    #[test]
+    fn test_build_create_table_rejects_invalid_columns() {
+        assert!(build_create_table("t".to_string(), vec![], None).is_err());
+        assert!(build_create_table("".to_string(), vec![column("id", "INTEGER")], None).is_err());
+        assert!(
+            build_create_table("t".to_string(), vec![column("id", "NOT_A_TYPE")], None).is_err()
+        );
+        assert!(build_create_table(
+            "t".to_string(),
+            vec![column("id", "INTEGER"), column("ID", "TEXT")],
+            None
+        )
+        .is_err());
+    }
 }
@@ -188,6 +188,19 @@ describe("SQLite NAPI - BigInt Support", () => {
       expect(row).toBeDefined();
     });
 
+    test("rejects plain numbers beyond MAX_SAFE_INTEGER", () => {
+      expect(() =>
+        db.run("INSERT INTO big_numbers (big_val, name) VALUES (?, ?)", [
+          9007199254740993,
+          "lossy",
+        ])
+      ).toThrow(/BigInt/);
+      expect(() => db.query("SELECT ? AS v").get([-(2 ** 60)])).toThrow(/BigInt/);
+
+      const row = db.query("SELECT ? AS v").get([Number.MAX_SAFE_INTEGER]) as any;
+      expect(row.v).toBe(Number.MAX_SAFE_INTEGER);
+    });
+
     test("BigInt arithmetic in SQL", () => {
       db.run("INSERT INTO big_numbers (big_val, name) VALUES (?, ?)", [
         BigInt("1000000000000"),
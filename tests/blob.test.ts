@@ -61,6 +61,27 @@ describe("SQLite NAPI - BLOB Support", () => {
 
       expect((row as any).size).toBe(1024 * 1024);
     });
+
+    test("binds only the bytes of a Buffer slice", () => {
+      const backing = Buffer.from([0, 1, 2, 3, 4, 5, 6, 7]);
+
+      db.run("INSERT INTO files (name, data) VALUES (?, ?)", ["slice.bin", backing.subarray(2, 5)]);
+
+      const row = db.query("SELECT hex(data) AS hex FROM files WHERE name = ?").get(["slice.bin"]);
+      expect((row as any).hex).toBe("020304");
+    });
+
+    test("keeps the stored bytes when the Buffer changes after binding", () => {
+      const data = Buffer.alloc(4 * 1024 * 1024, 0xab);
+
+      db.run("INSERT INTO files (name, data) VALUES (?, ?)", ["reused.bin", data]);
+      data.fill(0);
+
+      const row = db
+        .query("SELECT length(data) AS size, hex(substr(data, 1, 2)) AS head FROM files WHERE name = ?")
+        .get(["reused.bin"]);
+      expect(row).toEqual({ size: 4 * 1024 * 1024, head: "ABAB" });
+    });
   });
 
   describe("BLOB retrieval", () => {
@@ -293,4 +314,61 @@ describe("SQLite NAPI - BLOB Support", () => {
       expect(row).toBeNull();
     });
   });
+
+  describe("blobEncoding option", () => {
+    const bytes = new Uint8Array([0xde, 0xad, 0xbe, 0xef]);
+
+    test("defaults to base64", () => {
+      db.run("INSERT INTO files (name, data) VALUES (?, ?)", ["a.bin", bytes]);
+
+      const row = db.query("SELECT data FROM files").get();
+      expect(row.data).toBe("3q2+7w==");
+    });
+
+    test("returns hex strings", () => {
+      const hexDb = new Database(":memory:", { blobEncoding: "hex" });
+
+      const row = hexDb.query("SELECT ? AS data").get([bytes]);
+      expect(row.data).toBe("deadbeef");
+    });
+
+    test("returns Buffers from every statement method", () => {
+      const bufDb = new Database(":memory:", { blobEncoding: "buffer" });
+      bufDb.exec("CREATE TABLE files (name TEXT, data BLOB)");
+      bufDb.run("INSERT INTO files VALUES (?, ?)", ["a", bytes]);
+      bufDb.run("INSERT INTO files VALUES (?, ?)", ["b", null]);
+      const stmt = bufDb.query("SELECT name, data FROM files ORDER BY name");
+
+      const rows = stmt.all();
+      expect(Buffer.isBuffer(rows[0].data)).toBe(true);
+      expect([...rows[0].data]).toEqual([...bytes]);
+      expect(rows[1].data).toBeNull();
+
+      expect(Buffer.isBuffer(stmt.get().data)).toBe(true);
+      expect(Buffer.isBuffer(stmt.values()[0][1])).toBe(true);
+      expect(Buffer.isBuffer(stmt.allKeyed("name").a.data)).toBe(true);
+
+      const iter = stmt.iter();
+      expect(Buffer.isBuffer(iter.next().data)).toBe(true);
+      iter.reset();
+      expect(Buffer.isBuffer(iter.nextValues()[1])).toBe(true);
+      expect(Buffer.isBuffer(iter.all()[0].data)).toBe(true);
+    });
+
+    test("applies to execScript rows", () => {
+      const hexDb = new Database(":memory:", { blobEncoding: "hex" });
+      expect(hexDb.execScript("SELECT x'deadbeef' AS data")[0].rows).toEqual([{ data: "deadbeef" }]);
+
+      const bufDb = new Database(":memory:", { blobEncoding: "buffer" });
+      const [result] = bufDb.execScript("SELECT x'deadbeef' AS data, 1 AS n");
+      expect(Buffer.isBuffer(result.rows[0].data)).toBe(true);
+      expect([...result.rows[0].data]).toEqual([...bytes]);
+      expect(result.rows[0].n).toBe(1);
+      expect(result.changes).toBe(0);
+    });
+
+    test("rejects unknown encodings", () => {
+      expect(() => new Database(":memory:", { blobEncoding: "base32" as any })).toThrow();
+    });
+  });
 });
@@ -0,0 +1,281 @@
+import { expect, test, describe, beforeEach } from "bun:test";
+import { Database } from "../index";
+
+describe("SQLite NAPI - CRUD Helpers", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec(
+      "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER, status TEXT DEFAULT 'active')"
+    );
+  });
+
+  describe("insert", () => {
+    test("inserts a row from an object", () => {
+      const result = db.insert("users", { name: "Alice", age: 30 });
+
+      expect(result.changes).toBe(1);
+      expect(result.lastInsertRowid).toBe(1);
+      const row = db.query("SELECT * FROM users WHERE id = ?").get([1]);
+      expect(row).toEqual({ id: 1, name: "Alice", age: 30, status: "active" });
+    });
+
+    test("reports a null lastInsertRowid for WITHOUT ROWID tables", () => {
+      db.exec("CREATE TABLE kv (key TEXT PRIMARY KEY, value TEXT) WITHOUT ROWID");
+      db.insert("users", { name: "Alice" });
+
+      const result = db.insert("kv", { key: "a", value: "1" });
+
+      expect(result.changes).toBe(1);
+      expect(result.lastInsertRowid).toBeNull();
+      expect(db.insertMany("kv", [{ key: "b" }, { key: "c" }]).lastInsertRowid).toBeNull();
+    });
+
+    test("skips undefined values so column defaults apply", () => {
+      db.insert("users", { name: "Bob", status: undefined });
+
+      const row = db.query("SELECT status FROM users").get();
+      expect(row.status).toBe("active");
+    });
+
+    test("binds values as parameters rather than SQL", () => {
+      db.insert("users", { name: "Robert'); DROP TABLE users; --" });
+
+      expect(db.tableExists("users")).toBe(true);
+      expect(db.query("SELECT name FROM users").get().name).toBe("Robert'); DROP TABLE users; --");
+    });
+
+    test("quotes column names that are keywords", () => {
+      db.exec('CREATE TABLE orders ("order" INTEGER, "group" TEXT)');
+      db.insert("orders", { order: 1, group: "a" });
+
+      expect(db.query('SELECT "order", "group" FROM orders').get()).toEqual({ order: 1, group: "a" });
+    });
+
+    test("throws for unknown columns", () => {
+      expect(() => db.insert("users", { name: "Alice", missing: 1 })).toThrow();
+    });
+
+    test("throws for non-object data", () => {
+      expect(() => db.insert("users", [1, 2] as any)).toThrow();
+    });
+  });
+
+  describe("insertMany", () => {
+    test("inserts all rows", () => {
+      const result = db.insertMany("users", [{ name: "A" }, { name: "B", age: 2 }, { name: "C" }]);
+
+      expect(result.changes).toBe(3);
+      expect(result.lastInsertRowid).toBe(3);
+      expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(3);
+    });
+
+    test("rolls back every row when one insert fails", () => {
+      db.insert("users", { name: "First" });
+      expect(() => db.insertMany("users", [{ name: "A" }, { age: 5 }])).toThrow();
+
+      expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(1);
+      expect(db.query("SELECT last_insert_rowid() AS id").get().id).toBe(1);
+    });
+
+    test("composes with an open transaction", () => {
+      const tx = db.transaction();
+      db.insertMany("users", [{ name: "A" }, { name: "B" }]);
+      tx.rollback();
+
+      expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(0);
+    });
+  });
+
+  describe("update", () => {
+    beforeEach(() => {
+      db.insertMany("users", [{ name: "A", age: 1 }, { name: "B", age: 2 }, { name: "C" }]);
+    });
+
+    test("updates rows matching the where object", () => {
+      const result = db.update("users", { age: 10 }, { name: "A" });
+
+      expect(result.changes).toBe(1);
+      expect(db.query("SELECT age FROM users WHERE name = 'A'").get().age).toBe(10);
+      expect(db.query("SELECT age FROM users WHERE name = 'B'").get().age).toBe(2);
+    });
+
+    test("matches null values with IS NULL", () => {
+      const result = db.update("users", { age: 0 }, { age: null });
+
+      expect(result.changes).toBe(1);
+      expect(db.query("SELECT age FROM users WHERE name = 'C'").get().age).toBe(0);
+    });
+
+    test("refuses an empty where object by default", () => {
+      expect(() => db.update("users", { age: 0 }, {})).toThrow();
+    });
+
+    test("updates every row with allowFullTableUpdate", () => {
+      const result = db.update("users", { age: 0 }, {}, { allowFullTableUpdate: true });
+
+      expect(result.changes).toBe(3);
+    });
+
+    test("throws for empty data", () => {
+      expect(() => db.update("users", {}, { name: "A" })).toThrow();
+    });
+  });
+
+  describe("updateMany", () => {
+    beforeEach(() => {
+      db.insertMany("users", [{ name: "A", age: 1 }, { name: "B", age: 2 }, { name: "C", age: 3 }]);
+    });
+
+    test("applies each row's values to the row with its key", () => {
+      const result = db.updateMany("users", "id", [
+        { id: 1, name: "Alice", age: 31 },
+        { id: 3, age: 33, name: "Carol" },
+      ]);
+
+      expect(result.changes).toBe(2);
+      expect(db.query("SELECT name, age FROM users ORDER BY id").values()).toEqual([
+        ["Alice", 31],
+        ["B", 2],
+        ["Carol", 33],
+      ]);
+    });
+
+    test("ignores keys with no matching row", () => {
+      const result = db.updateMany("users", "id", [{ id: 99, age: 1 }]);
+
+      expect(result.changes).toBe(0);
+    });
+
+    test("updates more rows than fit in one statement's parameters", () => {
+      db.setLimit("VARIABLE_NUMBER", 10);
+      const rows = [1, 2, 3].map((id) => ({ id, age: id * 10 }));
+
+      expect(db.updateMany("users", "id", rows).changes).toBe(3);
+      expect(db.query("SELECT age FROM users ORDER BY id").values()).toEqual([[10], [20], [30]]);
+    });
+
+    test("rolls back every batch when one fails", () => {
+      db.setLimit("VARIABLE_NUMBER", 2);
+
+      expect(() =>
+        db.updateMany("users", "id", [
+          { id: 1, name: "Alice" },
+          { id: 2, name: null },
+        ])
+      ).toThrow();
+      expect(db.query("SELECT name FROM users WHERE id = 1").get().name).toBe("A");
+    });
+
+    test("requires the same columns in every row", () => {
+      expect(() => db.updateMany("users", "id", [{ id: 1, age: 5 }, { id: 2, name: "B" }])).toThrow(
+        /missing column/
+      );
+      expect(() => db.updateMany("users", "id", [{ age: 5 }])).toThrow();
+      expect(() => db.updateMany("users", "id", [{ id: 1 }])).toThrow(/besides the key/);
+    });
+  });
+
+  describe("jsonSet / jsonRemove", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE docs (id INTEGER PRIMARY KEY, data TEXT)");
+      db.run(`INSERT INTO docs (id, data) VALUES (1, '{"a":1}'), (2, NULL)`);
+    });
+
+    const data = (id: number) => JSON.parse(db.query("SELECT data FROM docs WHERE id = ?").get(id).data);
+
+    test("sets scalar and nested values as JSON", () => {
+      const result = db.jsonSet("docs", "data", 1, "$.b", "x");
+      db.jsonSet("docs", "data", 1, "$.c", { n: [1, 2] });
+      db.jsonSet("docs", "data", 1, "$.a", true);
+
+      expect(result.changes).toBe(1);
+      expect(data(1)).toEqual({ a: true, b: "x", c: { n: [1, 2] } });
+    });
+
+    test("starts a NULL document as an empty object", () => {
+      db.jsonSet("docs", "data", 2, "$.z", 5);
+
+      expect(data(2)).toEqual({ z: 5 });
+    });
+
+    test("removes a path", () => {
+      db.jsonSet("docs", "data", 1, "$.b", "x");
+      const result = db.jsonRemove("docs", "data", 1, "$.a");
+
+      expect(result.changes).toBe(1);
+      expect(data(1)).toEqual({ b: "x" });
+    });
+
+    test("reports no changes for a missing rowid", () => {
+      expect(db.jsonSet("docs", "data", 99, "$.a", 1).changes).toBe(0);
+    });
+
+    test("throws for a bad path or identifier", () => {
+      expect(() => db.jsonSet("docs", "data", 1, "a", 1)).toThrow(/bad JSON path/);
+      expect(() => db.jsonRemove("docs", "data; DROP TABLE docs", 1, "$.a")).toThrow();
+    });
+  });
+
+  describe("delete", () => {
+    beforeEach(() => {
+      db.insertMany("users", [{ name: "A", age: 1 }, { name: "B", age: 2 }]);
+    });
+
+    test("deletes rows matching the where object", () => {
+      const result = db.delete("users", { name: "A", age: 1 });
+
+      expect(result.changes).toBe(1);
+      expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(1);
+    });
+
+    test("refuses an empty where object by default", () => {
+      expect(() => db.delete("users", {})).toThrow();
+      expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(2);
+    });
+
+    test("deletes every row with allowFullTableUpdate", () => {
+      const result = db.delete("users", {}, { allowFullTableUpdate: true });
+
+      expect(result.changes).toBe(2);
+    });
+  });
+
+  describe("upsert", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT, updated INTEGER)");
+      db.insert("settings", { key: "theme", value: "light", updated: 1 });
+    });
+
+    test("inserts when there is no conflict", () => {
+      const result = db.upsert("settings", { key: "lang", value: "en" }, ["key"]);
+
+      expect(result.changes).toBe(1);
+      expect(result.lastInsertRowid).toBe(2);
+      expect(db.query("SELECT COUNT(*) AS n FROM settings").get().n).toBe(2);
+    });
+
+    test("updates the existing row on conflict", () => {
+      const result = db.upsert("settings", { key: "theme", value: "dark", updated: 2 }, ["key"]);
+
+      expect(result.changes).toBe(1);
+      expect(result.lastInsertRowid).toBeNull();
+      const row = db.query("SELECT value, updated FROM settings WHERE key = 'theme'").get();
+      expect(row).toEqual({ value: "dark", updated: 2 });
+      expect(db.query("SELECT COUNT(*) AS n FROM settings").get().n).toBe(1);
+    });
+
+    test("keeps the existing row with doNothing", () => {
+      const result = db.upsert("settings", { key: "theme", value: "dark" }, ["key"], { doNothing: true });
+
+      expect(result.changes).toBe(0);
+      expect(result.lastInsertRowid).toBeNull();
+      expect(db.query("SELECT value FROM settings WHERE key = 'theme'").get().value).toBe("light");
+    });
+
+    test("throws without conflict columns", () => {
+      expect(() => db.upsert("settings", { key: "theme" }, [])).toThrow();
+    });
+  });
+});
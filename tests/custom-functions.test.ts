@@ -71,6 +71,44 @@ describe("SQLite NAPI - Custom Functions", () => {
 
       expect(row).toBeDefined();
     });
+
+    test("removeFunction unregisters a function so it can be registered again", () => {
+      db.createFunction("reloadable", () => null, { argCount: 1 });
+      db.removeFunction("reloadable");
+
+      expect(() => db.query("SELECT reloadable(1)").get()).toThrow(/no such function/);
+      db.createFunction("reloadable", () => null);
+      expect(db.query("SELECT reloadable(1, 2) AS result").get()).toEqual({ result: null });
+    });
+
+    test("removeFunction throws for unknown functions", () => {
+      expect(() => db.removeFunction("never_registered")).toThrow(/not registered/);
+    });
+
+    test("functions are non-deterministic unless marked deterministic", () => {
+      db.createFunction("volatile_fn", () => null, { argCount: 1 });
+      db.createFunction("pure_fn", () => null, { argCount: 1, deterministic: true });
+
+      expect(() => db.exec("CREATE INDEX idx_volatile ON test (volatile_fn(value))")).toThrow(
+        /non-deterministic/
+      );
+      db.exec("CREATE INDEX idx_pure ON test (pure_fn(value))");
+    });
+
+    test("argCount fixes the function's arity", () => {
+      db.createFunction("one_arg", () => null, { argCount: 1 });
+
+      expect(db.query("SELECT one_arg(1) AS result").get()).toEqual({ result: null });
+      expect(() => db.query("SELECT one_arg() AS result").get()).toThrow(/wrong number of arguments/);
+    });
+
+    test("directOnly functions can't be used from views", () => {
+      db.createFunction("direct_fn", () => null, { directOnly: true });
+      db.exec("CREATE VIEW direct_view AS SELECT direct_fn() AS result");
+
+      expect(db.query("SELECT direct_fn() AS result").get()).toEqual({ result: null });
+      expect(() => db.query("SELECT * FROM direct_view").all()).toThrow(/unsafe use/);
+    });
   });
 
   describe("function cleanup", () => {
@@ -89,6 +127,114 @@ describe("SQLite NAPI - Custom Functions", () => {
   });
 });
 
+describe("SQLite NAPI - REGEXP", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE emails (address TEXT)");
+    db.run("INSERT INTO emails (address) VALUES (?), (?), (?)", ["a@example.com", "not-an-email", null]);
+  });
+
+  test("REGEXP is unavailable until enabled", () => {
+    expect(() => db.query("SELECT 'a' REGEXP 'a'").get()).toThrow();
+  });
+
+  test("enableRegexp backs the REGEXP operator", () => {
+    db.enableRegexp();
+
+    const rows = db.query("SELECT address FROM emails WHERE address REGEXP ?").all(["^[^@]+@[^@]+$"]);
+    expect(rows).toEqual([{ address: "a@example.com" }]);
+  });
+
+  test("NULL operands produce NULL", () => {
+    db.enableRegexp();
+
+    expect(db.query("SELECT NULL REGEXP 'a' AS m").get().m).toBeNull();
+  });
+
+  test("invalid patterns throw", () => {
+    db.enableRegexp();
+
+    expect(() => db.query("SELECT 'a' REGEXP '(' AS m").get()).toThrow();
+  });
+
+  test("enabling twice is a no-op", () => {
+    db.enableRegexp();
+    expect(() => db.enableRegexp()).not.toThrow();
+    expect(db.query("SELECT 'abc' REGEXP 'b' AS m").get().m).toBe(1);
+  });
+
+  test("replaces a regexp stub registered with createFunction", () => {
+    db.createFunction("regexp", () => null);
+    db.enableRegexp();
+
+    expect(db.query("SELECT 'abc' REGEXP 'b' AS m").get().m).toBe(1);
+    expect(db.listFunctions()).toEqual(["regexp"]);
+    db.removeFunction("regexp");
+    expect(() => db.query("SELECT 'abc' REGEXP 'b'").get()).toThrow();
+  });
+});
+
+describe("SQLite NAPI - Crypto Functions", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.enableCryptoFunctions();
+  });
+
+  test("hashes text as lowercase hex", () => {
+    const row = db.query("SELECT sha256('abc') AS s, md5('abc') AS m, crc32('123456789') AS c").get();
+
+    expect(row.s).toBe("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    expect(row.m).toBe("900150983cd24fb0d6963f7d28e17f72");
+    expect(row.c).toBe("cbf43926");
+  });
+
+  test("hashes blobs and returns raw digests from _blob variants", () => {
+    const row = db
+      .query("SELECT md5(?) AS hex, md5_blob(?) AS raw")
+      .get([Buffer.from("abc"), Buffer.from("abc")]);
+
+    expect(row.hex).toBe("900150983cd24fb0d6963f7d28e17f72");
+    expect(Buffer.from(row.raw, "base64").toString("hex")).toBe("900150983cd24fb0d6963f7d28e17f72");
+  });
+
+  test("returns NULL for NULL input", () => {
+    expect(db.query("SELECT sha256(NULL) AS h").get().h).toBeNull();
+  });
+
+  test("hashes numbers as the text SQLite converts them to", () => {
+    const row = db
+      .query(
+        "SELECT sha256(1.0) = sha256(CAST(1.0 AS TEXT)) AS real, sha256(1.0) = sha256('1.0') AS text, " +
+          "md5(0.1) = md5(CAST(0.1 AS TEXT)) AS fraction, sha256(42) = sha256('42') AS int"
+      )
+      .get();
+
+    expect(row).toEqual({ real: 1, text: 1, fraction: 1, int: 1 });
+  });
+
+  test("replaces stubs registered with createFunction", () => {
+    const other = new Database(":memory:");
+    other.createFunction("sha256", () => null);
+    other.enableCryptoFunctions();
+
+    expect(other.query("SELECT sha256('abc') AS s").get().s).toBe(
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    expect(other.listFunctions()).toContain("sha256");
+  });
+
+  test("can be used in generated columns", () => {
+    db.exec("CREATE TABLE docs (body TEXT, etag TEXT GENERATED ALWAYS AS (md5(body)) STORED)");
+    db.run("INSERT INTO docs (body) VALUES (?)", ["abc"]);
+
+    expect(db.query("SELECT etag FROM docs").get().etag).toBe("900150983cd24fb0d6963f7d28e17f72");
+  });
+});
+
 describe("SQLite NAPI - Custom Collations", () => {
   let db: Database;
 
@@ -100,6 +246,52 @@ describe("SQLite NAPI - Custom Collations", () => {
     db.run("INSERT INTO items (name) VALUES (?)", ["cherry"]);
   });
 
+  describe("listFunctions / listCollations", () => {
+    test("list registered names in sorted order", () => {
+      expect(db.listFunctions()).toEqual([]);
+      db.createFunction("zeta", () => null);
+      db.createFunction("alpha", () => null);
+      db.createCollation("my_order", () => 0);
+
+      expect(db.listFunctions()).toEqual(["alpha", "zeta"]);
+      expect(db.listCollations()).toEqual(["my_order"]);
+
+      db.removeFunction("zeta");
+      expect(db.listFunctions()).toEqual(["alpha"]);
+    });
+
+    test("include built-in definitions on request", () => {
+      db.createFunction("alpha", () => null);
+      db.createCollation("my_order", () => 0);
+
+      const functions = db.listFunctions(true);
+      expect(functions).toContain("alpha");
+      expect(functions).toContain("upper");
+      expect(db.listCollations(true)).toEqual(["BINARY", "NOCASE", "RTRIM", "my_order"]);
+    });
+
+    test("leave removed collations out of the built-in list", () => {
+      db.createCollation("my_order", () => 0);
+      expect(db.listCollations(true)).toContain("my_order");
+
+      db.removeCollation("my_order");
+      expect(db.listCollations()).toEqual([]);
+      expect(db.listCollations(true)).toEqual(["BINARY", "NOCASE", "RTRIM"]);
+    });
+
+    test("hasCollation finds built-in and registered collations", () => {
+      expect(db.hasCollation("NOCASE")).toBe(true);
+      expect(db.hasCollation("rtrim")).toBe(true);
+      expect(db.hasCollation("my_order")).toBe(false);
+
+      db.createCollation("my_order", () => 0);
+      expect(db.hasCollation("MY_ORDER")).toBe(true);
+
+      db.removeCollation("my_order");
+      expect(db.hasCollation("my_order")).toBe(false);
+    });
+  });
+
   describe("createCollation", () => {
     test("registers a custom collation", () => {
       // Note: The current implementation uses default Rust string comparison
@@ -122,6 +314,21 @@ describe("SQLite NAPI - Custom Collations", () => {
       }).toThrow();
     });
 
+    test("removeCollation unregisters a collation so it can be registered again", () => {
+      db.createCollation("reloadable", () => 0);
+      db.removeCollation("reloadable");
+
+      expect(() => db.query("SELECT name FROM items ORDER BY name COLLATE reloadable").all()).toThrow(
+        /no such collation/
+      );
+      db.createCollation("reloadable", () => 0);
+      expect(db.query("SELECT name FROM items ORDER BY name COLLATE reloadable").all().length).toBe(3);
+    });
+
+    test("removeCollation throws for unknown collations", () => {
+      expect(() => db.removeCollation("never_registered")).toThrow(/not registered/);
+    });
+
     test("collation can be used in ORDER BY", () => {
       db.createCollation("reverse", () => 0);
 
@@ -171,3 +378,151 @@ describe("SQLite NAPI - Custom Collations", () => {
     });
   });
 });
+
+describe("SQLite NAPI - Authorizer", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, password TEXT)");
+    db.run("INSERT INTO users (name, password) VALUES (?, ?)", ["alice", "secret"]);
+  });
+
+  test("receives the action and its arguments", () => {
+    const seen: any[] = [];
+    db.setAuthorizer((action, arg1, arg2, database) => {
+      seen.push([action, arg1, arg2, database]);
+      return "allow";
+    });
+
+    db.query("SELECT name FROM users").all();
+
+    expect(seen).toContainEqual(["SELECT", null, null, null]);
+    expect(seen).toContainEqual(["READ", "users", "name", "main"]);
+  });
+
+  test("deny blocks statements during prepare", () => {
+    db.setAuthorizer((action) => (action === "PRAGMA" || action === "ATTACH" ? "deny" : "allow"));
+
+    expect(() => db.query("PRAGMA user_version").get()).toThrow();
+    expect(() => db.exec("ATTACH ':memory:' AS other")).toThrow();
+    expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(1);
+  });
+
+  test("ignore reads a column as NULL", () => {
+    db.setAuthorizer((action, table, column) =>
+      action === "READ" && table === "users" && column === "password" ? "ignore" : "allow"
+    );
+
+    expect(db.query("SELECT name, password FROM users").get()).toEqual({ name: "alice", password: null });
+  });
+
+  test("a throwing callback denies the action", () => {
+    db.setAuthorizer(() => {
+      throw new Error("boom");
+    });
+
+    expect(() => db.query("SELECT 1").get()).toThrow();
+  });
+
+  test("clearAuthorizer restores normal access", () => {
+    db.setAuthorizer(() => "deny");
+    db.clearAuthorizer();
+
+    expect(db.query("SELECT COUNT(*) AS n FROM users").get().n).toBe(1);
+  });
+});
+
+describe("SQLite NAPI - Trace", () => {
+  let db: Database;
+  const flush = () => new Promise((resolve) => setTimeout(resolve, 10));
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+  });
+
+  test("reports each statement with its parameters expanded", async () => {
+    const traced: string[] = [];
+    db.setTrace((sql) => traced.push(sql));
+
+    db.run("INSERT INTO users (name) VALUES (?)", ["O'Brien"]);
+    db.query("SELECT * FROM users WHERE id = ?").get([1]);
+    await flush();
+
+    expect(traced).toEqual([
+      "INSERT INTO users (name) VALUES ('O''Brien')",
+      "SELECT * FROM users WHERE id = 1",
+    ]);
+  });
+
+  test("reports statements run by triggers", async () => {
+    db.exec("CREATE TABLE log (name TEXT)");
+    db.exec(
+      "CREATE TRIGGER users_log AFTER INSERT ON users BEGIN INSERT INTO log VALUES (new.name); END"
+    );
+    const traced: string[] = [];
+    db.setTrace((sql) => traced.push(sql));
+
+    db.run("INSERT INTO users (name) VALUES ('alice')");
+    await flush();
+
+    expect(traced).toContain("-- TRIGGER users_log");
+  });
+
+  test("the callback may use the database", async () => {
+    let count: number | undefined;
+    db.setTrace(() => {
+      db.clearTrace();
+      count = db.query("SELECT COUNT(*) AS n FROM users").get().n;
+    });
+
+    db.run("INSERT INTO users (name) VALUES ('bob')");
+    await flush();
+
+    expect(count).toBe(1);
+  });
+
+  test("setProfiler reports each statement's SQL and duration", async () => {
+    const profiles: { sql: string; nanos: number }[] = [];
+    db.setProfiler((profile) => profiles.push(profile));
+
+    db.query("SELECT * FROM users WHERE id = ?").get([1]);
+    await flush();
+
+    expect(profiles.length).toBe(1);
+    expect(profiles[0].sql).toBe("SELECT * FROM users WHERE id = ?");
+    expect(profiles[0].nanos).toBeGreaterThanOrEqual(0);
+  });
+
+  test("trace and profiler callbacks work together", async () => {
+    const traced: string[] = [];
+    const profiled: string[] = [];
+    db.setTrace((sql) => traced.push(sql));
+    db.setProfiler(({ sql }) => profiled.push(sql));
+    db.clearTrace();
+
+    db.query("SELECT 1").get();
+    await flush();
+
+    expect(traced).toEqual([]);
+    expect(profiled).toEqual(["SELECT 1"]);
+
+    db.clearProfiler();
+    db.query("SELECT 2").get();
+    await flush();
+
+    expect(profiled).toEqual(["SELECT 1"]);
+  });
+
+  test("clearTrace stops reporting", async () => {
+    const traced: string[] = [];
+    db.setTrace((sql) => traced.push(sql));
+    db.clearTrace();
+
+    db.query("SELECT 1").get();
+    await flush();
+
+    expect(traced).toEqual([]);
+  });
+});
@@ -205,6 +205,33 @@ describe("SQLite NAPI - Database Properties", () => {
     });
   });
 
+  describe("isAutocommit", () => {
+    test("is true outside a transaction and false inside", () => {
+      const db = new Database(":memory:");
+      expect(db.isAutocommit()).toBe(true);
+
+      const tx = db.transaction(null);
+      expect(db.isAutocommit()).toBe(false);
+
+      tx.commit();
+      expect(db.isAutocommit()).toBe(true);
+      db.close();
+    });
+
+    test("inTransaction reflects BEGIN and COMMIT run through exec", () => {
+      const db = new Database(":memory:");
+
+      db.exec("BEGIN");
+      expect(db.isAutocommit()).toBe(false);
+      expect(db.inTransaction()).toBe(true);
+
+      db.exec("COMMIT");
+      expect(db.isAutocommit()).toBe(true);
+      expect(db.inTransaction()).toBe(false);
+      db.close();
+    });
+  });
+
   describe("property combinations", () => {
     test("isClosed and inTransaction interaction", () => {
       const db = new Database(":memory:");
@@ -1,5 +1,5 @@
 import { expect, test, describe, beforeEach } from "bun:test";
-import { Database, getSqliteVersion } from "../index";
+import { Database, getGlobalMemoryUsed, getSqliteVersion, setSoftHeapLimit } from "../index";
 
 describe("SQLite NAPI - Core Functions", () => {
   test("getSqliteVersion returns version string", () => {
@@ -8,6 +8,19 @@ describe("SQLite NAPI - Core Functions", () => {
     expect(typeof version).toBe("string");
     expect(version.length).toBeGreaterThan(0);
   });
+
+  test("getGlobalMemoryUsed reports allocated bytes", () => {
+    const db = new Database(":memory:");
+    db.exec("CREATE TABLE t (x)");
+    expect(getGlobalMemoryUsed()).toBeGreaterThan(0);
+    db.close();
+  });
+
+  test("setSoftHeapLimit returns the previous limit", () => {
+    const original = setSoftHeapLimit(-1);
+    expect(setSoftHeapLimit(8 * 1024 * 1024)).toBe(original);
+    expect(setSoftHeapLimit(original)).toBe(8 * 1024 * 1024);
+  });
 });
 
 describe("SQLite NAPI - Database Class", () => {
@@ -26,7 +39,7 @@ describe("SQLite NAPI - Database Class", () => {
     const result = db.run("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)", []);
     expect(result).toBeDefined();
     expect(result.changes).toBe(0);
-    expect(result.lastInsertRowid).toBe(0);
+    expect(result.lastInsertRowid).toBeNull();
   });
 
   test("Database.run executes SQL with parameters", () => {
@@ -54,9 +67,364 @@ describe("SQLite NAPI - Database Class", () => {
     expect(result2.lastInsertRowid).toBe(2);
   });
 
+  test("lastInsertRowid is null for statements that don't insert", () => {
+    db.run("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", []);
+    db.run("INSERT INTO users (name) VALUES (?)", ["Alice"]);
+
+    expect(db.run("UPDATE users SET name = 'Alicia'").lastInsertRowid).toBeNull();
+    expect(db.run("DELETE FROM users WHERE id = 99").lastInsertRowid).toBeNull();
+    expect(db.query("UPDATE users SET name = name").run().lastInsertRowid).toBeNull();
+    expect(db.run("REPLACE INTO users (id, name) VALUES (5, 'Eve')").lastInsertRowid).toBe(5);
+    expect(
+      db.run("WITH n(x) AS (SELECT 'Bob') INSERT INTO users (name) SELECT x FROM n").lastInsertRowid
+    ).toBe(6);
+    expect(db.exec("UPDATE users SET name = name; INSERT INTO users (name) VALUES ('Cy')").lastInsertRowid).toBe(7);
+    expect(db.exec("UPDATE users SET name = 'insert; values'").lastInsertRowid).toBeNull();
+  });
+
+  test("lastInsertRowid is null for inserts that change no rows", () => {
+    db.run("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE)", []);
+    expect(db.run("INSERT INTO users (name) VALUES ('Alice')").lastInsertRowid).toBe(1);
+
+    expect(db.run("INSERT OR IGNORE INTO users (name) VALUES ('Alice')").lastInsertRowid).toBeNull();
+    expect(db.query("INSERT OR IGNORE INTO users (name) VALUES (?)").run(["Alice"]).lastInsertRowid).toBeNull();
+    expect(db.run("INSERT INTO users (name) SELECT name FROM users WHERE 0").lastInsertRowid).toBeNull();
+  });
+
+  test("lastInsertRowid is null for an upsert taking its DO UPDATE path", () => {
+    db.run("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE, visits INTEGER DEFAULT 0)", []);
+    db.run("INSERT INTO users (name) VALUES ('Alice')");
+    db.run("INSERT INTO users (name) VALUES ('Bob')");
+    const upsert = "INSERT INTO users (name) VALUES (?) ON CONFLICT (name) DO UPDATE SET visits = visits + 1";
+
+    const updated = db.run(upsert, ["Alice"]);
+    expect(updated.changes).toBe(1);
+    expect(updated.lastInsertRowid).toBeNull();
+    expect(db.query(upsert).run(["Alice"]).lastInsertRowid).toBeNull();
+    expect(db.runWithRetry(upsert, ["Alice"]).lastInsertRowid).toBeNull();
+    expect(db.dryRun(upsert, ["Alice"]).lastInsertRowid).toBeNull();
+    expect(db.exec("INSERT INTO users (name) VALUES ('Bob') ON CONFLICT (name) DO UPDATE SET visits = 9").lastInsertRowid).toBeNull();
+    expect(db.transactionFn(null, [
+      "INSERT INTO users (name) VALUES ('Bob') ON CONFLICT (name) DO UPDATE SET visits = 10",
+    ]).lastInsertRowid).toBeNull();
+    const tx = db.transaction(null);
+    expect(tx.run(upsert, ["Alice"]).lastInsertRowid).toBeNull();
+    tx.commit();
+
+    expect(db.run(upsert, ["Cy"]).lastInsertRowid).toBe(3);
+    // SQL's own last_insert_rowid() still sees the previous insert
+    expect(db.query("SELECT last_insert_rowid() AS id").get()).toEqual({ id: 3 });
+  });
+
+  test("setLastInsertRowid overrides lastInsertRowid until the next insert", () => {
+    db.run("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", []);
+    db.run("INSERT INTO users (name) VALUES (?)", ["Alice"]);
+
+    db.setLastInsertRowid(1000);
+    db.run("UPDATE users SET name = 'Alicia'");
+    expect(db.query("SELECT last_insert_rowid() AS id").get().id).toBe(1000);
+    expect(db.run("INSERT INTO users (name) VALUES (?)", ["Bob"]).lastInsertRowid).toBe(2);
+  });
+
+  test("lastInsertRowid ignores inserts made by triggers", () => {
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+    db.exec("CREATE TABLE audit (id INTEGER PRIMARY KEY, note TEXT)");
+    db.exec("INSERT INTO audit (id, note) VALUES (500, 'seed')");
+    db.exec(
+      "CREATE TRIGGER users_audit AFTER INSERT ON users BEGIN INSERT INTO audit (note) VALUES (NEW.name); END"
+    );
+
+    const result = db.run("INSERT INTO users (name) VALUES (?)", ["Alice"]);
+    expect(result.lastInsertRowid).toBe(1);
+    expect(db.query("SELECT MAX(id) AS id FROM audit").get()).toEqual({ id: 501 });
+  });
+
+  test("totalChanges includes rows removed by foreign key cascades", () => {
+    db.exec("PRAGMA foreign_keys = ON");
+    db.exec("CREATE TABLE authors (id INTEGER PRIMARY KEY)");
+    db.exec(
+      "CREATE TABLE books (id INTEGER PRIMARY KEY, author_id INTEGER REFERENCES authors(id) ON DELETE CASCADE)"
+    );
+    db.exec("INSERT INTO authors (id) VALUES (1), (2)");
+    db.exec("INSERT INTO books (author_id) VALUES (1), (1), (1), (2)");
+
+    const result = db.run("DELETE FROM authors WHERE id = ?", [1]);
+    expect(result.changes).toBe(1);
+    expect(result.totalChanges).toBe(4);
+  });
+
+  test("totalChanges includes rows changed by triggers", () => {
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+    db.exec("CREATE TABLE audit (note TEXT)");
+    db.exec(
+      "CREATE TRIGGER users_audit AFTER UPDATE ON users BEGIN INSERT INTO audit VALUES (NEW.name); END"
+    );
+    db.exec("INSERT INTO users (name) VALUES ('Alice'), ('Bob')");
+
+    const result = db.run("UPDATE users SET name = upper(name)");
+    expect(result.changes).toBe(2);
+    expect(result.totalChanges).toBe(4);
+    expect(db.run("SELECT 1").totalChanges).toBe(0);
+  });
+
   test("Database.query returns Statement object", () => {
     db.run("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)", []);
     const stmt = db.query("SELECT * FROM users");
     expect(stmt).toBeDefined();
   });
 });
+
+describe("SQLite NAPI - runMany", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE, email TEXT)");
+  });
+
+  test("returns the rowid generated by every insert", () => {
+    db.exec("INSERT INTO users (id, name) VALUES (10, 'seed')");
+    const result = db.runMany("INSERT INTO users (name) VALUES (?)", [["Alice"], ["Bob"], ["Carol"]]);
+
+    expect(result).toEqual({ changes: 3, rowids: [11, 12, 13] });
+  });
+
+  test("accepts named parameter sets and unbinds missing ones", () => {
+    db.runMany("INSERT INTO users (name, email) VALUES (:name, :email)", [
+      { name: "Alice", email: "a@example.com" },
+      { name: "Bob" },
+    ]);
+
+    expect(db.query("SELECT name, email FROM users ORDER BY id").all()).toEqual([
+      { name: "Alice", email: "a@example.com" },
+      { name: "Bob", email: null },
+    ]);
+  });
+
+  test("keeps none of the runs when one fails", () => {
+    expect(() =>
+      db.runMany("INSERT INTO users (name) VALUES (?)", [["Alice"], ["Bob"], ["Alice"]])
+    ).toThrow(/parameter set 2/);
+    expect(db.query("SELECT COUNT(*) AS n FROM users").get()).toEqual({ n: 0 });
+  });
+
+  test("reports null for runs that insert no row", () => {
+    const ignored = db.runMany("INSERT OR IGNORE INTO users (name) VALUES (?)", [["Alice"], ["Alice"], ["Bob"]]);
+    expect(ignored).toEqual({ changes: 2, rowids: [1, null, 2] });
+
+    const upserted = db.runMany(
+      "INSERT INTO users (name) VALUES (?) ON CONFLICT (name) DO UPDATE SET email = 'dup'",
+      [["Bob"], ["Carol"]]
+    );
+    expect(upserted.rowids).toEqual([null, 3]);
+
+    const updated = db.runMany("UPDATE users SET email = ? WHERE name = ?", [["a@example.com", "Alice"]]);
+    expect(updated).toEqual({ changes: 1, rowids: [null] });
+    expect(db.query("SELECT last_insert_rowid() AS id").get().id).toBe(3);
+  });
+});
+
+describe("SQLite NAPI - dryRun", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+    db.exec("INSERT INTO users (name) VALUES ('Alice'), ('Bob'), ('Carol')");
+  });
+
+  test("reports the changes and rolls them back", () => {
+    const result = db.dryRun("DELETE FROM users WHERE id > ?", [1]);
+
+    expect(result.changes).toBe(2);
+    expect(db.query("SELECT COUNT(*) AS n FROM users").get()).toEqual({ n: 3 });
+  });
+
+  test("leaves lastInsertRowid untouched", () => {
+    expect(db.dryRun("INSERT INTO users (name) VALUES ('Dave')").lastInsertRowid).toBe(4);
+    expect(db.query("SELECT last_insert_rowid() AS id").get().id).toBe(3);
+  });
+
+  test("only rolls back its own work inside a transaction", () => {
+    const tx = db.transaction();
+    tx.run("INSERT INTO users (name) VALUES ('Dave')");
+    db.dryRun("DELETE FROM users");
+    tx.commit();
+
+    expect(db.query("SELECT COUNT(*) AS n FROM users").get()).toEqual({ n: 4 });
+  });
+
+  test("rejects statements a rollback can't undo", () => {
+    expect(() => db.dryRun("VACUUM")).toThrow(/VACUUM/);
+    expect(() => db.dryRun("PRAGMA journal_mode = MEMORY")).toThrow(/PRAGMA/);
+    expect(() => db.dryRun("  -- cleanup\n  commit")).toThrow(/COMMIT/);
+    expect(() => db.dryRun("/* setup */ ; attach ':memory:' AS other")).toThrow(/ATTACH/);
+  });
+
+  test("rolls back after a failing statement", () => {
+    expect(() => db.dryRun("INSERT INTO users (id, name) VALUES (1, 'dup')")).toThrow(/Dry run failed/);
+    expect(db.inTransaction()).toBe(false);
+  });
+});
+
+describe("SQLite NAPI - execScript", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+  });
+
+  test("returns a summary for every statement", () => {
+    const results = db.execScript(`
+      CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+      INSERT INTO users (name) VALUES ('Alice'), ('Bob');
+      SELECT name FROM users ORDER BY id;
+    `);
+
+    expect(results.length).toBe(3);
+    expect(results[0]).toEqual({
+      sql: "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);",
+      changes: 0,
+      lastInsertRowid: null,
+    });
+    expect(results[1].changes).toBe(2);
+    expect(results[1].lastInsertRowid).toBe(2);
+    expect(results[2].rows).toEqual([{ name: "Alice" }, { name: "Bob" }]);
+    expect(results[2].changes).toBe(0);
+    expect(results[2].lastInsertRowid).toBeNull();
+  });
+
+  test("reports a null lastInsertRowid for statements that insert nothing", () => {
+    const results = db.execScript(`
+      CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT UNIQUE);
+      INSERT INTO users (name) VALUES ('Alice');
+      INSERT OR IGNORE INTO users (name) VALUES ('Alice');
+      UPDATE users SET name = 'Alicia';
+    `);
+
+    expect(results.map((r) => r.lastInsertRowid)).toEqual([null, 1, null, null]);
+  });
+
+  test("keeps semicolons inside literals and trigger bodies", () => {
+    const results = db.execScript(`
+      CREATE TABLE t (x TEXT);
+      CREATE TABLE log (message TEXT);
+      CREATE TRIGGER t_insert AFTER INSERT ON t BEGIN
+        INSERT INTO log VALUES ('inserted; logged');
+      END;
+      INSERT INTO t VALUES ('a;b');
+      SELECT x FROM t;
+      SELECT message FROM log;
+    `);
+
+    expect(results.length).toBe(6);
+    expect(results[3].changes).toBe(1);
+    expect(results[4].rows).toEqual([{ x: "a;b" }]);
+    expect(results[5].rows).toEqual([{ message: "inserted; logged" }]);
+  });
+
+  test("includes RETURNING rows alongside the change count", () => {
+    db.exec("CREATE TABLE t (id INTEGER PRIMARY KEY, x TEXT)");
+    const [result] = db.execScript("INSERT INTO t (x) VALUES ('a'), ('b') RETURNING id");
+
+    expect(result.rows).toEqual([{ id: 1 }, { id: 2 }]);
+    expect(result.changes).toBe(2);
+  });
+
+  test("reports which statement failed", () => {
+    expect(() => db.execScript("SELECT 1; SELECT * FROM missing;")).toThrow(/statement 2/);
+  });
+});
+
+describe("SQLite NAPI - Slow Query Log", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE t (x INTEGER)");
+  });
+
+  test("reports statements over the threshold", () => {
+    const seen: { sql: string; durationMicros: number }[] = [];
+    db.onSlowQuery(0, (query) => seen.push(query));
+
+    db.run("INSERT INTO t VALUES (?)", [1]);
+    db.query("SELECT x FROM t").all();
+
+    expect(seen.map((q) => q.sql)).toEqual(["INSERT INTO t VALUES (?)", "SELECT x FROM t"]);
+    expect(seen[0].durationMicros).toBeGreaterThanOrEqual(0);
+  });
+
+  test("ignores statements under the threshold", () => {
+    const seen: string[] = [];
+    db.onSlowQuery(60_000_000, (query) => seen.push(query.sql));
+
+    db.query("SELECT 1").get();
+    expect(seen).toEqual([]);
+  });
+
+  test("lets the callback use the database without reporting its own queries", () => {
+    const seen: string[] = [];
+    db.onSlowQuery(0, (query) => {
+      seen.push(query.sql);
+      db.run("INSERT INTO t VALUES (99)");
+    });
+
+    db.query("SELECT COUNT(*) AS n FROM t").get();
+    expect(seen).toEqual(["SELECT COUNT(*) AS n FROM t"]);
+    db.clearSlowQuery();
+    expect(db.query("SELECT x FROM t").all()).toEqual([{ x: 99 }]);
+  });
+
+  test("clearSlowQuery removes the callback", () => {
+    const seen: string[] = [];
+    db.onSlowQuery(0, (query) => seen.push(query.sql));
+    db.clearSlowQuery();
+
+    db.exec("SELECT 1");
+    expect(seen).toEqual([]);
+  });
+});
+
+describe("SQLite NAPI - withTempView", () => {
+  let db: Database;
+
+  const views = () =>
+    db.query("SELECT name FROM sqlite_temp_schema WHERE type = 'view'").all();
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE orders (customer TEXT, amount INTEGER)");
+    db.exec("INSERT INTO orders VALUES ('a', 5), ('b', 7), ('a', 10)");
+  });
+
+  test("exposes the view to the callback and drops it afterwards", () => {
+    const rows = db.withTempView(
+      "totals",
+      "SELECT customer, SUM(amount) AS total FROM orders GROUP BY customer",
+      () => db.query("SELECT * FROM totals ORDER BY total DESC").all()
+    );
+
+    expect(rows).toEqual([
+      { customer: "a", total: 15 },
+      { customer: "b", total: 7 },
+    ]);
+    expect(views()).toEqual([]);
+  });
+
+  test("drops the view when the callback throws", () => {
+    expect(() =>
+      db.withTempView("totals", "SELECT * FROM orders", () => {
+        throw new Error("boom");
+      })
+    ).toThrow("boom");
+    expect(views()).toEqual([]);
+  });
+
+  test("quotes the name and rejects invalid names or extra statements", () => {
+    expect(db.withTempView("order", "SELECT 1 AS x", () => db.query('SELECT x FROM "order"').get())).toEqual({ x: 1 });
+    expect(() => db.withTempView("", "SELECT 1", () => 1)).toThrow(/Identifier cannot be empty/);
+    expect(() => db.withTempView("v", "SELECT 1; DROP TABLE orders", () => 1)).toThrow();
+    expect(db.query("SELECT COUNT(*) AS n FROM orders").get()).toEqual({ n: 3 });
+  });
+});
@@ -124,4 +124,100 @@ describe("SQLite NAPI - Error Handling", () => {
     expect(errorMessage.length).toBeGreaterThan(0);
     expect(errorMessage.toLowerCase()).toContain("no such table");
   });
+
+  test("truncates long SQL with multibyte characters in the message", () => {
+    // "é" is two bytes in UTF-8, so byte 100 falls inside a character
+    const sql = `SELECT * FROM nonexistent WHERE note = '${"é".repeat(100)}'`;
+
+    expect(() => db.run(sql)).toThrow(/no such table/);
+    expect(() => db.exec(sql)).toThrow(/no such table/);
+    expect(() => db.transactionFn(null, [sql])).toThrow(/no such table/);
+  });
+
+  test("keeps only a snippet of long SQL in every error message", () => {
+    const sql = `SELECT * FROM nonexistent WHERE note = '${"x".repeat(1000)}'`;
+    const message = (fn: () => unknown) => {
+      try {
+        fn();
+      } catch (e) {
+        return (e as Error).message;
+      }
+      throw new Error("expected an error");
+    };
+
+    for (const fn of [
+      () => db.run(sql),
+      () => db.runWithRetry(sql),
+      () => db.prepare(sql),
+      () => db.query(sql).all(),
+      () => db.estimateCount(sql),
+      () => db.runMany(sql, [[]]),
+    ]) {
+      const text = message(fn);
+      expect(text).toMatch(/no such table/);
+      expect(text).not.toContain("x".repeat(200));
+    }
+  });
+});
+
+describe("SQLite NAPI - Error Codes", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec(`
+      PRAGMA foreign_keys = ON;
+      CREATE TABLE parent (id INTEGER PRIMARY KEY);
+      CREATE TABLE child (
+        id INTEGER PRIMARY KEY,
+        email TEXT UNIQUE,
+        name TEXT NOT NULL DEFAULT 'x',
+        age INTEGER CHECK (age >= 0),
+        parent_id INTEGER REFERENCES parent(id)
+      );
+      INSERT INTO child (id, email) VALUES (1, 'a@example.com');
+    `);
+  });
+
+  const codeOf = (fn: () => unknown): string | undefined => {
+    try {
+      fn();
+    } catch (e: any) {
+      return e.code;
+    }
+    return undefined;
+  };
+
+  test("constraint violations carry their extended code", () => {
+    expect(codeOf(() => db.run("INSERT INTO child (email) VALUES (?)", ["a@example.com"]))).toBe(
+      "SQLITE_CONSTRAINT_UNIQUE"
+    );
+    expect(codeOf(() => db.run("INSERT INTO child (id) VALUES (1)"))).toBe(
+      "SQLITE_CONSTRAINT_PRIMARYKEY"
+    );
+    expect(codeOf(() => db.run("INSERT INTO child (name) VALUES (NULL)"))).toBe(
+      "SQLITE_CONSTRAINT_NOTNULL"
+    );
+    expect(codeOf(() => db.run("INSERT INTO child (age) VALUES (-1)"))).toBe(
+      "SQLITE_CONSTRAINT_CHECK"
+    );
+    expect(codeOf(() => db.run("INSERT INTO child (parent_id) VALUES (9)"))).toBe(
+      "SQLITE_CONSTRAINT_FOREIGNKEY"
+    );
+  });
+
+  test("other failures use the primary code", () => {
+    expect(codeOf(() => db.exec("SELECT * FROM missing"))).toBe("SQLITE_ERROR");
+    expect(codeOf(() => db.query("SELECT * FROM missing").all())).toBe("SQLITE_ERROR");
+  });
+
+  test("the message is unchanged", () => {
+    try {
+      db.run("INSERT INTO child (email) VALUES (?)", ["a@example.com"]);
+      throw new Error("expected a constraint error");
+    } catch (e: any) {
+      expect(e).toBeInstanceOf(Error);
+      expect(e.message).toContain("UNIQUE constraint failed: child.email");
+    }
+  });
 });
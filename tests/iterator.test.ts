@@ -184,6 +184,29 @@ describe("SQLite NAPI - Iterator Support", () => {
     });
   });
 
+  describe("iter.close", () => {
+    test("stops iteration early", () => {
+      const iter = db.query("SELECT * FROM users ORDER BY id").iter([]);
+
+      expect((iter.next() as any).name).toBe("Alice");
+      iter.close();
+
+      expect(iter.hasMore()).toBe(false);
+      expect(iter.next()).toBeNull();
+      expect(iter.nextValues()).toBeNull();
+      expect(iter.all()).toEqual([]);
+    });
+
+    test("discards the rows so reset has nothing to replay", () => {
+      const iter = db.query("SELECT * FROM users ORDER BY id").iter([]);
+
+      iter.close();
+      iter.reset();
+
+      expect(iter.hasMore()).toBe(false);
+    });
+  });
+
   describe("iter.reset", () => {
     test("resets iterator to beginning", () => {
       const stmt = db.query("SELECT * FROM users ORDER BY id");
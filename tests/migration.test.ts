@@ -27,6 +27,38 @@ describe("SQLite NAPI - Schema Migration", () => {
     });
   });
 
+  describe("user_version", () => {
+    test("reads and writes PRAGMA user_version", () => {
+      expect(db.getUserVersion()).toBe(0);
+
+      db.setUserVersion(7);
+      expect(db.getUserVersion()).toBe(7);
+      expect(db.pragma("user_version")).toBe(7);
+    });
+
+    test("is independent of the _schema_version table", () => {
+      db.setSchemaVersion(3);
+      expect(db.getUserVersion()).toBe(0);
+
+      db.setUserVersion(9);
+      expect(db.getSchemaVersion()).toBe(3);
+    });
+  });
+
+  describe("application_id", () => {
+    test("reads and writes PRAGMA application_id", () => {
+      expect(db.getApplicationId()).toBe(0);
+
+      db.setApplicationId(0x4d594150);
+      expect(db.getApplicationId()).toBe(0x4d594150);
+    });
+
+    test("keeps negative 32-bit ids", () => {
+      db.setApplicationId(-2);
+      expect(db.getApplicationId()).toBe(-2);
+    });
+  });
+
   describe("setSchemaVersion", () => {
     test("creates schema_version table", () => {
       db.setSchemaVersion(1);
@@ -176,12 +208,100 @@ describe("SQLite NAPI - Schema Migration", () => {
       expect(db.tableExists("posts")).toBe(true);
     });
 
+    test("runs transactional: false migrations outside the transaction", () => {
+      const version = db.migrate([
+        { version: 1, sql: "CREATE TABLE logs (id INTEGER PRIMARY KEY, body TEXT)" },
+        { version: 2, sql: "VACUUM", transactional: false },
+        { version: 3, sql: "CREATE TABLE posts (id INTEGER PRIMARY KEY)" },
+      ]);
+
+      expect(version).toBe(3);
+      expect(db.tableExists("posts")).toBe(true);
+      expect(db.query("SELECT version FROM _schema_version ORDER BY version").values()).toEqual([[1], [2], [3]]);
+      expect(db.isAutocommit()).toBe(true);
+    });
+
+    test("keeps earlier migrations when a non-transactional one fails", () => {
+      expect(() =>
+        db.migrate([
+          { version: 1, sql: "CREATE TABLE users (id INTEGER PRIMARY KEY)" },
+          { version: 2, sql: "CREATE TABLE a (x); INVALID SQL", transactional: false },
+          { version: 3, sql: "CREATE TABLE posts (id INTEGER PRIMARY KEY)" },
+        ])
+      ).toThrow(/Migration 2 failed outside a transaction.*stays at version 1/);
+
+      expect(db.getSchemaVersion()).toBe(1);
+      expect(db.tableExists("users")).toBe(true);
+      expect(db.tableExists("a")).toBe(true);
+      expect(db.tableExists("posts")).toBe(false);
+      expect(db.isAutocommit()).toBe(true);
+    });
+
+    test("rolls back only the migrations after a non-transactional one", () => {
+      expect(() =>
+        db.migrate([
+          { version: 1, sql: "CREATE TABLE users (id INTEGER PRIMARY KEY)" },
+          { version: 2, sql: "VACUUM", transactional: false },
+          { version: 3, sql: "CREATE TABLE posts (id INTEGER PRIMARY KEY)" },
+          { version: 4, sql: "INVALID SQL" },
+        ])
+      ).toThrow(/Migration 4 failed.*stays at version 2/);
+
+      expect(db.getSchemaVersion()).toBe(2);
+      expect(db.tableExists("posts")).toBe(false);
+    });
+
     test("works with empty migrations array", () => {
       const version = db.migrate([]);
       expect(version).toBe(0);
     });
   });
 
+  describe("migrateFromEntries", () => {
+    test("orders and describes migrations by filename", () => {
+      const version = db.migrateFromEntries([
+        { filename: "migrations/0002_add_email.sql", sql: "ALTER TABLE users ADD COLUMN email TEXT" },
+        { filename: "migrations/0001_create_users.sql", sql: "CREATE TABLE users (id INTEGER PRIMARY KEY)" },
+      ]);
+
+      expect(version).toBe(2);
+      expect(db.getColumns("users").map((c: any) => c.name)).toEqual(["id", "email"]);
+      expect(db.query("SELECT version, description FROM _schema_version ORDER BY version").all()).toEqual([
+        { version: 1, description: "create users" },
+        { version: 2, description: "add email" },
+      ]);
+    });
+
+    test("honours targetVersion", () => {
+      const entries = [
+        { filename: "0001_users.sql", sql: "CREATE TABLE users (id INTEGER PRIMARY KEY)" },
+        { filename: "0002_posts.sql", sql: "CREATE TABLE posts (id INTEGER PRIMARY KEY)" },
+      ];
+
+      expect(db.migrateFromEntries(entries, 1)).toBe(1);
+      expect(db.tableExists("posts")).toBe(false);
+      expect(db.migrateFromEntries(entries)).toBe(2);
+    });
+
+    test("rejects unparseable filenames", () => {
+      expect(() => db.migrateFromEntries([{ filename: "create_users.sql", sql: "SELECT 1" }])).toThrow(
+        /Invalid migration filename 'create_users.sql'/
+      );
+      expect(() => db.migrateFromEntries([{ filename: "0001_users.txt", sql: "SELECT 1" }])).toThrow(/\.sql/);
+      expect(db.getSchemaVersion()).toBe(0);
+    });
+
+    test("rejects duplicate versions", () => {
+      expect(() =>
+        db.migrateFromEntries([
+          { filename: "0001_users.sql", sql: "CREATE TABLE users (id INTEGER PRIMARY KEY)" },
+          { filename: "1_also_users.sql", sql: "CREATE TABLE people (id INTEGER PRIMARY KEY)" },
+        ])
+      ).toThrow(/Migration version 1 appears in both/);
+      expect(db.tableExists("users")).toBe(false);
+    });
+  });
+
   describe("createTableIfNotExists", () => {
     test("creates table when it doesn't exist", () => {
       const created = db.createTableIfNotExists(
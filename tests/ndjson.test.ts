@@ -0,0 +1,122 @@
+import { expect, test, describe, beforeEach, afterEach } from "bun:test";
+import { Database } from "../index";
+import { existsSync, unlinkSync, mkdirSync, readdirSync, readFileSync, writeFileSync } from "fs";
+import { tmpdir } from "os";
+import { join } from "path";
+
+describe("SQLite NAPI - NDJSON", () => {
+  const testDir = join(tmpdir(), "sqlite-napi-ndjson-tests");
+  let db: Database;
+  let filePath: string;
+
+  beforeEach(() => {
+    if (!existsSync(testDir)) {
+      mkdirSync(testDir, { recursive: true });
+    }
+    filePath = join(testDir, `export-${Date.now()}.ndjson`);
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT, payload TEXT)");
+    db.run("INSERT INTO events (kind, payload) VALUES (?, ?)", ["click", "a"]);
+    db.run("INSERT INTO events (kind, payload) VALUES (?, ?)", ["view", null]);
+    db.run("INSERT INTO events (kind, payload) VALUES (?, ?)", ["click", "line\nbreak"]);
+  });
+
+  afterEach(() => {
+    try {
+      if (existsSync(filePath)) {
+        unlinkSync(filePath);
+      }
+    } catch (e) {
+      // Ignore cleanup errors
+    }
+  });
+
+  describe("exportNdjson", () => {
+    test("writes one JSON object per line", () => {
+      const written = db.exportNdjson("SELECT * FROM events ORDER BY id", filePath);
+
+      expect(written).toBe(3);
+      const lines = readFileSync(filePath, "utf8").trimEnd().split("\n");
+      expect(lines.length).toBe(3);
+      expect(JSON.parse(lines[0])).toEqual({ id: 1, kind: "click", payload: "a" });
+      expect(JSON.parse(lines[1]).payload).toBeNull();
+      expect(JSON.parse(lines[2]).payload).toBe("line\nbreak");
+    });
+
+    test("binds positional and named parameters", () => {
+      expect(db.exportNdjson("SELECT id FROM events WHERE kind = ?", filePath, ["click"])).toBe(2);
+      expect(db.exportNdjson("SELECT id FROM events WHERE kind = $kind", filePath, { kind: "view" })).toBe(1);
+    });
+
+    test("writes an empty file for an empty result", () => {
+      expect(db.exportNdjson("SELECT * FROM events WHERE 0", filePath)).toBe(0);
+      expect(readFileSync(filePath, "utf8")).toBe("");
+    });
+
+    test("throws for invalid SQL", () => {
+      expect(() => db.exportNdjson("SELECT * FROM missing", filePath)).toThrow();
+    });
+
+    test("leaves an existing file untouched when the export fails", () => {
+      writeFileSync(filePath, "previous export\n");
+
+      expect(() => db.exportNdjson("SELECT * FROM missing", filePath)).toThrow();
+      expect(() => db.exportNdjson("SELECT id FROM events WHERE kind = ?", filePath, [])).toThrow();
+
+      expect(readFileSync(filePath, "utf8")).toBe("previous export\n");
+      const leftovers = readdirSync(testDir).filter((name) => name.endsWith(".tmp"));
+      expect(leftovers).toEqual([]);
+    });
+  });
+
+  describe("importNdjson", () => {
+    const write = (lines: string[]) => writeFileSync(filePath, lines.join("\n") + "\n");
+
+    test("inserts each object as a row", () => {
+      write(['{"kind":"a","payload":"x"}', "", '{"kind":"b"}']);
+
+      const result = db.importNdjson("events", filePath);
+
+      expect(result).toEqual({ inserted: 2, skipped: 0 });
+      expect(db.query("SELECT kind, payload FROM events WHERE id > 3 ORDER BY id").all()).toEqual([
+        { kind: "a", payload: "x" },
+        { kind: "b", payload: null },
+      ]);
+    });
+
+    test("round-trips an export", () => {
+      db.exportNdjson("SELECT kind, payload FROM events", filePath);
+      db.exec("DELETE FROM events");
+
+      expect(db.importNdjson("events", filePath).inserted).toBe(3);
+      expect(db.query("SELECT payload FROM events WHERE kind = 'click' ORDER BY id").all().length).toBe(2);
+    });
+
+    test("fails on unknown columns and inserts nothing", () => {
+      write(['{"kind":"a"}', '{"kind":"b","extra":1}']);
+
+      expect(() => db.importNdjson("events", filePath)).toThrow(/Line 2/);
+      expect(db.query("SELECT COUNT(*) AS n FROM events").get().n).toBe(3);
+    });
+
+    test("skips invalid rows when asked", () => {
+      write(['{"kind":"a"}', '{"kind":"b","extra":1}', "not json", "[1,2]"]);
+
+      const result = db.importNdjson("events", filePath, { skipInvalidRows: true });
+
+      expect(result).toEqual({ inserted: 1, skipped: 3 });
+    });
+
+    test("commits in batches", () => {
+      write(['{"kind":"a"}', '{"kind":"b"}', '{"id":1,"kind":"duplicate"}']);
+
+      expect(() => db.importNdjson("events", filePath, { batchSize: 2 })).toThrow();
+      expect(db.query("SELECT COUNT(*) AS n FROM events").get().n).toBe(5);
+    });
+
+    test("throws for a missing table", () => {
+      write(['{"kind":"a"}']);
+      expect(() => db.importNdjson("missing", filePath)).toThrow();
+    });
+  });
+});
@@ -49,6 +49,114 @@ describe("SQLite NAPI - Connection Options", () => {
     });
   });
 
+  describe("applyDefaultPragmas Option", () => {
+    test("applies WAL and foreign keys by default", () => {
+      const db = new Database(testDbPath);
+      expect(db.query("PRAGMA journal_mode").get()).toEqual({ journal_mode: "wal" });
+      expect(db.query("PRAGMA foreign_keys").get()).toEqual({ foreign_keys: 1 });
+      db.close();
+    });
+
+    test("keeps SQLite's defaults when disabled", () => {
+      const db = new Database(testDbPath, { applyDefaultPragmas: false });
+      expect(db.query("PRAGMA journal_mode").get()).toEqual({ journal_mode: "delete" });
+      expect(db.query("PRAGMA synchronous").get()).toEqual({ synchronous: 2 });
+      expect(db.query("PRAGMA foreign_keys").get()).toEqual({ foreign_keys: 0 });
+
+      // Extended result codes stay enabled
+      db.exec("CREATE TABLE t (id INTEGER PRIMARY KEY)");
+      db.run("INSERT INTO t (id) VALUES (1)");
+      try {
+        db.run("INSERT INTO t (id) VALUES (1)");
+        throw new Error("expected a constraint violation");
+      } catch (e: any) {
+        expect(e.code).toBe("SQLITE_CONSTRAINT_PRIMARYKEY");
+      }
+      db.close();
+    });
+
+    test("still applies cacheSpill when disabled", () => {
+      const db = new Database(testDbPath, { applyDefaultPragmas: false, cacheSpill: false });
+      expect(db.getCacheSpill()).toBe(false);
+      db.close();
+    });
+  });
+
+  describe("persistentWal Option", () => {
+    test("keeps the WAL files after closing", () => {
+      const db = new Database(testDbPath, { persistentWal: true });
+      db.exec("CREATE TABLE t (x INTEGER)");
+      db.run("INSERT INTO t VALUES (1)");
+      db.close();
+
+      expect(existsSync(`${testDbPath}-wal`)).toBe(true);
+      unlinkSync(`${testDbPath}-wal`);
+      unlinkSync(`${testDbPath}-shm`);
+    });
+
+    test("removes the WAL files by default", () => {
+      const db = new Database(testDbPath);
+      db.exec("CREATE TABLE t (x INTEGER)");
+      db.close();
+
+      expect(existsSync(`${testDbPath}-wal`)).toBe(false);
+    });
+
+    test("is ignored for in-memory databases", () => {
+      const db = new Database(":memory:", { persistentWal: true });
+      db.close();
+    });
+  });
+
+  describe("Temporary Databases", () => {
+    test("opens a private temporary database for an empty path", () => {
+      const db = new Database("", { temporary: true });
+      db.exec("CREATE TABLE t (x BLOB)");
+      db.run("INSERT INTO t VALUES (randomblob(100000))");
+
+      expect(db.query("SELECT length(x) AS n FROM t").get()).toEqual({ n: 100000 });
+      expect(db.filename()).toBe("");
+      db.close();
+    });
+
+    test("skips WAL, which temporary databases can't use", () => {
+      const db = new Database("");
+      expect(db.query("PRAGMA journal_mode").get()).toEqual({ journal_mode: "delete" });
+      expect(db.query("PRAGMA foreign_keys").get()).toEqual({ foreign_keys: 1 });
+      db.close();
+    });
+
+    test("rejects temporary with a path", () => {
+      expect(() => new Database(testDbPath, { temporary: true })).toThrow(/path must be ""/);
+      expect(existsSync(testDbPath)).toBe(false);
+    });
+
+    test("can't be cloned", () => {
+      const db = new Database("");
+      expect(() => db.cloneReadonly()).toThrow(/temporary database/);
+      db.close();
+    });
+  });
+
+  describe("dataVersion", () => {
+    test("changes when another connection commits", () => {
+      const db = new Database(testDbPath);
+      db.exec("CREATE TABLE t (x INTEGER)");
+      const before = db.dataVersion();
+
+      // The connection's own writes don't change it
+      db.run("INSERT INTO t VALUES (1)");
+      expect(db.dataVersion()).toBe(before);
+
+      const other = new Database(testDbPath);
+      other.run("INSERT INTO t VALUES (2)");
+      other.close();
+
+      expect(db.dataVersion()).not.toBe(before);
+      db.close();
+    });
+  });
+
   describe("Read-Only Option", () => {
     test("opens database in read-only mode", () => {
       // First create a database with some data
@@ -87,6 +195,25 @@ describe("SQLite NAPI - Connection Options", () => {
       db.close();
     });
 
+    test("isReadonly reports the access mode of main and attached schemas", () => {
+      const setupDb = new Database(testDbPath);
+      setupDb.exec("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+      setupDb.close();
+
+      const readonlyDb = new Database(testDbPath, { readonly: true });
+      expect(readonlyDb.isReadonly()).toBe(true);
+      readonlyDb.close();
+
+      const db = new Database(":memory:");
+      expect(db.isReadonly()).toBe(false);
+      db.exec(`ATTACH 'file:${testDbPath}?mode=ro' AS archive`);
+      expect(db.isReadonly("main")).toBe(false);
+      expect(db.isReadonly("archive")).toBe(true);
+      expect(() => db.isReadonly("missing")).toThrow(/missing/);
+      db.exec("DETACH archive");
+      db.close();
+    });
+
     test("read-only option with :memory: still allows writes", () => {
       // In-memory databases with readonly flag may still allow writes
       // This is SQLite's behavior
@@ -161,16 +288,44 @@ describe("SQLite NAPI - Connection Options", () => {
   });
 
   describe("Option Combinations", () => {
-    test("readonly: true with create: true", () => {
-      // This combination is contradictory - SQLite may throw or ignore
-      // We test that it either succeeds or throws appropriately
-      try {
-        const db = new Database(testDbPath, { readonly: true, create: true });
-        db.close();
-      } catch (e) {
-        // If it throws, that's expected behavior for contradictory options
-        expect(e).toBeDefined();
-      }
+    test("readonly: true with create: true is rejected", () => {
+      expect(() => new Database(testDbPath, { readonly: true, create: true })).toThrow(
+        /create needs write access/
+      );
+      expect(existsSync(testDbPath)).toBe(false);
+    });
+
+    test("readonly: true with readwrite: true is rejected", () => {
+      expect(() => new Database(testDbPath, { readonly: true, readwrite: true })).toThrow(
+        /can't both be true/
+      );
+    });
+
+    test("readwrite: false without readonly is rejected", () => {
+      expect(() => new Database(testDbPath, { readonly: false, readwrite: false, create: false })).toThrow(
+        /pass readonly: true/
+      );
+      expect(() => new Database(testDbPath, { readwrite: false })).toThrow(/pass readonly: true/);
+    });
+
+    test("readwrite without create opens an existing database for writing", () => {
+      const setupDb = new Database(testDbPath);
+      setupDb.close();
+
+      const db = new Database(testDbPath, { readwrite: true, create: false });
+      db.exec("CREATE TABLE test (id INTEGER)");
+      expect(db.isReadonly()).toBe(false);
+      db.close();
+    });
+
+    test("readwrite without create fails for a nonexistent file", () => {
+      expect(() => new Database(testDbPath, { readwrite: true, create: false })).toThrow();
+      expect(existsSync(testDbPath)).toBe(false);
+    });
+
+    test("readonly fails for a nonexistent file", () => {
+      expect(() => new Database(testDbPath, { readonly: true })).toThrow();
+      expect(existsSync(testDbPath)).toBe(false);
     });
 
     test("readonly: true with create: false on existing database", () => {
@@ -214,6 +369,149 @@ describe("SQLite NAPI - Connection Options", () => {
     });
   });
 
+  describe("URI Filenames and VFS", () => {
+    test("opens a URI filename with query parameters", () => {
+      const setup = new Database(testDbPath);
+      setup.exec("CREATE TABLE t (x INTEGER)");
+      setup.run("INSERT INTO t VALUES (1)");
+      setup.close();
+
+      const db = new Database(`file:${testDbPath}?mode=ro`);
+      expect(db.query("SELECT x FROM t").get()).toEqual({ x: 1 });
+      expect(() => db.run("INSERT INTO t VALUES (2)")).toThrow();
+      db.close();
+    });
+
+    test("opens a shared-cache in-memory URI", () => {
+      const a = new Database("file:uri_shared?mode=memory&cache=shared");
+      const b = new Database("file:uri_shared?mode=memory&cache=shared");
+      a.exec("CREATE TABLE t (x INTEGER)");
+
+      expect(b.tableExists("t")).toBe(true);
+      a.close();
+      b.close();
+    });
+
+    test("opens with a named VFS", () => {
+      const db = new Database("vfs-test.db", { vfs: "memdb" });
+      db.exec("CREATE TABLE t (x INTEGER)");
+      db.run("INSERT INTO t VALUES (1)");
+
+      expect(db.query("SELECT COUNT(*) AS n FROM t").get()).toEqual({ n: 1 });
+      db.close();
+    });
+
+    test("throws for an unknown VFS", () => {
+      expect(() => new Database(testDbPath, { vfs: "no-such-vfs" })).toThrow();
+    });
+  });
+
+  describe("cloneReadonly", () => {
+    test("reads the same file through a separate connection", () => {
+      const db = new Database(testDbPath);
+      db.exec("CREATE TABLE t (x INTEGER)");
+      db.run("INSERT INTO t VALUES (1)");
+
+      const reader = db.cloneReadonly();
+      expect(reader.filename()).toBe(testDbPath);
+      expect(reader.query("SELECT x FROM t").all()).toEqual([{ x: 1 }]);
+
+      db.run("INSERT INTO t VALUES (2)");
+      expect(reader.query("SELECT COUNT(*) AS n FROM t").get()).toEqual({ n: 2 });
+
+      reader.close();
+      db.close();
+    });
+
+    test("rejects writes on the clone", () => {
+      const db = new Database(testDbPath);
+      db.exec("CREATE TABLE t (x INTEGER)");
+
+      const reader = db.cloneReadonly();
+      expect(() => reader.run("INSERT INTO t VALUES (1)")).toThrow();
+
+      reader.close();
+      db.close();
+    });
+
+    test("throws for in-memory databases", () => {
+      const db = new Database(":memory:");
+      expect(() => db.cloneReadonly()).toThrow();
+    });
+  });
+
+  describe("runWithRetry", () => {
+    test("runs like run when the database is free", () => {
+      const db = new Database(testDbPath);
+      db.exec("CREATE TABLE t (x INTEGER)");
+
+      const result = db.runWithRetry("INSERT INTO t VALUES (?)", [1]);
+      expect(result.changes).toBe(1);
+      expect(result.lastInsertRowid).toBe(1);
+      db.close();
+    });
+
+    test("retries with backoff and then throws SQLITE_BUSY", () => {
+      const writer = new Database(testDbPath);
+      writer.exec("CREATE TABLE t (x INTEGER)");
+      const other = new Database(testDbPath);
+      other.pragma("busy_timeout", 0);
+
+      writer.exec("BEGIN IMMEDIATE");
+      const start = Date.now();
+      let code: string | undefined;
+      try {
+        other.runWithRetry("INSERT INTO t VALUES (1)", [], { maxRetries: 3, backoffMs: 5 });
+      } catch (e: any) {
+        code = e.code;
+      }
+      // 5 + 10 + 20 ms of backoff before giving up
+      expect(Date.now() - start).toBeGreaterThanOrEqual(35);
+      expect(code).toStartWith("SQLITE_BUSY");
+
+      writer.exec("COMMIT");
+      expect(other.runWithRetry("INSERT INTO t VALUES (1)").changes).toBe(1);
+      other.close();
+      writer.close();
+    });
+
+    test("does not retry other errors", () => {
+      const db = new Database(testDbPath);
+      const start = Date.now();
+
+      expect(() =>
+        db.runWithRetry("INSERT INTO missing VALUES (1)", [], { maxRetries: 5, backoffMs: 1000 })
+      ).toThrow(/no such table/);
+      expect(Date.now() - start).toBeLessThan(1000);
+      db.close();
+    });
+
+    test("caps each delay at maxBackoffMs", () => {
+      const writer = new Database(testDbPath);
+      writer.exec("CREATE TABLE t (x INTEGER)");
+      const other = new Database(testDbPath);
+      other.pragma("busy_timeout", 0);
+
+      writer.exec("BEGIN IMMEDIATE");
+      const start = Date.now();
+      expect(() =>
+        other.runWithRetry("INSERT INTO t VALUES (1)", [], { maxRetries: 3, backoffMs: 1000, maxBackoffMs: 10 })
+      ).toThrow(/still busy after 3 retries/);
+      expect(Date.now() - start).toBeLessThan(1000);
+
+      writer.exec("COMMIT");
+      other.close();
+      writer.close();
+    });
+
+    test("rejects more than 100 retries", () => {
+      const db = new Database(testDbPath);
+
+      expect(() => db.runWithRetry("SELECT 1", [], { maxRetries: 101 })).toThrow(/at most 100/);
+      db.close();
+    });
+  });
+
   describe("Database Filename Property", () => {
     test("filename returns correct path for file database", () => {
       const db = new Database(testDbPath);
@@ -228,6 +526,30 @@ describe("SQLite NAPI - Connection Options", () => {
     });
   });
 
+  describe("maxPageCount Option", () => {
+    test("caps the database size and fails writes past it", () => {
+      const db = new Database(":memory:", { maxPageCount: 10 });
+      db.exec("CREATE TABLE blobs (data BLOB)");
+
+      expect(db.query("PRAGMA max_page_count").get()).toEqual({ max_page_count: 10 });
+      expect(() => db.run("INSERT INTO blobs VALUES (randomblob(100000))")).toThrow(/full/);
+      db.close();
+    });
+
+    test("setMaxPageCount returns the effective limit", () => {
+      const db = new Database(":memory:");
+      db.exec("CREATE TABLE blobs (data BLOB)");
+      db.run("INSERT INTO blobs VALUES (randomblob(20000))");
+      const pages = db.query("PRAGMA page_count").get().page_count;
+
+      expect(db.setMaxPageCount(1000)).toBe(1000);
+      // The limit never drops below the pages already in use
+      expect(db.setMaxPageCount(1)).toBe(pages);
+      expect(() => db.setMaxPageCount(0)).toThrow(/at least 1/);
+      db.close();
+    });
+  });
+
   describe("Database Close and isClosed", () => {
     test("isClosed returns false for open database", () => {
       const db = new Database(":memory:");
@@ -253,6 +575,35 @@ describe("SQLite NAPI - Connection Options", () => {
       db.close();
       expect(db.isClosed()).toBe(true);
     });
+
+    test("refuses to close while a transaction is open", () => {
+      const db = new Database(":memory:");
+      db.exec("CREATE TABLE test (id INTEGER)");
+      const tx = db.transaction(null);
+      tx.run("INSERT INTO test (id) VALUES (1)");
+
+      expect(() => db.close()).toThrow(/transaction is open/);
+      expect(db.isClosed()).toBe(false);
+
+      tx.commit();
+      db.close();
+      expect(db.isClosed()).toBe(true);
+    });
+
+    test("force rolls back the open transaction and closes", () => {
+      const db = new Database(":memory:");
+      db.exec("CREATE TABLE test (id INTEGER)");
+      db.exec("BEGIN; INSERT INTO test (id) VALUES (1)");
+
+      expect(db.close({ force: true })).toEqual({ rolledBack: true });
+      expect(db.isClosed()).toBe(true);
+      expect(db.inTransaction()).toBe(false);
+    });
+
+    test("reports no rollback when no transaction was open", () => {
+      const db = new Database(":memory:");
+      expect(db.close({ force: true })).toEqual({ rolledBack: false });
+    });
   });
 
   describe("inTransaction Property", () => {
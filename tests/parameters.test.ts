@@ -96,6 +96,18 @@ describe("SQLite NAPI - Parameter Binding", () => {
       expect(rows.length).toBe(1);
     });
 
+    test("numbered parameters reuse a single array slot", () => {
+      const values = db.query("SELECT ?1, ?1, ?2").values([10, 20]);
+
+      expect((values as any[])[0]).toEqual([10, 10, 20]);
+    });
+
+    test("plain ? after a numbered parameter takes the next index", () => {
+      const values = db.query("SELECT ?2, ?, ?1").values(["a", "b", "c"]);
+
+      expect((values as any[])[0]).toEqual(["b", "c", "a"]);
+    });
+
     test("numbered parameters with gaps", () => {
       db.run("INSERT INTO users (name, age) VALUES (?2, ?3)", [
         "unused",
@@ -152,6 +164,61 @@ describe("SQLite NAPI - Parameter Binding", () => {
 
       expect((row as any).name).toBe("Alice");
     });
+
+    test("undefined named values are left unbound while null binds NULL", () => {
+      db.run("INSERT INTO users (name, age, email) VALUES ($name, $age, $email)", {
+        $name: "Alice",
+        $age: undefined,
+        $email: null,
+        $unused: undefined,
+      });
+
+      const row = db.query("SELECT age, email FROM users WHERE name = 'Alice'").get() as any;
+      expect(row.age).toBeNull();
+      expect(row.email).toBeNull();
+    });
+
+    test("named values keep BigInt and Buffer types", () => {
+      const row = db
+        .query("SELECT typeof($big) AS big, typeof($bytes) AS bytes")
+        .get({ $big: BigInt("9007199254740993"), $bytes: Buffer.from([1, 2]) }) as any;
+
+      expect(row.big).toBe("integer");
+      expect(row.bytes).toBe("blob");
+    });
+  });
+
+  describe("Named Parameters (plain keys)", () => {
+    test("plain keys match whichever prefix the SQL uses", () => {
+      db.run("INSERT INTO users (name, age) VALUES (?, ?)", ["Alice", 30]);
+      const params = { name: "Alice", age: 30 };
+
+      for (const sigil of ["$", ":", "@"]) {
+        const row = db
+          .query(`SELECT name FROM users WHERE name = ${sigil}name AND age = ${sigil}age`)
+          .get(params) as any;
+        expect(row.name).toBe("Alice");
+      }
+    });
+
+    test("plain keys work with db.run and transactions", () => {
+      db.run("INSERT INTO users (name, age) VALUES (:name, @age)", { name: "Bob", age: 25 });
+      const tx = db.transaction(null);
+      tx.run("UPDATE users SET age = :age WHERE name = :name", { name: "Bob", age: 26 });
+      tx.commit();
+
+      const row = db.query("SELECT age FROM users WHERE name = 'Bob'").get() as any;
+      expect(row.age).toBe(26);
+    });
+
+    test("throws when a key matches no placeholder", () => {
+      expect(() =>
+        db.query("SELECT * FROM users WHERE name = :name").get({ name: "Alice", nickname: "Al" })
+      ).toThrow(/nickname/);
+      expect(() =>
+        db.query("SELECT * FROM users WHERE name = :name").get({ $name: "Alice" })
+      ).toThrow(/\$name/);
+    });
   });
 
   describe("Named Parameters (:name format)", () => {
@@ -251,6 +318,44 @@ describe("SQLite NAPI - Parameter Binding", () => {
       expect((row as any).price).toBeCloseTo(19.99);
     });
 
+    test("rejects NaN and Infinity instead of storing NULL", () => {
+      db.exec("CREATE TABLE products (id INTEGER PRIMARY KEY, price REAL)");
+
+      expect(() => db.run("INSERT INTO products (price) VALUES (?)", [Infinity])).toThrow(
+        /Infinity/
+      );
+      expect(() => db.run("INSERT INTO products (price) VALUES (?)", [-Infinity])).toThrow(
+        /-Infinity/
+      );
+      expect(() => db.query("SELECT ? AS v").get([NaN])).toThrow(/NaN/);
+      expect(() => db.run("INSERT INTO products (price) VALUES (?)", [new Date("nope")])).toThrow(
+        /invalid Date/
+      );
+
+      const count = db.query("SELECT COUNT(*) AS n FROM products").get() as any;
+      expect(count.n).toBe(0);
+    });
+
+    test("Date parameter is stored as integer milliseconds", () => {
+      db.exec("CREATE TABLE events (id INTEGER PRIMARY KEY, at INTEGER)");
+      const when = new Date("2024-01-15T10:30:00.123Z");
+      db.run("INSERT INTO events (at) VALUES (?)", [when]);
+
+      const row = db
+        .query(
+          "SELECT at, typeof(at) AS type, strftime('%Y-%m-%d %H:%M:%S', at / 1000, 'unixepoch') AS text FROM events"
+        )
+        .get() as any;
+
+      expect(row.type).toBe("integer");
+      expect(row.at).toBe(when.getTime());
+      expect(new Date(row.at).toISOString()).toBe(when.toISOString());
+      expect(row.text).toBe("2024-01-15 10:30:00");
+
+      const match = db.query("SELECT COUNT(*) AS n FROM events WHERE at = ?").get([when]) as any;
+      expect(match.n).toBe(1);
+    });
+
     test("empty string parameter", () => {
       db.run("INSERT INTO users (name) VALUES (?)", [""]);
 
@@ -240,4 +240,72 @@ describe("SQLite NAPI - Pragma Methods", () => {
       expect(result).toBeDefined();
     });
   });
+
+  describe("setLimit / getLimit", () => {
+    test("returns the previous value", () => {
+      const original = db.getLimit("EXPR_DEPTH");
+      expect(db.setLimit("EXPR_DEPTH", 10)).toBe(original);
+      expect(db.getLimit("EXPR_DEPTH")).toBe(10);
+    });
+
+    test("enforces the limit on new statements", () => {
+      db.setLimit("SQL_LENGTH", 20);
+      expect(() => db.query("SELECT 1 + 1 + 1 + 1 + 1 + 1 + 1").get()).toThrow();
+    });
+
+    test("accepts the SQLITE_LIMIT_ prefix and any case", () => {
+      db.setLimit("sqlite_limit_attached", 0);
+      expect(db.getLimit("ATTACHED")).toBe(0);
+    });
+
+    test("rejects unknown categories", () => {
+      expect(() => db.setLimit("ROWS", 1)).toThrow(/Unknown limit category/);
+    });
+  });
+
+  describe("cache spill", () => {
+    test("is enabled by default and can be toggled", () => {
+      expect(db.getCacheSpill()).toBe(true);
+
+      db.setCacheSpill(false);
+      expect(db.getCacheSpill()).toBe(false);
+      expect(db.pragma("cache_spill")).toBe(0);
+
+      db.setCacheSpill(true);
+      expect(db.getCacheSpill()).toBe(true);
+    });
+
+    test("can be disabled when opening", () => {
+      const other = new Database(":memory:", { cacheSpill: false });
+      expect(other.getCacheSpill()).toBe(false);
+      other.close();
+    });
+  });
+
+  describe("typed pragma setters", () => {
+    test("setSynchronous validates the mode and returns the new one", () => {
+      expect(db.setSynchronous("FULL")).toBe("full");
+      expect(db.pragma("synchronous")).toBe(2);
+      expect(db.setSynchronous("off")).toBe("off");
+      expect(() => db.setSynchronous("fast")).toThrow(/Invalid synchronous mode 'fast'/);
+    });
+
+    test("setJournalMode returns the mode SQLite switched to", () => {
+      expect(db.setJournalMode("OFF")).toBe("off");
+      // In-memory databases can't use WAL and stay in memory mode
+      expect(db.setJournalMode("wal")).toBe("memory");
+      expect(() => db.setJournalMode("wal; DROP TABLE test")).toThrow(/Invalid journal mode/);
+    });
+
+    test("setCacheSize accepts pages or a negative size in KiB", () => {
+      expect(db.setCacheSize(500)).toBe(500);
+      expect(db.setCacheSize(-8000)).toBe(-8000);
+    });
+
+    test("setForeignKeys toggles enforcement", () => {
+      expect(db.setForeignKeys(false)).toBe(false);
+      expect(db.setForeignKeys(true)).toBe(true);
+      expect(db.pragma("foreign_keys")).toBe(1);
+    });
+  });
 });
@@ -0,0 +1,197 @@
+import { expect, test, describe, beforeEach } from "bun:test";
+import { Database } from "../index";
+
+describe("SQLite NAPI - Query Options", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec(
+      "CREATE TABLE items (id INTEGER PRIMARY KEY, active INTEGER, created INTEGER, updated TEXT, meta TEXT)"
+    );
+    db.run("INSERT INTO items (active, created, updated, meta) VALUES (?, ?, ?, ?)", [
+      1,
+      1700000000000,
+      "2024-01-15 10:30:00",
+      '{"tags":["a","b"]}',
+    ]);
+    db.run("INSERT INTO items (active, created, updated, meta) VALUES (?, ?, ?, ?)", [
+      0,
+      null,
+      null,
+      "not json",
+    ]);
+  });
+
+  describe("columnTypes", () => {
+    test("converts boolean columns", () => {
+      const rows = db
+        .query("SELECT id, active FROM items ORDER BY id")
+        .all([], { columnTypes: { active: "boolean" } });
+
+      expect(rows[0].active).toBe(true);
+      expect(rows[1].active).toBe(false);
+    });
+
+    test("converts millisecond timestamps and date strings to Date", () => {
+      const row = db
+        .query("SELECT created, updated FROM items WHERE id = 1")
+        .get([], { columnTypes: { created: "date", updated: "date" } });
+
+      expect(row.created).toBeInstanceOf(Date);
+      expect(row.created.getTime()).toBe(1700000000000);
+      expect(row.updated).toBeInstanceOf(Date);
+      expect(row.updated.toISOString()).toBe("2024-01-15T10:30:00.000Z");
+    });
+
+    test("converts second-based timestamps with unixepoch", () => {
+      const row = db
+        .query("SELECT 1700000000 AS ts")
+        .get([], { columnTypes: { ts: "unixepoch" } });
+
+      expect(row.ts).toBeInstanceOf(Date);
+      expect(row.ts.getTime()).toBe(1700000000000);
+    });
+
+    test("parses JSON columns and keeps invalid JSON as text", () => {
+      const rows = db
+        .query("SELECT meta FROM items ORDER BY id")
+        .all([], { columnTypes: { meta: "json" } });
+
+      expect(rows[0].meta).toEqual({ tags: ["a", "b"] });
+      expect(rows[1].meta).toBe("not json");
+    });
+
+    test("leaves NULL values untouched", () => {
+      const row = db
+        .query("SELECT created, updated FROM items WHERE id = 2")
+        .get([], { columnTypes: { created: "date", updated: "date" } });
+
+      expect(row.created).toBeNull();
+      expect(row.updated).toBeNull();
+    });
+
+    test("returns null from get() when there are no rows", () => {
+      const row = db
+        .query("SELECT active FROM items WHERE id = 99")
+        .get([], { columnTypes: { active: "boolean" } });
+
+      expect(row).toBeNull();
+    });
+
+    test("rejects unknown column types", () => {
+      expect(() =>
+        db.query("SELECT active FROM items").all([], { columnTypes: { active: "money" } })
+      ).toThrow();
+    });
+  });
+
+  describe("parseJson", () => {
+    test("parses listed JSON columns", () => {
+      const rows = db.query("SELECT id, meta FROM items ORDER BY id").all([], { parseJson: ["meta"] });
+
+      expect(rows[0].meta).toEqual({ tags: ["a", "b"] });
+      expect(rows[0].meta.tags.length).toBe(2);
+    });
+
+    test("falls back to the raw string when parsing fails", () => {
+      const row = db.query("SELECT meta FROM items WHERE id = 2").get([], { parseJson: ["meta"] });
+
+      expect(row.meta).toBe("not json");
+    });
+
+    test("only parses the listed columns", () => {
+      const row = db
+        .query("SELECT meta, meta AS raw FROM items WHERE id = 1")
+        .get([], { parseJson: ["meta"] });
+
+      expect(row.meta).toEqual({ tags: ["a", "b"] });
+      expect(row.raw).toBe('{"tags":["a","b"]}');
+    });
+
+    test("parses JSON arrays and scalars", () => {
+      const row = db
+        .query("SELECT '[1,2,3]' AS list, '42' AS answer")
+        .get([], { parseJson: ["list", "answer"] });
+
+      expect(row.list).toEqual([1, 2, 3]);
+      expect(row.answer).toBe(42);
+    });
+  });
+
+  describe("decimalColumns", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE prices (id INTEGER PRIMARY KEY, amount NUMERIC, note TEXT)");
+      db.exec("INSERT INTO prices (amount, note) VALUES ('19.99', 'a'), (0.1 + 0.2, 'b'), (20, 'c'), (NULL, 'd')");
+    });
+
+    test("returns exact decimal text instead of floats", () => {
+      const rows = db
+        .query("SELECT amount FROM prices ORDER BY id")
+        .all([], { decimalColumns: ["amount"] });
+
+      expect(rows).toEqual([
+        { amount: "19.99" },
+        { amount: "0.30000000000000004" },
+        { amount: "20" },
+        { amount: null },
+      ]);
+      expect(db.query("SELECT amount FROM prices WHERE id = 2").get().amount).toBe(0.30000000000000004);
+    });
+
+    test("matches CAST(... AS TEXT) and leaves other columns alone", () => {
+      const row = db
+        .query("SELECT amount, CAST(amount AS TEXT) AS text, note, 1.5 AS other FROM prices WHERE id = 1")
+        .get([], { decimalColumns: ["amount", "note"] });
+
+      expect(row).toEqual({ amount: "19.99", text: "19.99", note: "a", other: 1.5 });
+    });
+
+    test("can be requested through columnTypes", () => {
+      const row = db.query("SELECT 2.50 AS total").get([], { columnTypes: { total: "decimal" } });
+
+      expect(row.total).toBe("2.5");
+    });
+
+    test("keeps integers beyond 2^53 and REALs that need 17 digits exact", () => {
+      db.exec("INSERT INTO prices (amount, note) VALUES (9007199254740993, 'big'), (0.1 * 3, 'real')");
+      const rows = db
+        .query("SELECT amount FROM prices WHERE note IN ('big', 'real') ORDER BY id")
+        .all([], { decimalColumns: ["amount"] });
+
+      expect(rows).toEqual([{ amount: "9007199254740993" }, { amount: "0.30000000000000004" }]);
+      const row = db.query("SELECT -9223372036854775808 AS min").get([], { decimalColumns: ["min"] });
+      expect(row.min).toBe("-9223372036854775808");
+    });
+  });
+
+  describe("maxRows", () => {
+    let capped: Database;
+
+    beforeEach(() => {
+      capped = new Database(":memory:", { maxRows: 2 });
+      capped.exec("CREATE TABLE t (x INTEGER)");
+      capped.exec("INSERT INTO t VALUES (1), (2), (3)");
+    });
+
+    test("allows results up to the cap", () => {
+      expect(capped.query("SELECT x FROM t LIMIT 2").all()).toEqual([{ x: 1 }, { x: 2 }]);
+      expect(capped.query("SELECT x FROM t LIMIT 2").values()).toEqual([[1], [2]]);
+    });
+
+    test("throws once all(), values() or iter() exceed the cap", () => {
+      expect(() => capped.query("SELECT x FROM t").all()).toThrow(/more than 2 rows/);
+      expect(() => capped.query("SELECT x FROM t").values()).toThrow(/maxRows/);
+      expect(() => capped.query("SELECT x FROM t").iter()).toThrow(/maxRows/);
+    });
+
+    test("doesn't limit single-row lookups", () => {
+      expect(capped.query("SELECT x FROM t ORDER BY x").get()).toEqual({ x: 1 });
+    });
+
+    test("per-call maxRows overrides the connection cap", () => {
+      expect(capped.query("SELECT x FROM t").all([], { maxRows: 10 }).length).toBe(3);
+      expect(() => db.query("SELECT * FROM items").all([], { maxRows: 1 })).toThrow(/more than 1 rows/);
+    });
+  });
+});
@@ -8,6 +8,43 @@ describe("SQLite NAPI - Schema Introspection", () => {
     db = new Database(":memory:");
   });
 
+  // columnMetadata is only compiled in with the `column_metadata` cargo feature
+  const hasColumnMetadata = typeof (Database.prototype as any).columnMetadata === "function";
+
+  describe.skipIf(!hasColumnMetadata)("column_metadata", () => {
+    beforeEach(() => {
+      db.exec(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, email TEXT NOT NULL COLLATE NOCASE, note)"
+      );
+    });
+
+    test("reports collation, NOT NULL and AUTOINCREMENT", () => {
+      expect((db as any).columnMetadata("users", "id")).toEqual({
+        declaredType: "INTEGER",
+        collation: "BINARY",
+        notNull: false,
+        primaryKey: true,
+        autoIncrement: true,
+      });
+      expect((db as any).columnMetadata("users", "email")).toEqual({
+        declaredType: "TEXT",
+        collation: "NOCASE",
+        notNull: true,
+        primaryKey: false,
+        autoIncrement: false,
+      });
+    });
+
+    test("returns a null declared type for untyped columns", () => {
+      expect((db as any).columnMetadata("users", "note").declaredType).toBeNull();
+    });
+
+    test("throws for a missing table or column", () => {
+      expect(() => (db as any).columnMetadata("users", "missing")).toThrow(/No column 'missing'/);
+      expect(() => (db as any).columnMetadata("missing", "id")).toThrow();
+    });
+  });
+
   describe("get_tables", () => {
     test("returns empty array for empty database", () => {
       const tables = db.getTables();
@@ -76,6 +113,51 @@ describe("SQLite NAPI - Schema Introspection", () => {
     });
   });
 
+  describe("primary_key_columns", () => {
+    test("returns a single primary key", () => {
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+      expect(db.primaryKeyColumns("users")).toEqual(["id"]);
+    });
+
+    test("returns composite keys in key order", () => {
+      db.exec("CREATE TABLE memberships (user_id INTEGER, group_id INTEGER, role TEXT, PRIMARY KEY (group_id, user_id)) WITHOUT ROWID");
+      expect(db.primaryKeyColumns("memberships")).toEqual(["group_id", "user_id"]);
+    });
+
+    test("returns empty array without a primary key", () => {
+      db.exec("CREATE TABLE logs (message TEXT)");
+      expect(db.primaryKeyColumns("logs")).toEqual([]);
+    });
+  });
+
+  describe("generated_columns", () => {
+    test("returns virtual and stored generated columns", () => {
+      db.exec(
+        "CREATE TABLE boxes (w INTEGER, h INTEGER, area INTEGER AS (w * h), perimeter INTEGER AS (2 * (w + h)) STORED)"
+      );
+      expect(db.generatedColumns("boxes")).toEqual(["area", "perimeter"]);
+    });
+
+    test("returns empty array when there are none", () => {
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY)");
+      expect(db.generatedColumns("users")).toEqual([]);
+    });
+  });
+
+  describe("is_without_rowid", () => {
+    test("detects WITHOUT ROWID tables", () => {
+      db.exec("CREATE TABLE kv (key TEXT PRIMARY KEY, value TEXT) WITHOUT ROWID");
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+
+      expect(db.isWithoutRowid("kv")).toBe(true);
+      expect(db.isWithoutRowid("users")).toBe(false);
+    });
+
+    test("throws for a missing table", () => {
+      expect(() => db.isWithoutRowid("missing")).toThrow();
+    });
+  });
+
   describe("get_indexes", () => {
     test("returns empty array for table with no indexes", () => {
       db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY)");
@@ -196,4 +278,181 @@ describe("SQLite NAPI - Schema Introspection", () => {
       expect(after.table_count).toBe(2);
     });
   });
+
+  describe("supports_json", () => {
+    test("reports the bundled JSON functions", () => {
+      expect(db.supportsJson()).toBe(true);
+      expect(db.query(`SELECT json_extract('{"a":{"b":2}}', '$.a.b') AS v`).get()).toEqual({ v: 2 });
+    });
+  });
+
+  describe("memory_stats", () => {
+    test("returns connection memory counters", () => {
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY)");
+      db.query("SELECT * FROM users").all();
+
+      const stats = db.memoryStats();
+
+      expect(stats.cache_used).toBeGreaterThan(0);
+      expect(stats.schema_used).toBeGreaterThan(0);
+      expect(stats.stmt_used).toBeGreaterThanOrEqual(0);
+      expect(stats.cache_hit).toBeGreaterThanOrEqual(0);
+      expect(stats.cache_miss).toBeGreaterThanOrEqual(0);
+    });
+  });
+
+  describe("row_counts", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+      db.exec("CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT)");
+      db.exec("CREATE INDEX idx_posts_title ON posts (title)");
+      for (let i = 0; i < 5; i++) db.run("INSERT INTO users (name) VALUES (?)", [`u${i}`]);
+      db.run("INSERT INTO posts (title) VALUES ('hello')");
+    });
+
+    test("counts every table precisely", () => {
+      expect(db.rowCounts({ precise: true })).toEqual({
+        estimated: false,
+        counts: { posts: 1, users: 5 },
+      });
+    });
+
+    test("returns null estimates before ANALYZE", () => {
+      expect(db.rowCounts()).toEqual({
+        estimated: true,
+        counts: { posts: null, users: null },
+      });
+    });
+
+    test("reads estimates from sqlite_stat1 after ANALYZE", () => {
+      db.exec("ANALYZE");
+
+      const result = db.rowCounts();
+      expect(result.estimated).toBe(true);
+      expect(result.counts).toEqual({ posts: 1, users: 5 });
+    });
+  });
+
+  describe("estimateCount", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE posts (id INTEGER PRIMARY KEY, author INTEGER, body TEXT)");
+      db.exec("CREATE INDEX idx_posts_author ON posts (author)");
+      db.exec(
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000) " +
+          "INSERT INTO posts (author, body) SELECT x % 10, 'post' FROM n"
+      );
+    });
+
+    test("sizes full scans by the table", () => {
+      expect(db.estimateCount("SELECT * FROM posts")).toBe(1000);
+      db.exec("ANALYZE");
+      expect(db.estimateCount("SELECT * FROM posts;")).toBe(1000);
+    });
+
+    test("uses index statistics after ANALYZE", () => {
+      expect(db.estimateCount("SELECT * FROM posts WHERE author = ?", [3])).toBe(10);
+      db.exec("ANALYZE");
+      expect(db.estimateCount("SELECT * FROM posts WHERE author = ?", [3])).toBe(100);
+      expect(db.estimateCount("SELECT * FROM posts WHERE id = 5")).toBe(1);
+    });
+
+    test("returns null for plans it can't estimate", () => {
+      expect(
+        db.estimateCount("SELECT * FROM posts WHERE author IN (SELECT author FROM posts WHERE id < 3)")
+      ).toBeNull();
+      expect(db.estimateCount("SELECT * FROM (SELECT author FROM posts GROUP BY author)")).toBeNull();
+    });
+
+    test("reports invalid SQL", () => {
+      expect(() => db.estimateCount("SELECT * FROM missing")).toThrow(/Estimate failed/);
+    });
+  });
+
+  describe("preload", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+      db.exec("CREATE INDEX idx_name ON users (name)");
+      db.exec("CREATE TABLE posts (id INTEGER PRIMARY KEY, body TEXT)");
+      for (let i = 0; i < 500; i++) {
+        db.run("INSERT INTO users (name) VALUES (?)", ["user-" + "x".repeat(50) + i]);
+      }
+    });
+
+    test("touches every page by default", () => {
+      const pages = db.preload();
+      expect(pages).toBeGreaterThan(0);
+      expect(pages).toBeLessThanOrEqual(db.getMetadata().page_count);
+    });
+
+    test("only touches the listed tables", () => {
+      const all = db.preload();
+      const posts = db.preload({ warmTables: ["posts"] });
+      const users = db.preload({ warmTables: ["users"] });
+
+      expect(posts).toBe(1);
+      expect(users).toBeGreaterThan(posts);
+      expect(users).toBeLessThan(all);
+    });
+
+    test("throws for a missing table", () => {
+      expect(() => db.preload({ warmTables: ["missing"] })).toThrow();
+    });
+  });
+
+  describe("autoVacuumIfNeeded", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE blobs (id INTEGER PRIMARY KEY, data BLOB)");
+      db.exec(
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 2000) " +
+          "INSERT INTO blobs (data) SELECT randomblob(200) FROM n"
+      );
+    });
+
+    test("skips VACUUM while the free-page ratio is below the threshold", () => {
+      expect(db.autoVacuumIfNeeded(0.1)).toBe(false);
+      expect(db.pragma("freelist_count")).toBe(0);
+    });
+
+    test("vacuums once enough pages are free", () => {
+      db.exec("DELETE FROM blobs WHERE id > 200");
+      const pages = db.pragma("page_count");
+      expect(db.pragma("freelist_count")).toBeGreaterThan(0);
+
+      expect(db.autoVacuumIfNeeded(0.99)).toBe(false);
+      expect(db.autoVacuumIfNeeded(0.5)).toBe(true);
+      expect(db.pragma("freelist_count")).toBe(0);
+      expect(db.pragma("page_count")).toBeLessThan(pages);
+    });
+
+    test("rejects thresholds outside 0..1", () => {
+      expect(() => db.autoVacuumIfNeeded(1.5)).toThrow(/between 0 and 1/);
+      expect(() => db.autoVacuumIfNeeded(-0.1)).toThrow(/between 0 and 1/);
+    });
+  });
+
+  describe("reindex", () => {
+    beforeEach(() => {
+      db.exec("CREATE TABLE words (w TEXT)");
+      db.exec("CREATE INDEX idx_words_nocase ON words (w COLLATE NOCASE)");
+      db.exec("INSERT INTO words (w) VALUES ('b'), ('A'), ('c')");
+    });
+
+    test("rebuilds every index, or one index, table or collation", () => {
+      expect(() => db.reindex()).not.toThrow();
+      expect(() => db.reindex("idx_words_nocase")).not.toThrow();
+      expect(() => db.reindex("words")).not.toThrow();
+      expect(() => db.reindex("NOCASE")).not.toThrow();
+
+      const rows = db.query("SELECT w FROM words ORDER BY w COLLATE NOCASE").all();
+      expect(rows.map((r: any) => r.w)).toEqual(["A", "b", "c"]);
+    });
+
+    test("throws when the target matches nothing", () => {
+      expect(() => db.reindex("missing")).toThrow(/unable to identify the object to be reindexed/);
+    });
+
+    test("rejects an empty target", () => {
+      expect(() => db.reindex("")).toThrow();
+    });
+  });
 });
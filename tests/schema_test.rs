@@ -2,9 +2,10 @@
 //! Run with: cargo test --test schema_test
 
 use sqlite_napi::{
-    check_sql_expression, get_autoincrement_info, get_sqlite_functions, is_sql_expression,
-    validate_column_definition, validate_create_table, AutoincrementInfo, ColumnValidation,
-    ExpressionCheck, SchemaValidation, SqliteType, TypeMapping,
+    build_create_table, check_sql_expression, column_affinity, get_autoincrement_info,
+    get_sqlite_functions, is_sql_expression, parse_migration_filename, split_sql_statements,
+    validate_column_definition, validate_create_table, AutoincrementInfo, ColumnDef,
+    ColumnValidation, ExpressionCheck, SchemaValidation, SqliteType, TypeMapping,
 };
 
 // ============================================================================
@@ -326,6 +327,26 @@ mod sqlite_type_tests {
         assert_eq!(SqliteType::parse_type("CLOB"), Some(SqliteType::Text));
     }
 
+    #[test]
+    fn test_from_str_parameterized_types() {
+        assert_eq!(
+            SqliteType::parse_type("VARCHAR(255)"),
+            Some(SqliteType::Text)
+        );
+        assert_eq!(
+            SqliteType::parse_type("DECIMAL(10,2)"),
+            Some(SqliteType::Real)
+        );
+        assert_eq!(SqliteType::parse_type("CHAR(1)"), Some(SqliteType::Text));
+        assert_eq!(SqliteType::parse_type("NUMERIC(8)"), Some(SqliteType::Real));
+        assert_eq!(
+            SqliteType::parse_type("decimal (10, 2)"),
+            Some(SqliteType::Real)
+        );
+        assert_eq!(SqliteType::parse_type("VARCHAR(255"), None);
+        assert_eq!(SqliteType::parse_type("NOTATYPE(1)"), None);
+    }
+
     #[test]
     fn test_from_str_invalid() {
         assert_eq!(SqliteType::parse_type("INVALID"), None);
@@ -519,6 +540,51 @@ mod validate_column_definition_tests {
         assert!(result.issues.is_empty());
     }
 
+    #[test]
+    fn test_parameterized_type_is_valid() {
+        for column_type in ["VARCHAR(255)", "DECIMAL(10,2)", "CHAR(1)", "NUMERIC(8)"] {
+            let result = validate_column_definition(
+                "code".to_string(),
+                column_type.to_string(),
+                false,
+                false,
+                false,
+                None,
+            );
+            assert!(result.valid, "{}: {:?}", column_type, result.issues);
+        }
+    }
+
+    #[test]
+    fn test_reserved_word_column_name_warns() {
+        let result = validate_column_definition(
+            "order".to_string(),
+            "INTEGER".to_string(),
+            false,
+            false,
+            false,
+            None,
+        );
+        assert!(result.valid);
+        assert_eq!(
+            result.warnings,
+            vec![
+                "Column name 'order' is a reserved SQLite keyword; quote it as \"order\""
+                    .to_string()
+            ]
+        );
+
+        let result = validate_column_definition(
+            "key".to_string(),
+            "TEXT".to_string(),
+            false,
+            false,
+            false,
+            None,
+        );
+        assert!(result.warnings.is_empty());
+    }
+
     #[test]
     fn test_empty_column_name() {
         let result = validate_column_definition(
@@ -767,6 +833,63 @@ mod validate_create_table_tests {
         let result = validate_create_table(sql.to_string());
         assert!(result.valid);
     }
+
+    #[test]
+    fn test_reserved_word_names_warn() {
+        let sql = "CREATE TABLE IF NOT EXISTS main.group (id INTEGER PRIMARY KEY, order INTEGER, \"where\" TEXT, UNIQUE (id))";
+        let result = validate_create_table(sql.to_string());
+        assert!(result.valid);
+        assert_eq!(
+            result.warnings,
+            vec![
+                "Table name 'group' is a reserved SQLite keyword; quote it as \"group\""
+                    .to_string(),
+                "Column name 'order' is a reserved SQLite keyword; quote it as \"order\""
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_with_unknown_column_warns() {
+        let sql =
+            "CREATE TABLE orders (id INTEGER PRIMARY KEY, status TEXT CHECK(stauts IN ('a','b')))";
+        let result = validate_create_table(sql.to_string());
+        assert!(result.valid);
+        assert!(result
+            .warnings
+            .contains(&"CHECK constraint references unknown column 'stauts'".to_string()));
+    }
+
+    #[test]
+    fn test_check_with_known_columns_has_no_warning() {
+        let sql = "CREATE TABLE events (
+            id INTEGER PRIMARY KEY,
+            starts_at TEXT,
+            ends_at TEXT,
+            kind TEXT CHECK(lower(kind) IN ('a', 'b') AND kind IS NOT NULL),
+            price REAL CHECK(CAST(price AS REAL) >= 0.5),
+            CONSTRAINT valid_range CHECK (events.ends_at > \"starts_at\" COLLATE NOCASE)
+        )";
+        let result = validate_create_table(sql.to_string());
+        assert!(result.valid);
+        assert!(
+            result.warnings.iter().all(|w| !w.contains("CHECK")),
+            "{:?}",
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn test_check_with_unbalanced_parentheses_warns() {
+        let sql = "CREATE TABLE t (id INTEGER PRIMARY KEY, n INTEGER CHECK((n > 0), name TEXT)";
+        let result = validate_create_table(sql.to_string());
+        assert!(result.valid);
+        assert_eq!(
+            result.warnings,
+            vec!["CHECK constraint has unbalanced parentheses".to_string()]
+        );
+    }
 }
 
 // ============================================================================
@@ -880,6 +1003,7 @@ mod column_validation_tests {
         let validation = ColumnValidation {
             valid: true,
             issues: vec![],
+            warnings: vec![],
         };
         assert!(validation.valid);
         assert!(validation.issues.is_empty());
@@ -890,8 +1014,239 @@ mod column_validation_tests {
         let validation = ColumnValidation {
             valid: false,
             issues: vec!["Column name cannot be empty".to_string()],
+            warnings: vec![],
         };
         assert!(!validation.valid);
         assert!(!validation.issues.is_empty());
     }
 }
+
+// ============================================================================
+// build_create_table tests
+// ============================================================================
+
+mod build_create_table_tests {
+    use super::*;
+
+    fn column(name: &str, column_type: &str) -> ColumnDef {
+        ColumnDef {
+            name: name.to_string(),
+            column_type: column_type.to_string(),
+            primary_key: None,
+            not_null: None,
+            unique: None,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_generated_sql_passes_validation() {
+        let mut id = column("id", "INTEGER");
+        id.primary_key = Some(true);
+        let mut email = column("email", "TEXT");
+        email.not_null = Some(true);
+        email.unique = Some(true);
+
+        let sql = build_create_table("users".to_string(), vec![id, email], None).unwrap();
+        let validation = validate_create_table(sql.clone());
+        assert!(validation.valid, "{:?}", validation.issues);
+        assert!(validation.warnings.is_empty());
+        assert!(sql.contains("\"email\" TEXT NOT NULL UNIQUE"));
+    }
+
+    #[test]
+    fn test_expression_defaults_are_not_quoted() {
+        let mut created = column("created_at", "TEXT");
+        created.default_value = Some("CURRENT_TIMESTAMP".to_string());
+        let mut label = column("label", "TEXT");
+        label.default_value = Some("CURRENT_TIMESTAMP is a keyword".to_string());
+
+        let sql = build_create_table("t".to_string(), vec![created, label], None).unwrap();
+        assert!(sql.contains("DEFAULT CURRENT_TIMESTAMP,"));
+        assert!(sql.contains("DEFAULT 'CURRENT_TIMESTAMP is a keyword'"));
+    }
+
+    #[test]
+    fn test_invalid_column_is_rejected() {
+        let result = build_create_table("t".to_string(), vec![column("bad name", "TEXT")], None);
+        assert!(result.is_err());
+    }
+}
+
+// ============================================================================
+// column_affinity tests
+// ============================================================================
+
+mod column_affinity_tests {
+    use super::*;
+
+    fn affinity(declared_type: &str) -> String {
+        column_affinity(declared_type.to_string())
+    }
+
+    #[test]
+    fn test_affinity_examples_from_sqlite_docs() {
+        for t in ["INT", "INTEGER", "TINYINT", "UNSIGNED BIG INT", "INT8"] {
+            assert_eq!(affinity(t), "INTEGER", "{}", t);
+        }
+        for t in [
+            "CHARACTER(20)",
+            "VARCHAR(255)",
+            "NATIVE CHARACTER(70)",
+            "TEXT",
+            "CLOB",
+        ] {
+            assert_eq!(affinity(t), "TEXT", "{}", t);
+        }
+        for t in ["BLOB", "", "  "] {
+            assert_eq!(affinity(t), "BLOB", "{:?}", t);
+        }
+        for t in ["REAL", "DOUBLE", "DOUBLE PRECISION", "FLOAT"] {
+            assert_eq!(affinity(t), "REAL", "{}", t);
+        }
+        for t in ["NUMERIC", "DECIMAL(10,2)", "BOOLEAN", "DATE", "DATETIME"] {
+            assert_eq!(affinity(t), "NUMERIC", "{}", t);
+        }
+    }
+
+    #[test]
+    fn test_affinity_rules_apply_in_order() {
+        // "INT" wins over everything else, so "POINT" gets INTEGER affinity
+        assert_eq!(affinity("FLOATING POINT"), "INTEGER");
+        // "CHAR" is checked before "BLOB"
+        assert_eq!(affinity("CHARBLOB"), "TEXT");
+        // "STRING" matches no rule
+        assert_eq!(affinity("string"), "NUMERIC");
+    }
+}
+
+// ============================================================================
+// SQL statement splitting
+// ============================================================================
+
+mod split_statements_tests {
+    use super::*;
+
+    #[test]
+    fn test_split_simple_statements() {
+        assert_eq!(
+            split_sql_statements("CREATE TABLE t (x); INSERT INTO t VALUES (1);\nSELECT * FROM t"),
+            vec![
+                "CREATE TABLE t (x);",
+                "INSERT INTO t VALUES (1);",
+                "SELECT * FROM t"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_keeps_quoted_semicolons() {
+        assert_eq!(
+            split_sql_statements(
+                "INSERT INTO t VALUES ('a;b', \"c;d\"); SELECT [e;f], `g;h` FROM t;"
+            ),
+            vec![
+                "INSERT INTO t VALUES ('a;b', \"c;d\");",
+                "SELECT [e;f], `g;h` FROM t;"
+            ]
+        );
+        assert_eq!(
+            split_sql_statements("SELECT 'it''s; fine'; SELECT 2;"),
+            vec!["SELECT 'it''s; fine';", "SELECT 2;"]
+        );
+    }
+
+    #[test]
+    fn test_split_keeps_trigger_bodies_together() {
+        let trigger = "CREATE TRIGGER log_insert AFTER INSERT ON t BEGIN\n  \
+                       INSERT INTO log VALUES (1);\n  UPDATE t SET x = 2;\nEND;";
+        let sql = format!(
+            "CREATE TABLE t (x);\n{}\nINSERT INTO t VALUES (1);",
+            trigger
+        );
+        assert_eq!(
+            split_sql_statements(&sql),
+            vec!["CREATE TABLE t (x);", trigger, "INSERT INTO t VALUES (1);"]
+        );
+        let temp = "CREATE TEMP TRIGGER IF NOT EXISTS tr BEFORE DELETE ON t BEGIN SELECT 1; END;";
+        assert_eq!(split_sql_statements(temp), vec![temp]);
+    }
+
+    #[test]
+    fn test_split_handles_comments() {
+        assert_eq!(
+            split_sql_statements(
+                "-- seed data; first\nSELECT 1; /* block; comment */ SELECT 2; -- trailing;"
+            ),
+            vec![
+                "-- seed data; first\nSELECT 1;",
+                "/* block; comment */ SELECT 2;"
+            ]
+        );
+        assert_eq!(
+            split_sql_statements("SELECT 1 -- not; the end\n + 1;"),
+            vec!["SELECT 1 -- not; the end\n + 1;"]
+        );
+    }
+
+    #[test]
+    fn test_split_drops_empty_statements() {
+        assert!(split_sql_statements("").is_empty());
+        assert!(split_sql_statements("  ;; -- only a comment\n /* and another */ ;").is_empty());
+        assert_eq!(split_sql_statements(";SELECT 1;;"), vec!["SELECT 1;"]);
+    }
+}
+
+// ============================================================================
+// parse_migration_filename Tests
+// ============================================================================
+
+mod parse_migration_filename_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_and_description() {
+        assert_eq!(
+            parse_migration_filename("0003_add_users.sql").unwrap(),
+            (3, Some("add users".to_string()))
+        );
+        assert_eq!(
+            parse_migration_filename("12-create-posts.SQL").unwrap(),
+            (12, Some("create-posts".to_string()))
+        );
+        assert_eq!(parse_migration_filename("7.sql").unwrap(), (7, None));
+    }
+
+    #[test]
+    fn test_parse_ignores_directories() {
+        assert_eq!(
+            parse_migration_filename("migrations/0001_init.sql").unwrap(),
+            (1, Some("init".to_string()))
+        );
+        assert_eq!(
+            parse_migration_filename("C:\\app\\migrations\\0002_seed.sql").unwrap(),
+            (2, Some("seed".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_names() {
+        for name in [
+            "add_users.sql",
+            "0003_add_users.txt",
+            "0003_add_users",
+            "0003add.sql",
+            "0003_.sql",
+            "0000_init.sql",
+            "99999999999_big.sql",
+        ] {
+            let err = parse_migration_filename(name).unwrap_err();
+            assert!(
+                err.reason.contains("Invalid migration filename"),
+                "{}: {}",
+                name,
+                err.reason
+            );
+        }
+    }
+}
@@ -1,5 +1,8 @@
 import { describe, expect, test } from "bun:test";
 import { Database } from "../index";
+import { readFileSync, unlinkSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
 
 describe("SQLite NAPI - Binary Serialization", () => {
   describe("serializeBinary", () => {
@@ -162,4 +165,91 @@ describe("SQLite NAPI - Binary Serialization", () => {
       expect(row?.null_val).toBeNull();
     });
   });
+
+  describe("serializeToFile", () => {
+    const tmpPath = () => join(tmpdir(), `sqlite-napi-serialize-${Date.now()}-${Math.random()}.db`);
+
+    test("writes the same bytes as serializeBinary", () => {
+      const db = new Database(":memory:");
+      db.run("CREATE TABLE t (id INTEGER PRIMARY KEY, v TEXT)");
+      db.run("INSERT INTO t (v) VALUES (?)", ["a"]);
+      const path = tmpPath();
+
+      const bytes = db.serializeToFile(path);
+
+      expect(bytes).toBe(readFileSync(path).length);
+      expect(readFileSync(path).equals(db.serializeBinary())).toBe(true);
+      unlinkSync(path);
+    });
+
+    test("writes a deserialized database without copying", () => {
+      const source = new Database(":memory:");
+      source.run("CREATE TABLE t (v TEXT)");
+      source.run("INSERT INTO t (v) VALUES (?)", ["kept"]);
+      const db = new Database(":memory:");
+      db.deserializeBinary(source.serializeBinary());
+      const path = tmpPath();
+
+      db.serializeToFile(path);
+
+      const restored = new Database(path);
+      expect(restored.query("SELECT v FROM t").get().v).toBe("kept");
+      restored.close();
+      unlinkSync(path);
+    });
+
+    test("throws for an unwritable path", () => {
+      const db = new Database(":memory:");
+
+      expect(() => db.serializeToFile(join(tmpdir(), "missing-dir", "x", "out.db"))).toThrow(/Failed to write/);
+    });
+  });
+
+  describe("openFromBuffer", () => {
+    const snapshot = () => {
+      const source = new Database(":memory:");
+      source.run("CREATE TABLE t (v TEXT)");
+      source.run("INSERT INTO t (v) VALUES (?)", ["snap"]);
+      return source.serializeBinary();
+    };
+
+    test("opens a new connection from serialized bytes", () => {
+      const db = Database.openFromBuffer(snapshot(), false);
+
+      expect(db.query("SELECT v FROM t").get().v).toBe("snap");
+      db.run("INSERT INTO t (v) VALUES (?)", ["more"]);
+      expect(db.query("SELECT COUNT(*) AS n FROM t").get().n).toBe(2);
+    });
+
+    test("rejects writes when read-only", () => {
+      const db = Database.openFromBuffer(snapshot(), true);
+
+      expect(() => db.run("INSERT INTO t (v) VALUES ('x')")).toThrow(/readonly/);
+      expect(db.query("SELECT COUNT(*) AS n FROM t").get().n).toBe(1);
+    });
+
+    test("opens a snapshot of a WAL-mode file database", () => {
+      const path = join(tmpdir(), `sqlite-napi-wal-${Date.now()}-${Math.random()}.db`);
+      const source = new Database(path);
+      source.run("CREATE TABLE t (v TEXT)");
+      source.run("INSERT INTO t (v) VALUES ('wal')");
+
+      const db = Database.openFromBuffer(source.serializeBinary(), true);
+
+      expect(db.query("SELECT v FROM t").get().v).toBe("wal");
+      source.close();
+      unlinkSync(path);
+    });
+
+    test("rejects data without the SQLite header", () => {
+      expect(() => Database.openFromBuffer(Buffer.from("not a database"), true)).toThrow(/SQLite format 3 header/);
+    });
+
+    test("rejects corrupt pages after a valid header", () => {
+      const data = snapshot();
+      data.fill(0xab, 100);
+
+      expect(() => Database.openFromBuffer(data, true)).toThrow(/malformed/);
+    });
+  });
 });
@@ -0,0 +1,68 @@
+import { expect, test, describe, beforeEach, afterEach } from "bun:test";
+import { Database } from "../index";
+import { existsSync, mkdirSync, rmSync } from "fs";
+import { tmpdir } from "os";
+import { join } from "path";
+
+// Snapshots are only compiled in with the `snapshot` cargo feature
+const hasSnapshots = typeof (Database.prototype as any).snapshot === "function";
+
+describe.skipIf(!hasSnapshots)("SQLite NAPI - WAL Snapshots", () => {
+  const testDir = join(tmpdir(), "sqlite-napi-snapshot-tests");
+  let dbPath: string;
+  let writer: any;
+  let reader: any;
+
+  beforeEach(() => {
+    if (!existsSync(testDir)) {
+      mkdirSync(testDir, { recursive: true });
+    }
+    dbPath = join(testDir, `snapshot-${Date.now()}.db`);
+    writer = new Database(dbPath);
+    writer.exec("CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT)");
+    writer.run("INSERT INTO events (kind) VALUES (?)", ["first"]);
+    writer.exec("PRAGMA wal_autocheckpoint = 0");
+    reader = new Database(dbPath);
+  });
+
+  afterEach(() => {
+    for (const suffix of ["", "-wal", "-shm"]) {
+      rmSync(dbPath + suffix, { force: true });
+    }
+  });
+
+  test("another connection reads exactly the snapshot's state", () => {
+    const snapshot = writer.snapshot();
+    writer.run("INSERT INTO events (kind) VALUES (?)", ["second"]);
+
+    reader.openSnapshot(snapshot);
+    expect(reader.inTransaction()).toBe(true);
+    expect(reader.query("SELECT COUNT(*) AS n FROM events").get().n).toBe(1);
+    reader.exec("COMMIT");
+
+    expect(reader.query("SELECT COUNT(*) AS n FROM events").get().n).toBe(2);
+  });
+
+  test("snapshots compare by age", () => {
+    const older = writer.snapshot();
+    writer.run("INSERT INTO events (kind) VALUES (?)", ["second"]);
+    const newer = writer.snapshot();
+
+    expect(older.isOlderThan(newer)).toBe(true);
+    expect(newer.isOlderThan(older)).toBe(false);
+  });
+
+  test("openSnapshot refuses to run inside an open transaction", () => {
+    const snapshot = writer.snapshot();
+    reader.exec("BEGIN");
+
+    expect(() => reader.openSnapshot(snapshot)).toThrow(/another transaction/);
+    reader.exec("ROLLBACK");
+  });
+
+  test("snapshot requires a WAL database", () => {
+    const memory: any = new Database(":memory:");
+
+    expect(() => memory.snapshot()).toThrow(/WAL/);
+  });
+});
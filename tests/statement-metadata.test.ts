@@ -99,6 +99,125 @@ describe("SQLite NAPI - Statement Metadata", () => {
     });
   });
 
+  describe("statement.columnCount", () => {
+    test("counts SELECT columns without executing", () => {
+      expect(db.query("SELECT id, name, age FROM users").columnCount()).toBe(3);
+      expect(db.query("SELECT * FROM posts").columnCount()).toBe(3);
+    });
+
+    test("is 0 for statements that return no data", () => {
+      expect(db.query("INSERT INTO posts (title) VALUES (?)").columnCount()).toBe(0);
+    });
+
+    test("throws for invalid SQL", () => {
+      expect(() => db.query("SELECT * FROM missing").columnCount()).toThrow();
+    });
+  });
+
+  describe("statement.kind", () => {
+    test("classifies statements by their leading keyword", () => {
+      expect(db.query("SELECT * FROM users").kind()).toBe("select");
+      expect(db.query("VALUES (1), (2)").kind()).toBe("select");
+      expect(db.query("INSERT INTO posts (title) VALUES (?)").kind()).toBe("insert");
+      expect(db.query("REPLACE INTO posts (id, title) VALUES (1, 'a')").kind()).toBe("insert");
+      expect(db.query("UPDATE users SET age = 1").kind()).toBe("update");
+      expect(db.query("DELETE FROM users").kind()).toBe("delete");
+      expect(db.query("CREATE INDEX idx_age ON users (age)").kind()).toBe("ddl");
+      expect(db.query("DROP TABLE posts").kind()).toBe("ddl");
+      expect(db.query("ALTER TABLE users ADD COLUMN bio TEXT").kind()).toBe("ddl");
+      expect(db.query("PRAGMA table_info(users)").kind()).toBe("pragma");
+      expect(db.query("BEGIN").kind()).toBe("other");
+    });
+
+    test("skips leading comments", () => {
+      expect(db.query("-- fetch users\n/* all of them */ SELECT * FROM users").kind()).toBe("select");
+    });
+
+    test("classifies WITH statements by what follows the CTEs", () => {
+      expect(db.query("WITH adults AS (SELECT * FROM users WHERE age >= 18) SELECT * FROM adults").kind()).toBe(
+        "select"
+      );
+      expect(
+        db
+          .query("WITH stale AS (SELECT id FROM users WHERE age > 90) DELETE FROM users WHERE id IN (SELECT id FROM stale)")
+          .kind()
+      ).toBe("delete");
+      expect(
+        db.query("WITH named AS (SELECT name FROM users) INSERT INTO posts (title) SELECT name FROM named").kind()
+      ).toBe("insert");
+    });
+
+    test("throws for invalid SQL", () => {
+      expect(() => db.query("SELECT * FROM missing").kind()).toThrow();
+    });
+  });
+
+  describe("statement.expandedSql", () => {
+    test("substitutes positional parameters as literals", () => {
+      const stmt = db.query("SELECT * FROM users WHERE name = ? AND age > ?");
+      expect(stmt.expandedSql(["O'Brien", 30])).toBe(
+        "SELECT * FROM users WHERE name = 'O''Brien' AND age > 30"
+      );
+    });
+
+    test("substitutes named parameters and blobs", () => {
+      const stmt = db.query("SELECT $data AS data, $missing AS missing");
+      expect(stmt.expandedSql({ data: new Uint8Array([1, 0xab]) })).toBe(
+        "SELECT x'01ab' AS data, NULL AS missing"
+      );
+    });
+
+    test("does not execute the statement", () => {
+      const stmt = db.query("INSERT INTO posts (title) VALUES (?)");
+      expect(stmt.expandedSql(["never"])).toBe("INSERT INTO posts (title) VALUES ('never')");
+      expect(db.query("SELECT COUNT(*) AS n FROM posts").get()).toEqual({ n: 0 });
+    });
+  });
+
+  describe("database.prepare", () => {
+    test("throws immediately for invalid SQL", () => {
+      expect(() => db.prepare("SELECT * FROM missing_table")).toThrow(/missing_table/);
+      expect(() => db.prepare("SELEC 1")).toThrow();
+    });
+
+    test("query stays lazy for the same SQL", () => {
+      const stmt = db.query("SELECT * FROM missing_table");
+
+      expect(stmt.source()).toBe("SELECT * FROM missing_table");
+      expect(() => stmt.all()).toThrow(/missing_table/);
+    });
+
+    test("returns a working statement for valid SQL", () => {
+      const stmt = db.prepare("SELECT name FROM users WHERE age = ?");
+
+      expect(stmt.get([30])).toEqual({ name: "Alice" });
+    });
+  });
+
+  describe("database.prepareCached", () => {
+    test("throws immediately for invalid SQL", () => {
+      expect(() => db.prepareCached("SELECT * FROM missing_table")).toThrow(/missing_table/);
+    });
+
+    test("reuses the statement across calls with fresh bindings", () => {
+      const stmt = db.prepareCached("SELECT name FROM users WHERE age = :age");
+
+      expect(stmt.get({ age: 30 })).toEqual({ name: "Alice" });
+      expect(stmt.get({ age: 999 })).toBeNull();
+      expect(stmt.all({ age: 30 })).toEqual([{ name: "Alice" }]);
+      expect(stmt.status()?.runs).toBe(1);
+    });
+
+    test("runs writes and sees later schema changes", () => {
+      const insert = db.prepareCached("INSERT INTO posts (title) VALUES (?)");
+      insert.run(["a"]);
+      db.exec("ALTER TABLE posts ADD COLUMN body TEXT");
+      insert.run(["b"]);
+
+      expect(db.query("SELECT COUNT(*) AS n FROM posts").get()).toEqual({ n: 2 });
+    });
+  });
+
   describe("statement.source", () => {
     test("returns original SQL string", () => {
       const sql = "SELECT * FROM users WHERE id = ?";
@@ -197,6 +316,48 @@ describe("SQLite NAPI - Statement Metadata", () => {
     });
   });
 
+  describe("statement.status", () => {
+    test("returns null before the statement runs", () => {
+      expect(db.query("SELECT * FROM users").status()).toBeNull();
+    });
+
+    test("reports full table scans", () => {
+      db.run("INSERT INTO users (name, age, email) VALUES (?, ?, ?)", [
+        "Bob",
+        25,
+        "bob@example.com",
+      ]);
+      const stmt = db.query("SELECT * FROM users WHERE email = ?");
+      stmt.get(["bob@example.com"]);
+      const status = stmt.status()!;
+
+      expect(status.fullscanSteps).toBeGreaterThan(0);
+      expect(status.vmSteps).toBeGreaterThan(0);
+      expect(status.runs).toBe(1);
+    });
+
+    test("reports no scan steps for rowid lookups", () => {
+      const stmt = db.query("SELECT * FROM users WHERE id = ?");
+      stmt.all([1]);
+
+      expect(stmt.status()!.fullscanSteps).toBe(0);
+    });
+
+    test("counts sorts", () => {
+      const stmt = db.query("SELECT * FROM users ORDER BY age");
+      stmt.values();
+
+      expect(stmt.status()!.sorts).toBeGreaterThan(0);
+    });
+
+    test("is updated by run()", () => {
+      const stmt = db.query("UPDATE users SET age = age + 1");
+      stmt.run();
+
+      expect(stmt.status()!.fullscanSteps).toBeGreaterThan(0);
+    });
+  });
+
   describe("edge cases", () => {
     test("columns for query with no FROM clause", () => {
       const stmt = db.query("SELECT 1 as one, 2 as two");
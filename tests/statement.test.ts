@@ -58,6 +58,57 @@ describe("SQLite NAPI - Statement Class", () => {
     expect(row).toBeNull();
   });
 
+  test("Statement.getOne returns the only row", () => {
+    const row = db.query("SELECT name FROM users WHERE name = ?").getOne(["Bob"]);
+
+    expect(row).toEqual({ name: "Bob" });
+  });
+
+  test("Statement.getOne throws for zero or several rows", () => {
+    const stmt = db.query("SELECT name FROM users WHERE name LIKE ?");
+
+    expect(() => stmt.getOne(["Nobody"])).toThrow(/returned none/);
+    expect(() => stmt.getOne(["%"])).toThrow(/more than one/);
+  });
+
+  test("Statement.getOptional allows zero rows but not several", () => {
+    const stmt = db.query("SELECT name FROM users WHERE name LIKE ?");
+
+    expect(stmt.getOptional(["Nobody"])).toBeNull();
+    expect(stmt.getOptional(["Char%"])).toEqual({ name: "Charlie" });
+    expect(() => stmt.getOptional(["%"])).toThrow(/more than one/);
+  });
+
+  test("Statement.tryGet tells a missing row apart from a row", () => {
+    const stmt = db.query("SELECT name FROM users WHERE name LIKE ? ORDER BY id");
+
+    expect(stmt.tryGet(["Nobody"])).toEqual({ found: false, row: null });
+    expect(stmt.tryGet(["%"])).toEqual({ found: true, row: { name: "Alice" } });
+  });
+
+  test("Statement.tryGet wraps rows with NULL values and applies options", () => {
+    const stmt = db.query("SELECT NULL AS value, '[1]' AS list");
+
+    expect(stmt.tryGet([], { parseJson: ["list"] })).toEqual({
+      found: true,
+      row: { value: null, list: [1] },
+    });
+  });
+
+  test("Statement.exists reports whether any row matches", () => {
+    const stmt = db.query("SELECT 1 FROM users WHERE name = :name");
+
+    expect(stmt.exists({ name: "Alice" })).toBe(true);
+    expect(stmt.exists({ name: "Nobody" })).toBe(false);
+    expect(db.query("SELECT id FROM users").exists()).toBe(true);
+  });
+
+  test("Statement.exists is true for a row of NULLs", () => {
+    db.run("INSERT INTO users (name, bio) VALUES (?, ?)", ["Dana", null]);
+
+    expect(db.query("SELECT bio FROM users WHERE name = ?").exists(["Dana"])).toBe(true);
+  });
+
   test("Statement.get returns first row only", () => {
     const stmt = db.query("SELECT * FROM users ORDER BY name");
     const row = stmt.get([]);
@@ -127,4 +178,124 @@ describe("SQLite NAPI - Statement Class", () => {
     const countRow = countStmt.get([]);
     expect((countRow as any).count).toBe(6);
   });
+
+  test("Statement.allKeyed returns rows keyed by a column", () => {
+    const users = db.query("SELECT id, name FROM users").allKeyed("id");
+
+    expect(Object.keys(users).sort()).toEqual(["1", "2", "3"]);
+    expect(users["2"]).toEqual({ id: 2, name: "Bob" });
+  });
+
+  test("Statement.allKeyed supports text keys and params", () => {
+    const users = db.query("SELECT name, bio FROM users WHERE id > ?").allKeyed("name", [1]);
+
+    expect(users.Bob.bio).toBe("Bun lover");
+    expect(users.Alice).toBeUndefined();
+  });
+
+  test("Statement.allKeyed throws for missing key column", () => {
+    expect(() => db.query("SELECT name FROM users").allKeyed("id")).toThrow();
+  });
+
+  test("Statement.allKeyed throws for duplicate keys unless lastWins", () => {
+    db.run("INSERT INTO users (name, bio) VALUES (?, ?)", ["Alice", "Second Alice"]);
+    const stmt = db.query("SELECT name, bio FROM users ORDER BY id");
+
+    expect(() => stmt.allKeyed("name")).toThrow();
+    expect(stmt.allKeyed("name", [], true).Alice.bio).toBe("Second Alice");
+  });
+
+  test("Statement.allMapped calls the mapper once with every row", () => {
+    const calls: number[] = [];
+    const names = db.query("SELECT id, name FROM users ORDER BY id").allMapped([], (rows: any[], offset: number) => {
+      calls.push(offset);
+      return rows.map((r) => `${r.id}:${r.name}`);
+    });
+
+    expect(names).toEqual(["1:Alice", "2:Bob", "3:Charlie"]);
+    expect(calls).toEqual([0]);
+  });
+
+  test("Statement.allMapped maps in order, chunkSize rows at a time", () => {
+    const calls: Array<[number, number]> = [];
+    const ids = db.query("SELECT id FROM users WHERE id >= ? ORDER BY id").allMapped(
+      [1],
+      (rows: any[], offset: number) => {
+        calls.push([offset, rows.length]);
+        return rows.map((r) => r.id * 10);
+      },
+      2
+    );
+
+    expect(ids).toEqual([10, 20, 30]);
+    expect(calls).toEqual([
+      [0, 2],
+      [2, 1],
+    ]);
+  });
+
+  test("Statement.allMapped skips the mapper when there are no rows", () => {
+    let called = false;
+    const result = db.query("SELECT id FROM users WHERE id > 100").allMapped([], (rows: any[]) => {
+      called = true;
+      return rows;
+    });
+
+    expect(result).toEqual([]);
+    expect(called).toBe(false);
+  });
+
+  test("Statement.allMapped rethrows mapper errors and checks the result length", () => {
+    const stmt = db.query("SELECT id FROM users ORDER BY id");
+
+    expect(() =>
+      stmt.allMapped(
+        [],
+        (rows: any[], offset: number) => {
+          if (offset > 0) throw new Error(`bad chunk at ${offset}`);
+          return rows;
+        },
+        1
+      )
+    ).toThrow("bad chunk at 1");
+    expect(() => stmt.allMapped([], (rows: any[]) => rows.slice(1))).toThrow(/returned 2 elements for 3 rows/);
+    expect(() => stmt.allMapped([], () => "nope" as any)).toThrow(/must return an array/);
+    expect(() => stmt.allMapped([], (rows: any[]) => rows, 0)).toThrow(/chunkSize/);
+  });
+
+  test("Statement.allTimed returns rows with a duration", () => {
+    const { rows, durationMicros } = db.query("SELECT name FROM users ORDER BY id").allTimed();
+
+    expect(rows.map((r: any) => r.name)).toEqual(["Alice", "Bob", "Charlie"]);
+    expect(typeof durationMicros).toBe("number");
+    expect(durationMicros).toBeGreaterThanOrEqual(0);
+  });
+
+  test("Statement.getTimed and runTimed report durations", () => {
+    const timedRow = db.query("SELECT name FROM users WHERE id = ?").getTimed([2]);
+    expect(timedRow.row).toEqual({ name: "Bob" });
+    expect(timedRow.durationMicros).toBeGreaterThanOrEqual(0);
+
+    const timedRun = db.query("INSERT INTO users (name) VALUES (?)").runTimed(["Dana"]);
+    expect(timedRun.result.changes).toBe(1);
+    expect(timedRun.result.lastInsertRowid).toBe(4);
+    expect(timedRun.durationMicros).toBeGreaterThanOrEqual(0);
+  });
+
+  test("Statement.allWithTypes reports each cell's storage type", () => {
+    db.exec("CREATE TABLE mixed (n INTEGER, v)");
+    db.run("INSERT INTO mixed VALUES (?, ?)", [1, 2.5]);
+    db.run("INSERT INTO mixed VALUES (?, ?)", ["not a number", Buffer.from([1])]);
+    db.run("INSERT INTO mixed VALUES (?, ?)", [null, "text"]);
+
+    const { rows, columnTypes } = db.query("SELECT n, v FROM mixed ORDER BY rowid").allWithTypes();
+
+    expect(rows.length).toBe(3);
+    expect(rows[1].n).toBe("not a number");
+    expect(columnTypes).toEqual([
+      ["integer", "real"],
+      ["text", "blob"],
+      ["null", "text"],
+    ]);
+  });
 });
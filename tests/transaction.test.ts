@@ -31,6 +31,15 @@ describe("SQLite NAPI - Transaction Support", () => {
     expect(tx).toBeDefined();
   });
 
+  test("concurrent mode reports when BEGIN CONCURRENT is unavailable", () => {
+    expect(() => db.transaction("concurrent")).toThrow(/BEGIN CONCURRENT is not supported/);
+    expect(() => db.transactionFn("concurrent", ["DELETE FROM accounts"])).toThrow(
+      /BEGIN CONCURRENT is not supported/
+    );
+    expect(db.inTransaction()).toBe(false);
+    expect(db.query("SELECT COUNT(*) AS n FROM accounts").get()).toEqual({ n: 2 });
+  });
+
   test("Transaction.commit commits changes", () => {
     const tx = db.transaction(null);
     db.run("INSERT INTO accounts (balance) VALUES (?)", [200]);
@@ -51,6 +60,28 @@ describe("SQLite NAPI - Transaction Support", () => {
     expect((row as any).count).toBe(2);
   });
 
+  test("commit and rollback report a null lastInsertRowid", () => {
+    const tx = db.transaction(null);
+    expect(tx.run("INSERT INTO accounts (balance) VALUES (?)", [300]).lastInsertRowid).toBe(3);
+    expect(tx.commit().lastInsertRowid).toBeNull();
+
+    const tx2 = db.transaction(null);
+    db.run("INSERT INTO accounts (balance) VALUES (?)", [400]);
+    expect(tx2.rollback().lastInsertRowid).toBeNull();
+  });
+
+  test("Transaction.run binds named parameters by name", () => {
+    const tx = db.transaction(null);
+    tx.run("UPDATE accounts SET balance = $balance WHERE id = $id", { $id: 2, $balance: 250 });
+    tx.commit();
+
+    const rows = db.query("SELECT id, balance FROM accounts ORDER BY id").all();
+    expect(rows).toEqual([
+      { id: 1, balance: 100 },
+      { id: 2, balance: 250 },
+    ]);
+  });
+
   test("Database.transactionFn executes multiple statements atomically", () => {
     const result = db.transactionFn(null, [
       "INSERT INTO accounts (balance) VALUES (300)",
@@ -80,6 +111,45 @@ describe("SQLite NAPI - Transaction Support", () => {
     expect((row as any).count).toBe(2);
   });
 
+  test("Database.transactionQuery returns the final statement's rows", () => {
+    const rows = db.transactionQuery("immediate", [
+      "INSERT INTO accounts (balance) VALUES (300)",
+      "SELECT id, balance FROM accounts WHERE id = last_insert_rowid()",
+    ]);
+
+    expect(rows).toEqual([{ id: 3, balance: 300 }]);
+    expect(db.inTransaction()).toBe(false);
+  });
+
+  test("Database.transactionQuery returns RETURNING rows or an empty array", () => {
+    expect(
+      db.transactionQuery(null, ["INSERT INTO accounts (balance) VALUES (5) RETURNING balance"])
+    ).toEqual([{ balance: 5 }]);
+    expect(db.transactionQuery(null, ["DELETE FROM accounts WHERE balance = 5"])).toEqual([]);
+  });
+
+  test("Database.transactionQuery encodes blobs like Statement.all", () => {
+    const hexDb = new Database(":memory:", { blobEncoding: "hex" });
+    expect(hexDb.transactionQuery(null, ["SELECT x'cafe' AS data"])).toEqual([{ data: "cafe" }]);
+
+    const bufDb = new Database(":memory:", { blobEncoding: "buffer" });
+    const [row] = bufDb.transactionQuery(null, ["SELECT x'cafe' AS data"]);
+    expect(Buffer.isBuffer(row.data)).toBe(true);
+    expect([...row.data]).toEqual([0xca, 0xfe]);
+  });
+
+  test("Database.transactionQuery rolls back when any statement fails", () => {
+    expect(() =>
+      db.transactionQuery(null, [
+        "INSERT INTO accounts (balance) VALUES (300)",
+        "SELECT * FROM missing_table",
+      ])
+    ).toThrow(/statement 1/);
+    expect(() => db.transactionQuery(null, [])).toThrow(/at least one statement/);
+
+    expect(db.query("SELECT COUNT(*) AS count FROM accounts").get().count).toBe(2);
+  });
+
   test("Transaction.savepoint creates nested transaction", () => {
     const tx = db.transaction(null);
     db.run("INSERT INTO accounts (balance) VALUES (?)", [200]);
@@ -150,3 +220,270 @@ describe("SQLite NAPI - Transaction Support", () => {
     expect((row as any).name).toBe("test_table");
   });
 });
+
+describe("SQLite NAPI - Transaction State", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)");
+  });
+
+  test("transaction() works after COMMIT is run through exec", () => {
+    db.transaction(null);
+    db.exec("COMMIT");
+
+    const tx = db.transaction(null);
+    db.run("INSERT INTO accounts (balance) VALUES (?)", [10]);
+    tx.commit();
+
+    expect(db.query("SELECT COUNT(*) AS n FROM accounts").get().n).toBe(1);
+  });
+
+  test("transactionFn() works after ROLLBACK is run through exec", () => {
+    db.transaction(null);
+    db.exec("ROLLBACK");
+
+    const result = db.transactionFn(null, ["INSERT INTO accounts (balance) VALUES (1)"]);
+    expect(result.changes).toBe(1);
+  });
+
+  test("transactionFn() explains when a transaction is already open", () => {
+    db.exec("BEGIN");
+
+    expect(() => db.transactionFn(null, ["INSERT INTO accounts (balance) VALUES (1)"])).toThrow(
+      /already open/
+    );
+    db.exec("ROLLBACK");
+  });
+});
+
+describe("SQLite NAPI - Nested transaction() Calls", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)");
+  });
+
+  test("a nested call creates a savepoint", () => {
+    const outer = db.transaction(null);
+    const inner = db.transaction(null);
+
+    expect(outer.savepointName()).toBeNull();
+    expect(inner.savepointName()).toBeString();
+
+    inner.commit();
+    expect(db.inTransaction()).toBe(true);
+    outer.commit();
+    expect(db.inTransaction()).toBe(false);
+  });
+
+  test("rolling back the nested call keeps the outer work", () => {
+    const outer = db.transaction(null);
+    db.run("INSERT INTO items (name) VALUES (?)", ["kept"]);
+
+    const inner = db.transaction(null);
+    db.run("INSERT INTO items (name) VALUES (?)", ["discarded"]);
+    inner.rollback();
+
+    outer.commit();
+    expect(db.query("SELECT name FROM items").all()).toEqual([{ name: "kept" }]);
+  });
+
+  test("savepoint names are unique", () => {
+    const outer = db.transaction(null);
+    const a = db.transaction(null);
+    const b = db.transaction(null);
+
+    expect(a.savepointName()).not.toBe(b.savepointName());
+    b.commit();
+    a.commit();
+    outer.rollback();
+  });
+
+  test("nests inside a transaction started with exec", () => {
+    db.exec("BEGIN");
+    const tx = db.transaction(null);
+    db.run("INSERT INTO items (name) VALUES (?)", ["x"]);
+    tx.commit();
+    db.exec("COMMIT");
+
+    expect(db.query("SELECT COUNT(*) AS n FROM items").get().n).toBe(1);
+  });
+});
+
+describe("SQLite NAPI - Read Transactions", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)");
+    db.run("INSERT INTO accounts (balance) VALUES (?)", [100]);
+    db.run("INSERT INTO accounts (balance) VALUES (?)", [50]);
+  });
+
+  test("returns the callback result", () => {
+    const total = db.readTransaction(() => {
+      const count = db.query("SELECT COUNT(*) AS n FROM accounts").get().n;
+      const sum = db.query("SELECT SUM(balance) AS s FROM accounts").get().s;
+      return { count, sum };
+    });
+
+    expect(total).toEqual({ count: 2, sum: 150 });
+  });
+
+  test("runs inside a transaction that is closed afterwards", () => {
+    let inside = false;
+    db.readTransaction(() => {
+      inside = db.inTransaction();
+    });
+
+    expect(inside).toBe(true);
+    expect(db.inTransaction()).toBe(false);
+  });
+
+  test("rejects writes inside the callback", () => {
+    expect(() =>
+      db.readTransaction(() => db.run("UPDATE accounts SET balance = 0"))
+    ).toThrow();
+
+    expect(db.query("SELECT SUM(balance) AS s FROM accounts").get().s).toBe(150);
+    db.run("UPDATE accounts SET balance = 0");
+    expect(db.query("SELECT SUM(balance) AS s FROM accounts").get().s).toBe(0);
+  });
+
+  test("rethrows errors from the callback and cleans up", () => {
+    expect(() =>
+      db.readTransaction(() => {
+        throw new Error("boom");
+      })
+    ).toThrow("boom");
+
+    expect(db.inTransaction()).toBe(false);
+  });
+
+  test("cannot be started inside another transaction", () => {
+    const tx = db.transaction(null);
+    expect(() => db.readTransaction(() => 1)).toThrow();
+    tx.rollback();
+  });
+});
+
+describe("SQLite NAPI - Deferred Foreign Keys", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE authors (id INTEGER PRIMARY KEY)");
+    db.exec("CREATE TABLE books (author_id INTEGER REFERENCES authors(id))");
+    db.exec("INSERT INTO authors VALUES (1); INSERT INTO books VALUES (1)");
+  });
+
+  test("foreign keys are checked immediately by default", () => {
+    const tx = db.transaction(null);
+    expect(() => tx.run("UPDATE authors SET id = 3 WHERE id = 1")).toThrow();
+    tx.rollback();
+  });
+
+  test("transaction.deferForeignKeys checks at commit time", () => {
+    const tx = db.transaction(null);
+    tx.deferForeignKeys();
+    tx.run("UPDATE authors SET id = 3 WHERE id = 1");
+    tx.run("UPDATE books SET author_id = 3");
+    tx.commit();
+
+    expect(db.query("SELECT author_id FROM books").get()).toEqual({ author_id: 3 });
+    expect(db.pragma("defer_foreign_keys")).toBe(0);
+  });
+
+  test("commit fails if violations remain", () => {
+    const tx = db.transaction(null);
+    tx.deferForeignKeys();
+    tx.run("DELETE FROM authors");
+
+    expect(() => tx.commit()).toThrow();
+    tx.rollback();
+    expect(db.query("SELECT COUNT(*) AS n FROM authors").get()).toEqual({ n: 1 });
+  });
+
+  test("database.deferForeignKeys applies to the open transaction", () => {
+    db.exec("BEGIN");
+    db.deferForeignKeys(true);
+    db.run("UPDATE authors SET id = 3 WHERE id = 1");
+    db.run("UPDATE books SET author_id = 3");
+    db.exec("COMMIT");
+
+    expect(db.query("SELECT id FROM authors").get()).toEqual({ id: 3 });
+  });
+});
+
+describe("SQLite NAPI - Batch", () => {
+  let db: Database;
+
+  beforeEach(() => {
+    db = new Database(":memory:");
+    db.exec("CREATE TABLE counters (name TEXT PRIMARY KEY, value INTEGER)");
+    db.run("INSERT INTO counters (name, value) VALUES (?, ?)", ["hits", 1]);
+  });
+
+  test("runs a read-modify-write sequence and returns the callback result", () => {
+    const next = db.batch((batch) => {
+      const { value } = batch.get("SELECT value FROM counters WHERE name = ?", ["hits"]);
+      batch.run("UPDATE counters SET value = ? WHERE name = ?", [value + 1, "hits"]);
+      return batch.get("SELECT value FROM counters WHERE name = :name", { name: "hits" }).value;
+    });
+
+    expect(next).toBe(2);
+    expect(db.query("SELECT value FROM counters").get()).toEqual({ value: 2 });
+  });
+
+  test("supports exec, all and missing rows", () => {
+    db.batch((batch) => {
+      expect(batch.exec("INSERT INTO counters VALUES ('a', 1); INSERT INTO counters VALUES ('b', 2)").changes).toBe(1);
+      expect(batch.all("SELECT name FROM counters ORDER BY name")).toEqual([
+        { name: "a" },
+        { name: "b" },
+        { name: "hits" },
+      ]);
+      expect(batch.get("SELECT * FROM counters WHERE name = ?", ["missing"])).toBeNull();
+    });
+  });
+
+  test("rejects the wrong number of positional parameters", () => {
+    db.batch((batch) => {
+      expect(() => batch.get("SELECT * FROM counters WHERE name = ?", [])).toThrow();
+    });
+  });
+
+  test("rethrows errors from the callback and releases the connection", () => {
+    expect(() =>
+      db.batch(() => {
+        throw new Error("boom");
+      })
+    ).toThrow("boom");
+
+    expect(db.query("SELECT COUNT(*) AS n FROM counters").get().n).toBe(1);
+  });
+
+  test("the handle stops working once the callback returns", () => {
+    let saved: any;
+    db.batch((batch) => {
+      saved = batch;
+    });
+
+    expect(() => saved.get("SELECT 1")).toThrow(/batch has ended/);
+  });
+
+  test("throws instead of hanging when the Database is used inside the callback", () => {
+    const stmt = db.query("SELECT value FROM counters");
+
+    db.batch(() => {
+      expect(() => db.run("UPDATE counters SET value = 0")).toThrow(/cannot use the Database inside batch/);
+      expect(() => stmt.get()).toThrow(/cannot use the Database inside batch/);
+      expect(() => db.batch(() => 1)).toThrow(/cannot use the Database inside batch/);
+    });
+
+    expect(stmt.get()).toEqual({ value: 1 });
+  });
+});
@@ -139,4 +139,40 @@ describe("SQLite NAPI - Data Types", () => {
     expect(values).toBeDefined();
     expect(values.length).toBe(0);
   });
+
+  describe("invalid UTF-8 text", () => {
+    beforeEach(() => {
+      // 0xff is never valid UTF-8; the cast stores the raw bytes as TEXT
+      db.run("INSERT INTO types_test (text_val) VALUES (CAST(x'41ff42' AS TEXT))");
+    });
+
+    test("replaces invalid bytes by default", () => {
+      const row = db.query("SELECT text_val FROM types_test").get();
+      expect(row.text_val).toBe("A\uFFFDB");
+    });
+
+    test("throws in error mode", () => {
+      db.setTextErrorMode("error");
+      expect(() => db.query("SELECT text_val FROM types_test").get()).toThrow();
+    });
+
+    test("returns the raw bytes in bytes mode", () => {
+      const stmt = db.query("SELECT text_val FROM types_test");
+      db.setTextErrorMode("bytes");
+
+      const row = stmt.get();
+      expect(Buffer.isBuffer(row.text_val)).toBe(true);
+      expect([...row.text_val]).toEqual([0x41, 0xff, 0x42]);
+    });
+
+    test("leaves valid text as strings in bytes mode", () => {
+      db.setTextErrorMode("bytes");
+      const row = db.query("SELECT 'héllo' AS text_val").get();
+      expect(row.text_val).toBe("héllo");
+    });
+
+    test("rejects unknown modes", () => {
+      expect(() => db.setTextErrorMode("strict" as any)).toThrow();
+    });
+  });
 });